@@ -0,0 +1,702 @@
+//! Solution to the puzzle of day 1, _Report Repair_.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::iter;
+use std::mem;
+use std::ops::Range;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+/// The sum of the entries to find in the puzzle.
+pub const TARGET_SUM: i64 = 2020;
+
+/// An error found while parsing the expense report.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// An entry isn't a number.
+    InvalidEntry(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidEntry(loc) => write!(f, "invalid entry `{}`", loc.token),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidEntry(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the expense report, one entry per line.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<Vec<i32>, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Vec<i32>, ParseError> {
+    source
+        .content()
+        .lines()
+        .map(|x| {
+            x.parse()
+                .map_err(|_| ParseError::InvalidEntry(source.locate(x)))
+        })
+        .collect()
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Vec<i32>, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The most pairs of distinct values whose sums are hashed by [`sums`].
+const MAX_PAIR_SUMS: usize = 1 << 21;
+
+/// An iterator over every combination of `k` entries that sum to a target,
+/// created with [`sums`].
+///
+/// Entries are sorted once and grouped by value. The search is a meet in
+/// the middle: the sums of every pair of distinct values are hashed, and
+/// the first `k - 2` values of a combination are chosen in increasing
+/// order, cutting the branches whose smallest or largest possible sum
+/// misses the target, before looking up the pairs completing them. A value
+/// is used at most as many times as it appears in the report, and each
+/// combination of values is then expanded into every choice of the entries
+/// holding them, so the entries of a combination are always distinct.
+/// Searches that can't succeed because of the residues of the entries, like
+/// an odd sum of even entries, stop at once.
+pub struct Sums<'a> {
+    values: &'a [i32],
+    sorted: Vec<(i64, usize)>,
+    /// The sums of the first `i` sorted entries.
+    cumulative: Vec<i64>,
+    /// The sorted positions of the entries of each distinct value.
+    runs: Vec<Range<usize>>,
+    /// The distinct value of the entry at each sorted position.
+    run_of: Vec<usize>,
+    /// The pairs of distinct values by their sum, sorted, unless there are
+    /// too many of them.
+    pair_sums: Option<HashMap<i64, Vec<(usize, usize)>>>,
+    target: i64,
+    k: usize,
+    /// Sorted positions of the first `k - 2` entries of the combination,
+    /// the first ones of their values.
+    prefix: Vec<usize>,
+    /// The combinations of values left to yield, each value with the
+    /// number of times it's used, last first.
+    pending: Vec<Vec<(usize, usize)>>,
+    /// The choices of entries for the combination of values being yielded.
+    expansion: Option<Expansion>,
+    started: bool,
+    done: bool,
+}
+
+/// The combinations of sorted positions taking a number of entries from
+/// each of a set of runs.
+struct Expansion {
+    /// Each run, with the positions taken from it.
+    groups: Vec<(Range<usize>, Vec<usize>)>,
+    fresh: bool,
+}
+
+impl Expansion {
+    fn new(groups: impl Iterator<Item = (Range<usize>, usize)>) -> Self {
+        Expansion {
+            groups: groups
+                .map(|(run, m)| (run.clone(), (run.start..run.start + m).collect()))
+                .collect(),
+            fresh: true,
+        }
+    }
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        if !self.fresh {
+            let advanced = self.groups.iter_mut().rev().any(|(run, chosen)| {
+                let m = chosen.len();
+                match (0..m).rev().find(|&i| chosen[i] < run.end - (m - i)) {
+                    Some(i) => {
+                        chosen[i] += 1;
+                        for j in i + 1..m {
+                            chosen[j] = chosen[j - 1] + 1;
+                        }
+                        true
+                    }
+                    None => {
+                        for (j, x) in chosen.iter_mut().enumerate() {
+                            *x = run.start + j;
+                        }
+                        false
+                    }
+                }
+            });
+            if !advanced {
+                return None;
+            }
+        }
+        self.fresh = false;
+        Some(self.groups.iter().flat_map(|(_, x)| x).copied().collect())
+    }
+}
+
+/// Returns an iterator over every combination of `k` entries that sum to
+/// `target`, each with the indices and values of its entries in the order
+/// they appear in the report.
+///
+/// Combinations are distinct by the indices of their entries, not by their
+/// values: entries with repeated values are told apart by their indices, so
+/// five entries of 1010 give ten pairs that sum to 2020.
+///
+/// With `d` distinct values, finding the first combination takes
+/// `O(d^2 + d^(k-2))` time in the worst case, or `O(d^(k-1))` if there are
+/// too many pairs of values to hash, but the bounds on the sums usually cut
+/// most of the search.
+pub fn sums(values: &[i32], target: i64, k: usize) -> Sums<'_> {
+    let sorted = sort_entries(values);
+    let cumulative = iter::once(0)
+        .chain(sorted.iter().scan(0, |sum, &(x, _)| {
+            *sum += x;
+            Some(*sum)
+        }))
+        .collect();
+    let mut runs: Vec<Range<usize>> = Vec::new();
+    let mut run_of = Vec::with_capacity(sorted.len());
+    for (p, &(x, _)) in sorted.iter().enumerate() {
+        match runs.last_mut() {
+            Some(run) if sorted[run.start].0 == x => run.end += 1,
+            _ => runs.push(p..p + 1),
+        }
+        run_of.push(runs.len() - 1);
+    }
+    let d = runs.len();
+    let pair_sums = (k > 2 && d * (d + 1) / 2 <= MAX_PAIR_SUMS).then(|| {
+        let mut pair_sums = HashMap::new();
+        for (d1, a) in runs.iter().enumerate() {
+            let first = if a.len() > 1 { d1 } else { d1 + 1 };
+            for (d2, b) in runs.iter().enumerate().skip(first) {
+                let sum = sorted[a.start].0 + sorted[b.start].0;
+                pair_sums.entry(sum).or_insert_with(Vec::new).push((d1, d2));
+            }
+        }
+        pair_sums
+    });
+    Sums {
+        values,
+        done: unreachable(&sorted, target, k),
+        sorted,
+        cumulative,
+        runs,
+        run_of,
+        pair_sums,
+        target,
+        k,
+        prefix: Vec::new(),
+        pending: Vec::new(),
+        expansion: None,
+        started: false,
+    }
+}
+
+impl<'a> Sums<'a> {
+    /// Returns the sum of the sorted entries in `range`.
+    fn total(&self, range: Range<usize>) -> i64 {
+        self.cumulative[range.end] - self.cumulative[range.start]
+    }
+
+    /// Returns the value of the distinct value `d`.
+    fn value(&self, d: usize) -> i64 {
+        self.sorted[self.runs[d].start].0
+    }
+
+    /// Moves to the next prefix that may be completed, queueing the
+    /// combinations of values it's part of, and returning false if there
+    /// is none left.
+    fn advance(&mut self) -> bool {
+        if self.k < 2 {
+            if self.started {
+                return false;
+            }
+            self.started = true;
+            if self.k == 0 && self.target == 0 {
+                self.pending.push(Vec::new());
+            }
+            if self.k == 1 {
+                let d = self
+                    .runs
+                    .partition_point(|run| self.sorted[run.start].0 < self.target);
+                if d < self.runs.len() && self.value(d) == self.target {
+                    self.pending.push(vec![(d, 1)]);
+                }
+            }
+            return true;
+        }
+
+        let n = self.sorted.len();
+        let depth = self.k - 2;
+        let mut pos = if !self.started {
+            self.started = true;
+            0
+        } else {
+            match self.prefix.pop() {
+                Some(p) => self.runs[self.run_of[p]].end,
+                None => return false,
+            }
+        };
+
+        loop {
+            let prefix_sum = self.prefix.iter().map(|&p| self.sorted[p].0).sum::<i64>();
+            if self.prefix.len() == depth {
+                self.queue_pairs(pos, self.target - prefix_sum);
+                return true;
+            }
+            let remaining = self.k - self.prefix.len();
+            if pos + remaining > n || prefix_sum + self.total(pos..pos + remaining) > self.target {
+                match self.prefix.pop() {
+                    Some(p) => pos = self.runs[self.run_of[p]].end,
+                    None => return false,
+                }
+            } else if prefix_sum + self.sorted[pos].0 + self.total(n + 1 - remaining..n)
+                < self.target
+            {
+                pos = self.runs[self.run_of[pos]].end;
+            } else {
+                self.prefix.push(pos);
+                pos += 1;
+            }
+        }
+    }
+
+    /// Queues the combinations of the prefix with the pairs of entries
+    /// from the sorted position `pos` on that sum to `rest`.
+    fn queue_pairs(&mut self, pos: usize, rest: i64) {
+        if pos >= self.sorted.len() {
+            return;
+        }
+        // The entries of the first value before `pos` are in the prefix.
+        let first = self.run_of[pos];
+        let available = self.runs[first].end - pos;
+        let usable = |&(d1, d2): &(usize, usize)| d1 != first || available > (d2 == first) as usize;
+        let mut pairs = Vec::new();
+        match &self.pair_sums {
+            Some(pair_sums) => {
+                let candidates = pair_sums.get(&rest).map_or(&[][..], |x| &x[..]);
+                let start = candidates.partition_point(|&(d1, _)| d1 < first);
+                pairs.extend(candidates[start..].iter().filter(|x| usable(x)));
+            }
+            None => {
+                let (mut lo, mut hi) = (first, self.runs.len() - 1);
+                while lo <= hi {
+                    let sum = self.value(lo) + self.value(hi);
+                    if sum < rest {
+                        lo += 1;
+                    } else if sum > rest {
+                        match hi.checked_sub(1) {
+                            Some(x) => hi = x,
+                            None => break,
+                        }
+                    } else {
+                        if (lo < hi || self.runs[lo].len() > 1) && usable(&(lo, hi)) {
+                            pairs.push((lo, hi));
+                        }
+                        lo += 1;
+                        hi = hi.saturating_sub(1);
+                    }
+                }
+            }
+        }
+
+        for (d1, d2) in pairs.into_iter().rev() {
+            let mut values = self
+                .prefix
+                .iter()
+                .map(|&p| self.run_of[p])
+                .chain(vec![d1, d2])
+                .map(|d| (d, 1))
+                .collect::<Vec<_>>();
+            values.dedup_by(|(d, m), (prev, n)| {
+                let same = d == prev;
+                if same {
+                    *n += *m;
+                }
+                same
+            });
+            self.pending.push(values);
+        }
+    }
+
+    /// Returns the entries at the sorted positions, in report order.
+    fn entries(&self, positions: impl Iterator<Item = usize>) -> Vec<(usize, i32)> {
+        let mut entries = positions
+            .map(|p| {
+                let i = self.sorted[p].1;
+                (i, self.values[i])
+            })
+            .collect::<Vec<_>>();
+        entries.sort_unstable();
+        entries
+    }
+
+    /// Counts the combinations of entries left, without building them, up
+    /// to `u64::MAX`.
+    fn count_combinations(mut self) -> u64 {
+        let mut count = 0u64;
+        while !self.done {
+            for values in mem::take(&mut self.pending) {
+                let ways = values.iter().fold(1u64, |ways, &(d, m)| {
+                    ways.saturating_mul(binomial(self.runs[d].len(), m))
+                });
+                count = count.saturating_add(ways);
+            }
+            self.done = !self.advance();
+        }
+        count
+    }
+}
+
+/// Returns the number of ways of choosing `m` out of `n` items, up to
+/// `u64::MAX`.
+fn binomial(n: usize, m: usize) -> u64 {
+    let mut ways = 1u128;
+    for i in 0..m as u128 {
+        ways = match ways.checked_mul(n as u128 - i) {
+            Some(x) => x / (i + 1),
+            None => return u64::MAX,
+        };
+    }
+    ways.min(u128::from(u64::MAX)) as u64
+}
+
+impl<'a> Iterator for Sums<'a> {
+    type Item = Vec<(usize, i32)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(positions) = self.expansion.as_mut().and_then(Expansion::next) {
+                return Some(self.entries(positions.into_iter()));
+            }
+            self.expansion = None;
+            if let Some(values) = self.pending.pop() {
+                let groups = values.iter().map(|&(d, m)| (self.runs[d].clone(), m));
+                self.expansion = Some(Expansion::new(groups));
+            } else if self.done || !self.advance() {
+                self.done = true;
+                return None;
+            }
+        }
+    }
+}
+
+/// Finds the first combination of `k` entries that sum to `target`.
+pub fn find_sum(values: &[i32], target: i64, k: usize) -> Option<Vec<(usize, i32)>> {
+    sums(values, target, k).next()
+}
+
+/// Counts the combinations of `k` entries that sum to `target`, up to
+/// `u64::MAX`, without building them. This takes as long as [`sums`] takes
+/// to go through every combination of values.
+pub fn count_sums(values: &[i32], target: i64, k: usize) -> u64 {
+    sums(values, target, k).count_combinations()
+}
+
+/// Checks if no `k` entries can sum to `target` because of their residues.
+/// All the entries are congruent to the first one modulo the greatest
+/// common divisor of their differences, and so are their sums of `k`.
+fn unreachable(sorted: &[(i64, usize)], target: i64, k: usize) -> bool {
+    fn gcd(a: i128, b: i128) -> i128 {
+        if b == 0 {
+            a.abs()
+        } else {
+            gcd(b, a % b)
+        }
+    }
+
+    let first = match sorted.first() {
+        Some(&(x, _)) => i128::from(x),
+        None => return false,
+    };
+    let g = sorted
+        .iter()
+        .fold(0, |g, &(x, _)| gcd(g, i128::from(x) - first));
+    g > 1 && (i128::from(target) - k as i128 * first).rem_euclid(g) != 0
+}
+
+fn sort_entries(values: &[i32]) -> Vec<(i64, usize)> {
+    let mut sorted = values
+        .iter()
+        .map(|&x| i64::from(x))
+        .enumerate()
+        .map(|(i, x)| (x, i))
+        .collect::<Vec<_>>();
+    sorted.sort_unstable();
+    sorted
+}
+
+/// Returns the product of the values of `entries`, or `None` if it
+/// overflows an `i128`.
+pub fn product(entries: &[(usize, i32)]) -> Option<i128> {
+    entries
+        .iter()
+        .try_fold(1i128, |acc, &(_, x)| acc.checked_mul(i128::from(x)))
+}
+
+/// Returns the product of the two entries that sum to 2020.
+pub fn solve_part1(values: &[i32]) -> Option<i128> {
+    find_sum(values, TARGET_SUM, 2).and_then(|entries| product(&entries))
+}
+
+/// Returns the product of the three entries that sum to 2020.
+pub fn solve_part2(values: &[i32]) -> Option<i128> {
+    find_sum(values, TARGET_SUM, 3).and_then(|entries| product(&entries))
+}
+
+/// The puzzle of day 1.
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<i32>;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, values: &Self::Input) -> Answer {
+        solve_part1(values).into()
+    }
+
+    fn part2(&self, values: &Self::Input) -> Answer {
+        solve_part2(values).into()
+    }
+}
+
+fn pairs(values: &[i32]) -> Vec<i32> {
+    let mut products = Vec::new();
+    for (i, a) in values.iter().enumerate() {
+        for b in &values[i + 1..] {
+            if i64::from(a + b) == TARGET_SUM {
+                products.push(a * b);
+            }
+        }
+    }
+    products
+}
+
+fn triples(values: &[i32]) -> Vec<i32> {
+    let mut products = Vec::new();
+    for (i, a) in values.iter().enumerate() {
+        for (j, b) in values.iter().enumerate().skip(i + 1) {
+            for c in &values[j + 1..] {
+                if i64::from(a + b + c) == TARGET_SUM {
+                    products.push(a * b * c);
+                }
+            }
+        }
+    }
+    products
+}
+
+impl Oracle for Day01 {
+    /// Generates `size` entries, with exactly one pair and one triple of
+    /// entries that sum to 2020.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let target = TARGET_SUM as i32;
+        loop {
+            let a = rng.range(1, target as usize) as i32;
+            let b = rng.range(1, target as usize - 1) as i32;
+            let c = rng.range(1, (target - b) as usize) as i32;
+            let mut values = vec![a, target - a, b, c, target - b - c];
+            while values.len() < size.max(values.len()) {
+                values.push(rng.range(1, target as usize) as i32);
+            }
+            rng.shuffle(&mut values);
+            if pairs(&values).len() == 1 && triples(&values).len() == 1 {
+                return values.iter().map(|x| format!("{}\n", x)).collect();
+            }
+        }
+    }
+
+    fn oracle(&self, values: &Self::Input, part: usize) -> Answer {
+        let products = if part == 1 {
+            pairs(values)
+        } else {
+            triples(values)
+        };
+        products.first().copied().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_example_input() {
+        let values = parse_input("example.txt").unwrap();
+        assert_eq!(solve_part1(&values), Some(514579));
+        assert_eq!(solve_part2(&values), Some(241861950));
+    }
+
+    #[test]
+    fn test_find_sum() {
+        let values = [1010, 7, -3, 1010, 5, -3];
+        assert_eq!(find_sum(&values, 2020, 2), Some(vec![(0, 1010), (3, 1010)]));
+        assert_eq!(find_sum(&values, -6, 2), Some(vec![(2, -3), (5, -3)]));
+        assert_eq!(find_sum(&values, 4, 2), Some(vec![(1, 7), (2, -3)]));
+        assert_eq!(
+            find_sum(&values, 6, 4),
+            Some(vec![(1, 7), (2, -3), (4, 5), (5, -3)])
+        );
+        assert_eq!(find_sum(&values, 2026, 6).map(|x| x.len()), Some(6));
+        assert_eq!(find_sum(&values, 1010, 2), None);
+        assert_eq!(find_sum(&values, 0, 7), None);
+        assert_eq!(find_sum(&values, 0, 0), Some(vec![]));
+    }
+
+    #[test]
+    fn test_count_sums() {
+        let values = [1010; 5];
+        assert_eq!(sums(&values, 2020, 2).count(), 10);
+        assert_eq!(count_sums(&values, 2020, 2), 10);
+        assert_eq!(count_sums(&values, 3030, 3), 10);
+        assert_eq!(count_sums(&values, 2021, 2), 0);
+    }
+
+    #[test]
+    fn test_product() {
+        assert_eq!(product(&[(0, -3), (1, 7)]), Some(-21));
+        assert_eq!(product(&[]), Some(1));
+        let max = [(0, i32::MAX); 5];
+        assert_eq!(product(&max[..4]), Some(i128::from(i32::MAX).pow(4)));
+        assert_eq!(product(&max), None);
+    }
+
+    #[test]
+    fn test_sums_random() {
+        let mut rng = Rng::new(1);
+        for _ in 0..500 {
+            let len = rng.range(0, 9);
+            let values = (0..len)
+                .map(|_| rng.range(0, 11) as i32 - 5)
+                .collect::<Vec<_>>();
+            let target = rng.range(0, 21) as i64 - 10;
+            let k = rng.range(0, 6);
+            let expected = (0..1usize << len)
+                .filter(|&mask| {
+                    mask.count_ones() as usize == k
+                        && (0..len)
+                            .filter(|i| mask >> i & 1 == 1)
+                            .map(|i| i64::from(values[i]))
+                            .sum::<i64>()
+                            == target
+                })
+                .map(|mask| {
+                    (0..len)
+                        .filter(|i| mask >> i & 1 == 1)
+                        .map(|i| (i, values[i]))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<HashSet<_>>();
+            let found = sums(&values, target, k).collect::<Vec<_>>();
+            assert_eq!(found.len(), expected.len(), "{:?} {} {}", values, target, k);
+            assert_eq!(found.into_iter().collect::<HashSet<_>>(), expected);
+            // Without hashing the sums of the pairs, as with many values.
+            let mut unhashed = sums(&values, target, k);
+            unhashed.pair_sums = None;
+            assert_eq!(unhashed.collect::<HashSet<_>>(), expected);
+            assert_eq!(count_sums(&values, target, k), expected.len() as u64);
+        }
+    }
+
+    #[test]
+    fn test_find_sum_large() {
+        let mut rng = Rng::new(0);
+        let values = (0..100_000)
+            .map(|_| rng.range(0, 2_000_000) as i32 - 1_000_000)
+            .collect::<Vec<_>>();
+        for k in 4..=6 {
+            let entries = find_sum(&values, 12345, k).unwrap();
+            let sum = entries.iter().map(|&(_, x)| i64::from(x)).sum::<i64>();
+            assert_eq!((entries.len(), sum), (k, 12345));
+        }
+    }
+
+    #[test]
+    fn test_find_sum_none() {
+        // Even entries can't sum to an odd target.
+        let mut rng = Rng::new(2);
+        let values = (0..100_000)
+            .map(|_| rng.range(0, 1_000_000) as i32 * 2 - 1_000_000)
+            .collect::<Vec<_>>();
+        for k in 2..=6 {
+            assert_eq!(find_sum(&values, 12345, k), None);
+            assert_eq!(count_sums(&values, 12345, k), 0);
+        }
+        // Nor can entries that are all 1 modulo 3 sum to a multiple of 3
+        // with 4 of them.
+        let values = values.iter().map(|x| x / 2 * 3 + 1).collect::<Vec<_>>();
+        assert_eq!(find_sum(&values, 3000, 4), None);
+        assert_eq!(find_sum(&values, 3004, 4).map(|x| x.len()), Some(4));
+        // Nor can many entries, a multiple of 10 but one, sum to 5 modulo
+        // 10, even if the greatest common divisor of their differences is 1.
+        let mut values = (0..100_000)
+            .map(|_| rng.range(0, 100) as i32 * 10)
+            .collect::<Vec<_>>();
+        values[50_000] = 3;
+        for k in 2..=6 {
+            let target = k as i64 * 300 + 5;
+            assert!(!unreachable(&sort_entries(&values), target, k));
+            assert_eq!(find_sum(&values, target, k), None);
+            assert_eq!(count_sums(&values, target, k), 0);
+        }
+        assert_eq!(find_sum(&values, 1213, 3).map(|x| x.len()), Some(3));
+        // Nor any entries beyond the bounds of the sums.
+        assert_eq!(find_sum(&values, 10_000_000, 5), None);
+        assert_eq!(find_sum(&values, -10_000_000, 5), None);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day01, 12);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day01, 1, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let values = Day01.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(Day01.part1(&values), Answer::Number(514579));
+        assert_eq!(Day01.part2(&values), Answer::Number(241861950));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "1721\n97x\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid entry `97x`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (2, 1));
+    }
+}
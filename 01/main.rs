@@ -1,77 +1,56 @@
-use std::collections::HashSet;
-use std::env;
-use std::fs;
-use std::path::Path;
-use std::process;
-
-const TARGET_SUM: i32 = 2020;
-
-fn parse_input(file_name: impl AsRef<Path>) -> Vec<i32> {
-    fs::read_to_string(file_name)
-        .unwrap()
-        .lines()
-        .map(|x| x.parse::<i32>().unwrap())
-        .collect()
-}
-
-fn solve_part1(values: &[i32]) -> Option<i32> {
-    let mut set = HashSet::new();
-
-    for n in values {
-        let diff = TARGET_SUM - n;
-        if set.contains(&diff) {
-            return Some(n * diff);
-        }
-        set.insert(n);
+use common::{or_exit, parse_arg, parse_args, take_flag, Answer, Printer, Record};
+use day01::{count_sums, find_sum, parse_input, product, sums, TARGET_SUM};
+
+/// Describes the entries of a combination, with their line numbers and
+/// product.
+fn describe(entries: &[(usize, i32)]) -> String {
+    let terms = entries
+        .iter()
+        .map(|(i, x)| format!("{} (line {})", x, i + 1))
+        .collect::<Vec<_>>()
+        .join(" + ");
+    match product(entries) {
+        Some(product) => format!("{}, product {}", terms, product),
+        None => format!("{}, product overflows", terms),
     }
-
-    None
 }
 
-fn solve_part2(values: &[i32]) -> Option<i32> {
-    let mut set = HashSet::new();
-
-    for (a_i, a) in values[..values.len() - 2].iter().enumerate() {
-        for b in values[a_i + 1..].iter() {
-            let c = TARGET_SUM - a - b;
-            if set.contains(&c) {
-                return Some(a * b * c);
+fn main() {
+    let (mut args, format) = parse_args(&["FILE", "[TARGET]", "[K]", "[--count]", "[--all]"]);
+    let count = take_flag(&mut args, "--count");
+    let all = take_flag(&mut args, "--all");
+    let values = or_exit(parse_input(&args[0]));
+    let target = args.get(1).map_or(TARGET_SUM, |x| parse_arg("TARGET", x));
+    let k = args.get(2).map(|x| parse_arg("K", x));
+
+    let mut printer = Printer::new(1, format);
+
+    if all {
+        let ks = k.map_or(vec![2, 3], |k| vec![k]);
+        for (part, &k) in (1..).zip(&ks) {
+            let mut combinations = sums(&values, target, k);
+            loop {
+                let record = Record::solve(1, part, || combinations.next().map(|x| describe(&x)));
+                if record.answer == Answer::Unsolved {
+                    break;
+                }
+                printer.print(&record);
             }
         }
-        set.insert(a);
+        return;
     }
 
-    None
-}
-
-fn main() {
-    if env::args().count() != 2 {
-        eprintln!("USAGE: {} FILE", env::args().next().unwrap());
-        process::exit(1);
-    }
-
-    let values = parse_input(env::args().nth(1).unwrap());
-    let part1 = solve_part1(&values);
-    let part2 = solve_part2(&values);
-    println!("Result (Part 1):{:?}", part1);
-    println!("Result (Part 2):{:?}", part2);
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_example_input() {
-        let values = parse_input("example.txt");
-        assert_eq!(solve_part1(&values), Some(514579));
-        assert_eq!(solve_part2(&values), Some(241861950));
-    }
-
-    #[test]
-    fn test_puzzle_input() {
-        let values = parse_input("input.txt");
-        assert_eq!(solve_part1(&values), Some(918339));
-        assert_eq!(solve_part2(&values), Some(23869440));
+    match k {
+        Some(k) if count => printer.solve(1, || count_sums(&values, target, k)),
+        Some(k) => printer.solve(1, || find_sum(&values, target, k).map(|x| describe(&x))),
+        None if count => {
+            printer.solve(1, || count_sums(&values, target, 2));
+            printer.solve(2, || count_sums(&values, target, 3));
+        }
+        None => {
+            let product = |entries: Vec<(usize, i32)>| product(&entries);
+            printer.solve(1, || find_sum(&values, target, 2).and_then(product));
+            printer.solve(2, || find_sum(&values, target, 3).and_then(product));
+        }
     }
 }
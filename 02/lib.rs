@@ -0,0 +1,293 @@
+//! Solution to the puzzle of day 2, _Password Philosophy_.
+
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+mod policy;
+mod report;
+
+pub use policy::{
+    count_valid_passwords, parse_policies, parse_policies_input, parse_policies_source, And,
+    CharClass, Forbidden, LetterCount, LetterPosition, MinDistinct, Not, Or, Policy, PolicyError,
+    Require, Rule,
+};
+pub use report::{
+    default_policies, policy_counts, policy_counts_to_string, violations, violations_to_csv,
+    violations_to_json, PolicyCount, Violation,
+};
+
+/// The policy in effect when a password was set.
+///
+/// Counts and positions are in characters, not bytes, so multi-byte
+/// letters and passwords are handled like ASCII ones.
+pub struct PasswordPolicy {
+    min: usize,
+    max: usize,
+    letter: char,
+}
+
+impl PasswordPolicy {
+    /// Creates a policy for `letter` with the given pair of numbers.
+    pub fn new(min: usize, max: usize, letter: char) -> Self {
+        PasswordPolicy { min, max, letter }
+    }
+
+    /// Returns how many times `letter` appears in `password`.
+    pub fn letter_count(&self, password: &str) -> usize {
+        password.chars().filter(|&c| c == self.letter).count()
+    }
+
+    /// Checks if `letter` is at the (1-indexed) `position` of `password`.
+    pub fn letter_at(&self, password: &str, position: usize) -> bool {
+        position
+            .checked_sub(1)
+            .and_then(|i| password.chars().nth(i))
+            == Some(self.letter)
+    }
+
+    /// Checks if `letter` appears between `min` and `max` times in
+    /// `password`.
+    pub fn validate_password_part1(&self, password: &str) -> bool {
+        let letter_count = self.letter_count(password);
+        letter_count >= self.min && letter_count <= self.max
+    }
+
+    /// Checks if `letter` appears in exactly one of the (1-indexed)
+    /// positions `min` and `max` of `password`.
+    pub fn validate_password_part2(&self, password: &str) -> bool {
+        self.letter_at(password, self.min) != self.letter_at(password, self.max)
+    }
+}
+
+impl fmt::Display for PasswordPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{} {}", self.min, self.max, self.letter)
+    }
+}
+
+/// An error found while parsing the password database.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// The policy doesn't start with a range like `1-3`.
+    InvalidRange(Location),
+    /// A bound of the range isn't a number.
+    InvalidNumber(Location),
+    /// The letter isn't a single character followed by `:`.
+    InvalidLetter(Location),
+    /// The line ends before the letter.
+    MissingLetter(Location),
+    /// The line ends before the password.
+    MissingPassword(Location),
+    /// The password is followed by more tokens.
+    TrailingToken(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidRange(loc) => write!(f, "invalid range `{}`", loc.token),
+            ParseError::InvalidNumber(loc) => write!(f, "invalid number `{}`", loc.token),
+            ParseError::InvalidLetter(loc) => write!(f, "invalid letter `{}`", loc.token),
+            ParseError::MissingLetter(_) => write!(f, "missing letter"),
+            ParseError::MissingPassword(_) => write!(f, "missing password"),
+            ParseError::TrailingToken(loc) => write!(f, "unexpected token `{}`", loc.token),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidRange(loc)
+            | ParseError::InvalidNumber(loc)
+            | ParseError::InvalidLetter(loc)
+            | ParseError::MissingLetter(loc)
+            | ParseError::MissingPassword(loc)
+            | ParseError::TrailingToken(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+fn parse_line(source: &Source, line: &str) -> Result<(PasswordPolicy, String), ParseError> {
+    let parse_number = |x: &str| {
+        x.parse()
+            .map_err(|_| ParseError::InvalidNumber(source.locate(x)))
+    };
+
+    let mut tokens = line.split_whitespace();
+    let range = tokens.next().unwrap_or(line);
+    let (min, max) = range
+        .split_once('-')
+        .ok_or_else(|| ParseError::InvalidRange(source.locate(range)))?;
+    let (min, max) = (parse_number(min)?, parse_number(max)?);
+
+    let letter = tokens
+        .next()
+        .ok_or_else(|| ParseError::MissingLetter(source.locate_end(line)))?;
+    let mut chars = letter.chars();
+    let letter = match (chars.next(), chars.as_str()) {
+        (Some(ch), ":") => ch,
+        _ => return Err(ParseError::InvalidLetter(source.locate(letter))),
+    };
+
+    let password = tokens
+        .next()
+        .ok_or_else(|| ParseError::MissingPassword(source.locate_end(line)))?;
+    if let Some(token) = tokens.next() {
+        return Err(ParseError::TrailingToken(source.locate(token)));
+    }
+
+    Ok((PasswordPolicy::new(min, max, letter), password.to_owned()))
+}
+
+/// Parses the password database into policy and password pairs.
+pub fn parse_input(
+    file_name: impl AsRef<Path>,
+) -> Result<Vec<(PasswordPolicy, String)>, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Vec<(PasswordPolicy, String)>, ParseError> {
+    source
+        .content()
+        .lines()
+        .map(|line| parse_line(source, line))
+        .collect()
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Vec<(PasswordPolicy, String)>, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// Counts the passwords valid under the sled rental policy.
+pub fn count_valid_passwords_part1(passwords: &[(PasswordPolicy, String)]) -> usize {
+    passwords
+        .iter()
+        .filter(|(pol, pass)| pol.validate_password_part1(pass))
+        .count()
+}
+
+/// Counts the passwords valid under the toboggan corporate policy.
+pub fn count_valid_passwords_part2(passwords: &[(PasswordPolicy, String)]) -> usize {
+    passwords
+        .iter()
+        .filter(|(pol, pass)| pol.validate_password_part2(pass))
+        .count()
+}
+
+/// The puzzle of day 2.
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<(PasswordPolicy, String)>;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, passwords: &Self::Input) -> Answer {
+        count_valid_passwords_part1(passwords).into()
+    }
+
+    fn part2(&self, passwords: &Self::Input) -> Answer {
+        count_valid_passwords_part2(passwords).into()
+    }
+}
+
+impl Oracle for Day02 {
+    /// Generates `size` passwords of up to 12 letters, some of them taking
+    /// more than one byte.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let letters = ['a', 'b', 'c', 'é', '🦀'];
+        let mut input = String::new();
+        for _ in 0..size {
+            let min = rng.range(1, 6);
+            let max = rng.range(min, min + 6);
+            let letter = rng.choose(&letters);
+            let password = (0..rng.range(1, 13))
+                .map(|_| rng.choose(&letters))
+                .collect::<String>();
+            input += &format!("{}-{} {}: {}\n", min, max, letter, password);
+        }
+        input
+    }
+
+    fn oracle(&self, passwords: &Self::Input, part: usize) -> Answer {
+        let mut count = 0;
+        for (policy, password) in passwords {
+            let chars = password.chars().collect::<Vec<_>>();
+            let valid = if part == 1 {
+                let n = chars.iter().filter(|&&c| c == policy.letter).count();
+                policy.min <= n && n <= policy.max
+            } else {
+                let at = |i: usize| chars.get(i - 1) == Some(&policy.letter);
+                at(policy.min) != at(policy.max)
+            };
+            if valid {
+                count += 1;
+            }
+        }
+        count.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input() {
+        let passwords = parse_input("example.txt").unwrap();
+        assert_eq!(count_valid_passwords_part1(&passwords), 2);
+        assert_eq!(count_valid_passwords_part2(&passwords), 1);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day02, 50);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day02, 2, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let passwords = Day02.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(Day02.part1(&passwords), Answer::Number(2));
+        assert_eq!(Day02.part2(&passwords), Answer::Number(1));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "1-3 a: abcde\n1-3 bb: cdefg\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid letter `bb:`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (2, 5));
+
+        let source = Source::new("test.txt", "1-3 a: abc junk\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "unexpected token `junk`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (1, 12));
+    }
+}
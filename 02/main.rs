@@ -1,91 +1,45 @@
-use std::env;
-use std::fs;
-use std::path::Path;
 use std::process;
 
-struct PasswordPolicy {
-    min: usize,
-    max: usize,
-    letter: char,
-}
-
-impl PasswordPolicy {
-    fn new(min: usize, max: usize, letter: char) -> Self {
-        PasswordPolicy { min, max, letter }
-    }
-
-    fn validate_password_part1(&self, password: &str) -> bool {
-        let letter_count = password.matches(self.letter).count();
-        letter_count >= self.min && letter_count <= self.max
-    }
-
-    fn validate_password_part2(&self, password: &str) -> bool {
-        let match1 = password.chars().nth(self.min - 1) == Some(self.letter);
-        let match2 = password.chars().nth(self.max - 1) == Some(self.letter);
-        (match1 || match2) && !(match1 && match2)
-    }
-}
-
-fn parse_input(file_name: impl AsRef<Path>) -> Vec<(PasswordPolicy, String)> {
-    fs::read_to_string(file_name)
-        .unwrap()
-        .lines()
-        .map(|x| {
-            let mut tokens = x.split_whitespace();
-            let mut range_tokens = tokens.next().unwrap().split('-');
-            let policy = PasswordPolicy::new(
-                range_tokens.next().unwrap().parse::<usize>().unwrap(),
-                range_tokens.next().unwrap().parse::<usize>().unwrap(),
-                tokens.next().unwrap().chars().next().unwrap(),
-            );
-            (policy, tokens.next().unwrap().to_owned())
-        })
-        .collect()
-}
-
-fn count_valid_passwords_part1(passwords: &[(PasswordPolicy, String)]) -> usize {
-    passwords
-        .iter()
-        .filter(|(pol, pass)| pol.validate_password_part1(&pass))
-        .count()
-}
-
-fn count_valid_passwords_part2(passwords: &[(PasswordPolicy, String)]) -> usize {
-    passwords
-        .iter()
-        .filter(|(pol, pass)| pol.validate_password_part2(&pass))
-        .count()
-}
-
+use common::{or_exit, parse_args, take_option, Printer};
+use day02::{
+    count_valid_passwords_part1, count_valid_passwords_part2, default_policies, parse_input,
+    parse_policies_input, policy_counts, policy_counts_to_string, violations, violations_to_csv,
+    violations_to_json, Violation,
+};
 
 fn main() {
-    if env::args().count() != 2 {
-        eprintln!("USAGE: {} FILE", env::args().next().unwrap());
-        process::exit(1);
+    let (mut args, format) = parse_args(&["FILE", "[POLICIES]", "[--report csv|json]"]);
+    let export: Option<fn(&[Violation]) -> String> = match take_option(&mut args, "--report") {
+        Ok(None) => None,
+        Ok(Some(report)) if report == "csv" => Some(violations_to_csv),
+        Ok(Some(report)) if report == "json" => Some(violations_to_json),
+        Ok(Some(report)) => {
+            eprintln!("error: invalid report format: {}", report);
+            process::exit(1);
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    };
+    let passwords = or_exit(parse_input(&args[0]));
+    let policies = args
+        .get(1)
+        .map(|file_name| or_exit(parse_policies_input(file_name)));
+
+    if let Some(export) = export {
+        let policies = policies.unwrap_or_else(default_policies);
+        print!("{}", export(&violations(&passwords, &policies)));
+        return;
     }
 
-    let passwords = parse_input(env::args().nth(1).unwrap());
-    let part1 = count_valid_passwords_part1(&passwords);
-    let part2 = count_valid_passwords_part2(&passwords);
-    println!("Result (Part 1) {}", part1);
-    println!("Result (Part 2) {}", part2);
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_example_input() {
-        let passwords = parse_input("example.txt");
-        assert_eq!(count_valid_passwords_part1(&passwords), 2);
-        assert_eq!(count_valid_passwords_part2(&passwords), 1);
+    if let Some(policies) = policies {
+        let counts = policy_counts(&passwords, &policies);
+        print!("{}", policy_counts_to_string(&counts, format));
+        return;
     }
 
-    #[test]
-    fn test_puzzle_input() {
-        let passwords = parse_input("input.txt");
-        assert_eq!(count_valid_passwords_part1(&passwords), 424);
-        assert_eq!(count_valid_passwords_part2(&passwords), 747);
-    }
+    let mut printer = Printer::new(2, format);
+    printer.solve(1, || count_valid_passwords_part1(&passwords));
+    printer.solve(2, || count_valid_passwords_part2(&passwords));
 }
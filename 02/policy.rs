@@ -0,0 +1,526 @@
+//! Password rules, and the policy description files that combine them.
+//!
+//! A policy file has one policy per line, made of a name and a rule:
+//!
+//! ```text
+//! # Both rules of the puzzle, and at least three distinct characters.
+//! strict: count and position and distinct 3
+//! lenient: (count or position) and not forbid abc
+//! ```
+//!
+//! The rules are:
+//!
+//! - `count`, the sled rental rule of part 1;
+//! - `position`, the toboggan corporate rule of part 2;
+//! - `distinct N`, at least `N` distinct characters;
+//! - `forbid TEXT`, no occurrence of `TEXT`;
+//! - `require N CLASS`, at least `N` characters of `CLASS`, one of `lower`,
+//!   `upper`, `letter`, `digit` or `symbol`.
+//!
+//! Rules are combined with `not`, `and` and `or`, from the highest to the
+//! lowest precedence, and grouped with parentheses. Blank lines and lines
+//! starting with `#` are ignored.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+use common::{Diagnostic, IoError, Location, Source};
+
+use crate::PasswordPolicy;
+
+/// A rule that a password must follow.
+pub trait Rule {
+    /// Explains why `password`, stored with `policy` in the database,
+    /// doesn't follow the rule, or returns `None` if it does.
+    fn violation(&self, policy: &PasswordPolicy, password: &str) -> Option<String>;
+
+    /// Checks if `password`, stored with `policy` in the database, follows
+    /// the rule.
+    fn check(&self, policy: &PasswordPolicy, password: &str) -> bool {
+        self.violation(policy, password).is_none()
+    }
+}
+
+/// The letter of the policy appears between `min` and `max` times.
+pub struct LetterCount;
+
+impl Rule for LetterCount {
+    fn violation(&self, policy: &PasswordPolicy, password: &str) -> Option<String> {
+        if policy.validate_password_part1(password) {
+            return None;
+        }
+        Some(format!(
+            "letter '{}' appears {} times, allowed {}-{}",
+            policy.letter,
+            policy.letter_count(password),
+            policy.min,
+            policy.max
+        ))
+    }
+}
+
+/// The letter of the policy is in exactly one of the positions `min` and
+/// `max`.
+pub struct LetterPosition;
+
+impl Rule for LetterPosition {
+    fn violation(&self, policy: &PasswordPolicy, password: &str) -> Option<String> {
+        if policy.validate_password_part2(password) {
+            return None;
+        }
+        let both = if policy.letter_at(password, policy.min) {
+            "both"
+        } else {
+            "neither"
+        };
+        Some(format!(
+            "positions {} and {} {} contain '{}'",
+            policy.min, policy.max, both, policy.letter
+        ))
+    }
+}
+
+/// The password has at least this many distinct characters.
+pub struct MinDistinct(pub usize);
+
+impl Rule for MinDistinct {
+    fn violation(&self, _: &PasswordPolicy, password: &str) -> Option<String> {
+        let distinct = password.chars().collect::<HashSet<_>>().len();
+        if distinct >= self.0 {
+            return None;
+        }
+        Some(format!(
+            "{} distinct characters, needs at least {}",
+            distinct, self.0
+        ))
+    }
+}
+
+/// The password doesn't contain this substring.
+pub struct Forbidden(pub String);
+
+impl Rule for Forbidden {
+    fn violation(&self, _: &PasswordPolicy, password: &str) -> Option<String> {
+        if !password.contains(&self.0) {
+            return None;
+        }
+        Some(format!("contains forbidden '{}'", self.0))
+    }
+}
+
+/// A class of characters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CharClass {
+    Lower,
+    Upper,
+    Letter,
+    /// An ASCII digit, `0` to `9`.
+    Digit,
+    /// Anything that isn't a letter, a digit or whitespace.
+    Symbol,
+}
+
+impl CharClass {
+    fn contains(self, c: char) -> bool {
+        match self {
+            CharClass::Lower => c.is_lowercase(),
+            CharClass::Upper => c.is_uppercase(),
+            CharClass::Letter => c.is_alphabetic(),
+            CharClass::Digit => c.is_ascii_digit(),
+            CharClass::Symbol => !c.is_alphabetic() && !c.is_ascii_digit() && !c.is_whitespace(),
+        }
+    }
+}
+
+impl fmt::Display for CharClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            CharClass::Lower => "lower",
+            CharClass::Upper => "upper",
+            CharClass::Letter => "letter",
+            CharClass::Digit => "digit",
+            CharClass::Symbol => "symbol",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The password has at least `min` characters of `class`.
+pub struct Require {
+    pub min: usize,
+    pub class: CharClass,
+}
+
+impl Rule for Require {
+    fn violation(&self, _: &PasswordPolicy, password: &str) -> Option<String> {
+        let count = password.chars().filter(|&c| self.class.contains(c)).count();
+        if count >= self.min {
+            return None;
+        }
+        Some(format!(
+            "{} {} characters, needs at least {}",
+            count, self.class, self.min
+        ))
+    }
+}
+
+/// The password follows all of the rules.
+pub struct And(pub Vec<Box<dyn Rule>>);
+
+impl Rule for And {
+    fn violation(&self, policy: &PasswordPolicy, password: &str) -> Option<String> {
+        let reasons = self
+            .0
+            .iter()
+            .filter_map(|rule| rule.violation(policy, password))
+            .collect::<Vec<_>>();
+        if reasons.is_empty() {
+            None
+        } else {
+            Some(reasons.join("; "))
+        }
+    }
+}
+
+/// The password follows any of the rules.
+pub struct Or(pub Vec<Box<dyn Rule>>);
+
+impl Rule for Or {
+    fn violation(&self, policy: &PasswordPolicy, password: &str) -> Option<String> {
+        let mut reasons = Vec::new();
+        for rule in &self.0 {
+            reasons.push(rule.violation(policy, password)?);
+        }
+        Some(reasons.join("; "))
+    }
+}
+
+/// The password doesn't follow the rule.
+pub struct Not(pub Box<dyn Rule>);
+
+impl Rule for Not {
+    fn violation(&self, policy: &PasswordPolicy, password: &str) -> Option<String> {
+        if self.0.check(policy, password) {
+            Some("follows a negated rule".to_owned())
+        } else {
+            None
+        }
+    }
+}
+
+/// A named rule of a policy file.
+pub struct Policy {
+    pub name: String,
+    pub rule: Box<dyn Rule>,
+}
+
+/// An error found while parsing a policy file.
+#[derive(Debug)]
+pub enum PolicyError {
+    /// The policy file couldn't be read.
+    Io(IoError),
+    /// The line doesn't start with a name followed by `:`.
+    MissingName(Location),
+    /// A rule isn't one of the known ones.
+    UnknownRule(Location),
+    /// The argument of a rule isn't a number.
+    InvalidNumber(Location),
+    /// The argument of `require` isn't a class of characters.
+    InvalidClass(Location),
+    /// The line ends before a rule or one of its arguments.
+    MissingRule(Location),
+    /// A parenthesis isn't closed.
+    UnclosedParen(Location),
+    /// A token is left after the rule.
+    UnexpectedToken(Location),
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolicyError::Io(err) => write!(f, "{}", err),
+            PolicyError::MissingName(_) => write!(f, "missing policy name"),
+            PolicyError::UnknownRule(loc) => write!(f, "unknown rule `{}`", loc.token),
+            PolicyError::InvalidNumber(loc) => write!(f, "invalid number `{}`", loc.token),
+            PolicyError::InvalidClass(loc) => write!(f, "invalid class `{}`", loc.token),
+            PolicyError::MissingRule(_) => write!(f, "missing rule"),
+            PolicyError::UnclosedParen(_) => write!(f, "unclosed parenthesis"),
+            PolicyError::UnexpectedToken(loc) => write!(f, "unexpected `{}`", loc.token),
+        }
+    }
+}
+
+impl Diagnostic for PolicyError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            PolicyError::Io(_) => None,
+            PolicyError::MissingName(loc)
+            | PolicyError::UnknownRule(loc)
+            | PolicyError::InvalidNumber(loc)
+            | PolicyError::InvalidClass(loc)
+            | PolicyError::MissingRule(loc)
+            | PolicyError::UnclosedParen(loc)
+            | PolicyError::UnexpectedToken(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for PolicyError {
+    fn from(err: IoError) -> Self {
+        PolicyError::Io(err)
+    }
+}
+
+/// Splits `s` into words and parentheses.
+fn tokenize(s: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut rest = s.trim_start();
+    while !rest.is_empty() {
+        let len = match rest.find(|c: char| c.is_whitespace() || c == '(' || c == ')') {
+            Some(0) => 1,
+            Some(len) => len,
+            None => rest.len(),
+        };
+        tokens.push(&rest[..len]);
+        rest = rest[len..].trim_start();
+    }
+    tokens
+}
+
+/// A recursive descent parser of the rule of a policy.
+struct Parser<'a> {
+    source: &'a Source,
+    line: &'a str,
+    tokens: Vec<&'a str>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Result<&'a str, PolicyError> {
+        let token = self
+            .peek()
+            .ok_or_else(|| PolicyError::MissingRule(self.source.locate_end(self.line)))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn number(&mut self) -> Result<usize, PolicyError> {
+        let token = self.next()?;
+        token
+            .parse()
+            .map_err(|_| PolicyError::InvalidNumber(self.source.locate(token)))
+    }
+
+    fn or(&mut self) -> Result<Box<dyn Rule>, PolicyError> {
+        let mut rules = vec![self.and()?];
+        while self.peek() == Some("or") {
+            self.pos += 1;
+            rules.push(self.and()?);
+        }
+        Ok(if rules.len() == 1 {
+            rules.remove(0)
+        } else {
+            Box::new(Or(rules))
+        })
+    }
+
+    fn and(&mut self) -> Result<Box<dyn Rule>, PolicyError> {
+        let mut rules = vec![self.not()?];
+        while self.peek() == Some("and") {
+            self.pos += 1;
+            rules.push(self.not()?);
+        }
+        Ok(if rules.len() == 1 {
+            rules.remove(0)
+        } else {
+            Box::new(And(rules))
+        })
+    }
+
+    fn not(&mut self) -> Result<Box<dyn Rule>, PolicyError> {
+        let token = self.next()?;
+        let rule: Box<dyn Rule> = match token {
+            "not" => Box::new(Not(self.not()?)),
+            "(" => {
+                let rule = self.or()?;
+                if self.peek() != Some(")") {
+                    return Err(PolicyError::UnclosedParen(self.source.locate(token)));
+                }
+                self.pos += 1;
+                rule
+            }
+            "count" => Box::new(LetterCount),
+            "position" => Box::new(LetterPosition),
+            "distinct" => Box::new(MinDistinct(self.number()?)),
+            "forbid" => Box::new(Forbidden(self.next()?.to_owned())),
+            "require" => {
+                let min = self.number()?;
+                let class = self.next()?;
+                let class = match class {
+                    "lower" => CharClass::Lower,
+                    "upper" => CharClass::Upper,
+                    "letter" => CharClass::Letter,
+                    "digit" => CharClass::Digit,
+                    "symbol" => CharClass::Symbol,
+                    _ => return Err(PolicyError::InvalidClass(self.source.locate(class))),
+                };
+                Box::new(Require { min, class })
+            }
+            _ => return Err(PolicyError::UnknownRule(self.source.locate(token))),
+        };
+        Ok(rule)
+    }
+}
+
+fn parse_policy(source: &Source, line: &str) -> Result<Policy, PolicyError> {
+    let (name, rule) = line
+        .split_once(':')
+        .filter(|(name, _)| !name.trim().is_empty())
+        .ok_or_else(|| PolicyError::MissingName(source.locate(&line[..0])))?;
+
+    let mut parser = Parser {
+        source,
+        line,
+        tokens: tokenize(rule),
+        pos: 0,
+    };
+    let rule = parser.or()?;
+    if let Some(token) = parser.peek() {
+        return Err(PolicyError::UnexpectedToken(source.locate(token)));
+    }
+
+    Ok(Policy {
+        name: name.trim().to_owned(),
+        rule,
+    })
+}
+
+/// Parses a policy file, one named rule per line.
+pub fn parse_policies_input(file_name: impl AsRef<Path>) -> Result<Vec<Policy>, PolicyError> {
+    parse_policies_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_policies_input`], but parsing an in-memory source.
+pub fn parse_policies_source(source: &Source) -> Result<Vec<Policy>, PolicyError> {
+    source
+        .content()
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|line| parse_policy(source, line))
+        .collect()
+}
+
+/// Same as [`parse_policies_input`], but parsing a string.
+pub fn parse_policies(input: &str) -> Result<Vec<Policy>, PolicyError> {
+    parse_policies_source(&Source::from(input))
+}
+
+/// Counts the passwords that follow `rule`.
+pub fn count_valid_passwords(passwords: &[(PasswordPolicy, String)], rule: &dyn Rule) -> usize {
+    passwords
+        .iter()
+        .filter(|(pol, pass)| rule.check(pol, pass))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules() {
+        let policy = PasswordPolicy::new(1, 3, 'a');
+        assert!(MinDistinct(5).check(&policy, "abcde"));
+        assert!(!MinDistinct(5).check(&policy, "abcdd"));
+        assert!(Forbidden("bc".to_owned()).check(&policy, "acb"));
+        assert!(!Forbidden("bc".to_owned()).check(&policy, "abc"));
+        let require = |min, class| Require { min, class };
+        assert!(require(2, CharClass::Upper).check(&policy, "ÀbC"));
+        assert!(require(1, CharClass::Digit).check(&policy, "ab1"));
+        assert!(!require(1, CharClass::Digit).check(&policy, "ab½٣"));
+        assert!(require(2, CharClass::Symbol).check(&policy, "ab½٣"));
+        assert!(require(2, CharClass::Symbol).check(&policy, "a!b?"));
+        assert!(!require(1, CharClass::Symbol).check(&policy, "a b"));
+        assert!(require(3, CharClass::Letter).check(&policy, "aé1ß"));
+        assert!(!require(1, CharClass::Lower).check(&policy, "AB1"));
+
+        let reason = |rule: &dyn Rule, password| rule.violation(&policy, password);
+        assert_eq!(
+            reason(&require(2, CharClass::Digit), "a1"),
+            Some("1 digit characters, needs at least 2".to_owned())
+        );
+        assert_eq!(
+            reason(&Forbidden("bc".to_owned()), "abc"),
+            Some("contains forbidden 'bc'".to_owned())
+        );
+        let both = And(vec![Box::new(MinDistinct(4)), Box::new(LetterCount)]);
+        assert_eq!(
+            reason(&both, "bcb"),
+            Some(
+                "2 distinct characters, needs at least 4; letter 'a' appears 0 times, allowed 1-3"
+                    .to_owned()
+            )
+        );
+        assert_eq!(reason(&Not(Box::new(LetterCount)), "bcb"), None);
+    }
+
+    #[test]
+    fn test_example_policies() {
+        let passwords = crate::parse_input("example.txt").unwrap();
+        let policies = parse_policies_input("policies.txt").unwrap();
+        let counts = policies
+            .iter()
+            .map(|p| (p.name.as_str(), count_valid_passwords(&passwords, &*p.rule)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            counts,
+            vec![
+                ("sled", 2),
+                ("toboggan", 1),
+                ("both", 1),
+                ("either", 2),
+                ("strict", 1),
+                ("varied", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_precedence() {
+        let policy = PasswordPolicy::new(1, 3, 'a');
+        let check = |rule: &str, password: &str| {
+            let policies = parse_policies(&format!("p: {}", rule)).unwrap();
+            policies[0].rule.check(&policy, password)
+        };
+        assert!(check("not forbid x or forbid a", "xa"));
+        assert!(!check("not (forbid x or forbid a)", "a"));
+        assert!(check("forbid x and forbid y or forbid a", "xy"));
+        assert!(!check("forbid x and (forbid y or forbid a)", "xy"));
+        assert!(check("not not forbid x", "a"));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let cases = [
+            ("p: count and", "missing rule", (1, 13)),
+            ("p: distinct x", "invalid number `x`", (1, 13)),
+            ("p: require 2 vowel", "invalid class `vowel`", (1, 14)),
+            ("p: (count or position", "unclosed parenthesis", (1, 4)),
+            ("p: count position", "unexpected `position`", (1, 10)),
+            ("p: length 8", "unknown rule `length`", (1, 4)),
+            ("count", "missing policy name", (1, 1)),
+        ];
+        for (input, message, position) in cases.iter() {
+            let source = Source::new("test.txt", format!("# comment\n\n{}\n", input));
+            let err = parse_policies_source(&source).err().unwrap();
+            assert_eq!(err.to_string(), *message);
+            let loc = err.location().unwrap();
+            assert_eq!((loc.line - 2, loc.column), *position);
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Reports of the passwords that don't follow a set of policies.
+
+use common::{csv_field, json_string, Format};
+
+use crate::{count_valid_passwords, LetterCount, LetterPosition, PasswordPolicy, Policy};
+
+/// A password that doesn't follow a policy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// Name of the policy that isn't followed.
+    pub policy: String,
+    /// Line number in the password database, starting at 1.
+    pub line: usize,
+    /// The policy stored with the password, like `1-3 a`.
+    pub password_policy: String,
+    pub password: String,
+    /// Why the password doesn't follow the policy.
+    pub reason: String,
+}
+
+/// Returns the policies of the two parts of the puzzle, named `sled` and
+/// `toboggan`.
+pub fn default_policies() -> Vec<Policy> {
+    vec![
+        Policy {
+            name: "sled".to_owned(),
+            rule: Box::new(LetterCount),
+        },
+        Policy {
+            name: "toboggan".to_owned(),
+            rule: Box::new(LetterPosition),
+        },
+    ]
+}
+
+/// Lists the passwords that don't follow each of the `policies`, in the
+/// order of the policies and then of the lines.
+pub fn violations(passwords: &[(PasswordPolicy, String)], policies: &[Policy]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for policy in policies {
+        for (i, (password_policy, password)) in passwords.iter().enumerate() {
+            if let Some(reason) = policy.rule.violation(password_policy, password) {
+                violations.push(Violation {
+                    policy: policy.name.clone(),
+                    line: i + 1,
+                    password_policy: password_policy.to_string(),
+                    password: password.clone(),
+                    reason,
+                });
+            }
+        }
+    }
+    violations
+}
+
+/// Formats the violations as CSV, with a header line.
+pub fn violations_to_csv(violations: &[Violation]) -> String {
+    let mut csv = String::from("policy,line,password_policy,password,reason\n");
+    for v in violations {
+        csv += &format!(
+            "{},{},{},{},{}\n",
+            csv_field(&v.policy),
+            v.line,
+            csv_field(&v.password_policy),
+            csv_field(&v.password),
+            csv_field(&v.reason)
+        );
+    }
+    csv
+}
+
+/// Formats the violations as a JSON array, with one object per line.
+pub fn violations_to_json(violations: &[Violation]) -> String {
+    let objects = violations
+        .iter()
+        .map(|v| {
+            format!(
+                r#"  {{"policy":{},"line":{},"password_policy":{},"password":{},"reason":{}}}"#,
+                json_string(&v.policy),
+                v.line,
+                json_string(&v.password_policy),
+                json_string(&v.password),
+                json_string(&v.reason)
+            )
+        })
+        .collect::<Vec<_>>();
+    if objects.is_empty() {
+        "[]\n".to_owned()
+    } else {
+        format!("[\n{}\n]\n", objects.join(",\n"))
+    }
+}
+
+/// The number of passwords that follow a policy, and that don't.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolicyCount {
+    pub policy: String,
+    pub valid: usize,
+    pub invalid: usize,
+}
+
+/// Counts the passwords that follow each of the `policies`, in the order of
+/// the policies.
+pub fn policy_counts(
+    passwords: &[(PasswordPolicy, String)],
+    policies: &[Policy],
+) -> Vec<PolicyCount> {
+    policies
+        .iter()
+        .map(|policy| {
+            let valid = count_valid_passwords(passwords, &*policy.rule);
+            PolicyCount {
+                policy: policy.name.clone(),
+                valid,
+                invalid: passwords.len() - valid,
+            }
+        })
+        .collect()
+}
+
+/// Formats the counts as a `NAME: N valid, M invalid` line per policy, as
+/// one JSON object per line, or as TSV with a header line.
+pub fn policy_counts_to_string(counts: &[PolicyCount], format: Format) -> String {
+    let mut s = match format {
+        Format::Tsv => String::from("policy\tvalid\tinvalid\n"),
+        _ => String::new(),
+    };
+    for count in counts {
+        s += &match format {
+            Format::Text => format!(
+                "{}: {} valid, {} invalid\n",
+                count.policy, count.valid, count.invalid
+            ),
+            Format::Json => format!(
+                "{{\"policy\":{},\"valid\":{},\"invalid\":{}}}\n",
+                json_string(&count.policy),
+                count.valid,
+                count.invalid
+            ),
+            Format::Tsv => format!(
+                "{}\t{}\t{}\n",
+                count.policy.replace(&['\t', '\n'][..], " "),
+                count.valid,
+                count.invalid
+            ),
+        };
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn test_example_violations() {
+        let passwords = crate::parse_input("example.txt").unwrap();
+        let violations = violations(&passwords, &default_policies());
+        let lines = violations
+            .iter()
+            .map(|v| (v.policy.as_str(), v.line, v.reason.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                ("sled", 2, "letter 'b' appears 0 times, allowed 1-3"),
+                ("toboggan", 2, "positions 1 and 3 neither contain 'b'"),
+                ("toboggan", 3, "positions 2 and 9 both contain 'c'"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_multi_byte() {
+        let passwords = parse("1-2 é: éaé\n2-3 🦀: a🦀🦀\n1-1 ß: aß\n1-1 é: éé\n").unwrap();
+        let violations = violations(&passwords, &default_policies());
+        let lines = violations
+            .iter()
+            .map(|v| (v.line, v.reason.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                (4, "letter 'é' appears 2 times, allowed 1-1"),
+                (2, "positions 2 and 3 both contain '🦀'"),
+                (3, "positions 1 and 1 neither contain 'ß'"),
+                (4, "positions 1 and 1 both contain 'é'"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_export() {
+        let violations = vec![Violation {
+            policy: "strict".to_owned(),
+            line: 7,
+            password_policy: "1-3 \"".to_owned(),
+            password: "a,b".to_owned(),
+            reason: "contains forbidden ','".to_owned(),
+        }];
+        assert_eq!(
+            violations_to_csv(&violations),
+            "policy,line,password_policy,password,reason\n\
+             strict,7,\"1-3 \"\"\",\"a,b\",\"contains forbidden ','\"\n"
+        );
+        assert_eq!(
+            violations_to_json(&violations),
+            "[\n  {\"policy\":\"strict\",\"line\":7,\"password_policy\":\"1-3 \\\"\",\
+             \"password\":\"a,b\",\"reason\":\"contains forbidden ','\"}\n]\n"
+        );
+        assert_eq!(violations_to_json(&[]), "[]\n");
+    }
+
+    #[test]
+    fn test_policy_counts() {
+        let passwords = crate::parse_input("example.txt").unwrap();
+        let counts = policy_counts(&passwords, &default_policies());
+        assert_eq!(
+            policy_counts_to_string(&counts, Format::Text),
+            "sled: 2 valid, 1 invalid\ntoboggan: 1 valid, 2 invalid\n"
+        );
+        assert_eq!(
+            policy_counts_to_string(&counts, Format::Json),
+            "{\"policy\":\"sled\",\"valid\":2,\"invalid\":1}\n\
+             {\"policy\":\"toboggan\",\"valid\":1,\"invalid\":2}\n"
+        );
+        assert_eq!(
+            policy_counts_to_string(&counts[..1], Format::Tsv),
+            "policy\tvalid\tinvalid\nsled\t2\t1\n"
+        );
+    }
+}
@@ -0,0 +1,473 @@
+//! Solution to the puzzle of day 3, _Toboggan Trajectory_.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::iter;
+use std::path::Path;
+use std::str::FromStr;
+
+use common::{Answer, Diagnostic, Format, IoError, Location, Oracle, Rng, Solution, Source};
+
+/// A `(row, column)` position in the map.
+pub type Point = (usize, usize);
+
+/// A slope, moving `right` columns (left if negative) and `down` rows at
+/// every step. Written as `RIGHT,DOWN`, like `3,1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Slope {
+    pub right: isize,
+    pub down: usize,
+}
+
+impl Slope {
+    pub fn new(right: isize, down: usize) -> Self {
+        Slope { right, down }
+    }
+}
+
+impl fmt::Display for Slope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.right, self.down)
+    }
+}
+
+impl FromStr for Slope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid slope: {}", s);
+        let (right, down) = s.split_once(',').ok_or_else(invalid)?;
+        let right = right.trim().parse().map_err(|_| invalid())?;
+        match down.trim().parse() {
+            Ok(down) if down > 0 => Ok(Slope { right, down }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// The slope checked in part 1.
+pub const PART_1_SLOPE: Slope = Slope { right: 3, down: 1 };
+
+/// The slopes checked in part 2.
+pub const PART_2_SLOPES: [Slope; 5] = [
+    Slope { right: 1, down: 1 },
+    Slope { right: 3, down: 1 },
+    Slope { right: 5, down: 1 },
+    Slope { right: 7, down: 1 },
+    Slope { right: 1, down: 2 },
+];
+
+/// The map of the trees in the area, repeating to the right.
+pub struct Map {
+    nrows: usize,
+    ncols: usize,
+    trees: HashSet<Point>,
+}
+
+impl Map {
+    /// Creates a map of `nrows` by `ncols` with trees at the given
+    /// positions.
+    pub fn new(nrows: usize, ncols: usize, trees: HashSet<Point>) -> Self {
+        Map {
+            nrows,
+            ncols,
+            trees,
+        }
+    }
+
+    /// Counts the trees hit moving right 3 and down 1.
+    pub fn count_trees_part1(&self) -> usize {
+        self.count_trees(PART_1_SLOPE)
+    }
+
+    /// Multiplies the trees hit with each of the slopes checked in part 2.
+    pub fn count_trees_part2(&self) -> usize {
+        PART_2_SLOPES.iter().map(|&x| self.count_trees(x)).product()
+    }
+
+    /// Counts the trees hit with `slope`. As the map repeats, a step to the
+    /// right is the same as a step of its remainder by the width.
+    pub fn count_trees(&self, slope: Slope) -> usize {
+        let right = slope.right.rem_euclid(self.ncols as isize) as usize;
+        self.trees
+            .iter()
+            .filter(|t| {
+                let steps = t.0 / slope.down;
+                t.0 % slope.down == 0 && t.1 == steps % self.ncols * right % self.ncols
+            })
+            .count()
+    }
+
+    /// Multiplies the trees hit with each of `slopes`, returning `None` if
+    /// the product overflows.
+    pub fn multiply_trees(&self, slopes: &[Slope]) -> Option<usize> {
+        slopes
+            .iter()
+            .try_fold(1usize, |acc, &x| acc.checked_mul(self.count_trees(x)))
+    }
+
+    /// Ranks every slope moving at most `bounds.right` columns either way
+    /// and at most `bounds.down` rows, from the fewest to the most trees
+    /// hit. Ties are in the order of the slopes.
+    pub fn rank_slopes(&self, bounds: Slope) -> Vec<(Slope, usize)> {
+        let max_right = bounds.right.abs();
+        let mut ranking = (1..=bounds.down)
+            .flat_map(|down| (-max_right..=max_right).map(move |right| Slope::new(right, down)))
+            .map(|slope| (slope, self.count_trees(slope)))
+            .collect::<Vec<_>>();
+        ranking.sort_unstable_by_key(|&(slope, trees)| (trees, slope));
+        ranking
+    }
+
+    /// Draws the map, repeated horizontally as far as the path of `slope`
+    /// goes, with the squares of the path marked: `O` for the trees hit and
+    /// `X` for the open squares. The starting square is marked too, as
+    /// [`Map::count_trees`] counts a tree there.
+    pub fn render(&self, slope: Slope) -> Vec<Vec<char>> {
+        let path = (0..self.nrows)
+            .step_by(slope.down)
+            .map(|row| (row, (row / slope.down) as isize * slope.right))
+            .collect::<Vec<_>>();
+        let ncols = self.ncols as isize;
+        let first = path.iter().map(|&(_, col)| col.div_euclid(ncols)).min();
+        let last = path.iter().map(|&(_, col)| col.div_euclid(ncols)).max();
+        let (first, last) = (
+            first.unwrap_or(0) * ncols,
+            last.unwrap_or(0) * ncols + ncols,
+        );
+
+        let mut grid = (0..self.nrows)
+            .map(|row| {
+                (first..last)
+                    .map(|col| {
+                        let col = col.rem_euclid(ncols) as usize;
+                        if self.trees.contains(&(row, col)) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        for (row, col) in path {
+            let square = &mut grid[row][(col - first) as usize];
+            *square = if *square == '#' { 'O' } else { 'X' };
+        }
+        grid
+    }
+
+    /// Same as [`Map::render`], as lines of text.
+    pub fn render_text(&self, slope: Slope) -> String {
+        self.render(slope)
+            .iter()
+            .map(|row| row.iter().collect::<String>() + "\n")
+            .collect()
+    }
+
+    /// Same as [`Map::render`], as a plain PPM image with a pixel per
+    /// square: white for open squares, green for trees, red for the trees
+    /// hit and blue for the open squares of the path.
+    pub fn render_ppm(&self, slope: Slope) -> String {
+        let grid = self.render(slope);
+        let width = grid.first().map_or(0, |row| row.len());
+        let mut ppm = format!("P3\n{} {}\n255\n", width, grid.len());
+        for row in &grid {
+            let pixels = row
+                .iter()
+                .map(|square| match square {
+                    '#' => "34 139 34",
+                    'O' => "255 0 0",
+                    'X' => "0 0 255",
+                    _ => "255 255 255",
+                })
+                .collect::<Vec<_>>();
+            ppm += &pixels.join(" ");
+            ppm.push('\n');
+        }
+        ppm
+    }
+}
+
+/// An error found while parsing the map.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A square is neither open (`.`) nor a tree (`#`).
+    InvalidSquare(Location),
+    /// A line is not as wide as the first one.
+    InvalidWidth(Location),
+    /// The map has no squares.
+    Empty,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidSquare(loc) => write!(f, "invalid square `{}`", loc.token),
+            ParseError::InvalidWidth(_) => write!(f, "line width differs from the first line"),
+            ParseError::Empty => write!(f, "empty map"),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) | ParseError::Empty => None,
+            ParseError::InvalidSquare(loc) | ParseError::InvalidWidth(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the map of trees (`#`) and open squares (`.`).
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<Map, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Map, ParseError> {
+    let content = source.content();
+    let ncols = content.lines().next().map_or(0, |x| x.chars().count());
+    if ncols == 0 {
+        return Err(ParseError::Empty);
+    }
+
+    let mut trees = HashSet::new();
+    for (row, line) in content.lines().enumerate() {
+        if line.chars().count() != ncols {
+            return Err(ParseError::InvalidWidth(source.locate(line)));
+        }
+        for (col, (i, ch)) in line.char_indices().enumerate() {
+            match ch {
+                '#' => {
+                    trees.insert((row, col));
+                }
+                '.' => (),
+                _ => {
+                    let square = &line[i..i + ch.len_utf8()];
+                    return Err(ParseError::InvalidSquare(source.locate(square)));
+                }
+            }
+        }
+    }
+
+    Ok(Map::new(content.lines().count(), ncols, trees))
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Map, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// Formats slopes and the trees hit with them, like a ranking, as a
+/// `RIGHT,DOWN: TREES` line per slope, as a JSON array of objects with the
+/// `right`, `down` and `trees` keys, or as TSV with a header line.
+pub fn ranking_to_string(ranking: &[(Slope, usize)], format: Format) -> String {
+    match format {
+        Format::Text => ranking
+            .iter()
+            .map(|(slope, trees)| format!("{}: {}\n", slope, trees))
+            .collect(),
+        Format::Json => {
+            let objects = ranking
+                .iter()
+                .map(|(slope, trees)| {
+                    format!(
+                        r#"  {{"right":{},"down":{},"trees":{}}}"#,
+                        slope.right, slope.down, trees
+                    )
+                })
+                .collect::<Vec<_>>();
+            if objects.is_empty() {
+                "[]\n".to_owned()
+            } else {
+                format!("[\n{}\n]\n", objects.join(",\n"))
+            }
+        }
+        Format::Tsv => iter::once("right\tdown\ttrees\n".to_owned())
+            .chain(
+                ranking
+                    .iter()
+                    .map(|(slope, trees)| format!("{}\t{}\t{}\n", slope.right, slope.down, trees)),
+            )
+            .collect(),
+    }
+}
+
+/// The puzzle of day 3.
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Map;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, map: &Self::Input) -> Answer {
+        map.count_trees_part1().into()
+    }
+
+    fn part2(&self, map: &Self::Input) -> Answer {
+        map.count_trees_part2().into()
+    }
+}
+
+impl Oracle for Day03 {
+    /// Generates a map of `size` rows and up to `size` columns.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let ncols = rng.range(1, size + 1);
+        let mut input = String::new();
+        for _ in 0..size {
+            input.extend((0..ncols).map(|_| if rng.ratio(1, 4) { '#' } else { '.' }));
+            input.push('\n');
+        }
+        input
+    }
+
+    fn oracle(&self, map: &Self::Input, part: usize) -> Answer {
+        if part == 1 {
+            slide(map, PART_1_SLOPE).into()
+        } else {
+            let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+            slopes
+                .iter()
+                .map(|&(right, down)| slide(map, Slope::new(right, down)))
+                .product::<usize>()
+                .into()
+        }
+    }
+}
+
+/// Counts the trees hit with `slope` by sliding down the map step by step.
+fn slide(map: &Map, slope: Slope) -> usize {
+    let (mut row, mut col, mut count) = (0, 0isize, 0);
+    while row < map.nrows {
+        let wrapped = col.rem_euclid(map.ncols as isize) as usize;
+        if map.trees.contains(&(row, wrapped)) {
+            count += 1;
+        }
+        row += slope.down;
+        col += slope.right;
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input() {
+        let map = parse_input("example.txt").unwrap();
+        assert_eq!(map.count_trees_part1(), 7);
+        assert_eq!(map.count_trees_part2(), 336);
+    }
+
+    #[test]
+    fn test_slopes() {
+        let map = parse_input("example.txt").unwrap();
+        assert_eq!(map.count_trees("-8,1".parse().unwrap()), 7);
+        assert_eq!(map.count_trees("25,1".parse().unwrap()), 7);
+        assert_eq!(map.multiply_trees(&PART_2_SLOPES), Some(336));
+        assert_eq!(map.multiply_trees(&[]), Some(1));
+        assert_eq!(map.multiply_trees(&[Slope::new(3, 1); 40]), None);
+        assert!("3,0".parse::<Slope>().is_err());
+        assert!("3".parse::<Slope>().is_err());
+        assert!("x,1".parse::<Slope>().is_err());
+
+        let ranking = map.rank_slopes(Slope::new(2, 2));
+        assert_eq!(ranking.len(), 10);
+        assert_eq!(ranking[0], (Slope::new(0, 2), 1));
+        assert!(ranking.windows(2).all(|w| w[0].1 <= w[1].1));
+        assert_eq!(
+            ranking_to_string(&ranking[..2], Format::Text),
+            "0,2: 1\n2,1: 1\n"
+        );
+        assert_eq!(
+            ranking_to_string(&ranking[..2], Format::Json),
+            "[\n  {\"right\":0,\"down\":2,\"trees\":1},\n  {\"right\":2,\"down\":1,\"trees\":1}\n]\n"
+        );
+        assert_eq!(
+            ranking_to_string(&ranking[..1], Format::Tsv),
+            "right\tdown\ttrees\n0\t2\t1\n"
+        );
+        assert_eq!(ranking_to_string(&[], Format::Json), "[]\n");
+
+        let mut rng = Rng::new(0);
+        for _ in 0..100 {
+            let map = parse(&Day03.generate(&mut rng, 12)).unwrap();
+            let slope = Slope::new(rng.range(0, 41) as isize - 20, rng.range(1, 4));
+            assert_eq!(map.count_trees(slope), slide(&map, slope), "{}", slope);
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let map = parse_input("example.txt").unwrap();
+        let text = map.render_text(PART_1_SLOPE);
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[0], "X.##.........##.........##.......");
+        assert_eq!(lines[1], "#..X#...#..#...#...#..#...#...#..");
+        assert_eq!(lines[10], ".#..#...#.#.#..#...#.#.#..#...O.#");
+        assert_eq!(text.matches('O').count(), 7);
+
+        let text = map.render_text(Slope::new(-1, 2));
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "..##.......X.##.......");
+        assert_eq!(lines[2], ".#....#..#X.#....#..#.");
+        assert_eq!(lines[4], ".#...##..O..#...##..#.");
+        assert_eq!(lines[10], ".#..#.X.#.#.#..#...#.#");
+
+        let ppm = map.render_ppm(Slope::new(-1, 2));
+        assert!(ppm.starts_with("P3\n22 11\n255\n255 255 255 255 255 255 34 139 34 "));
+        assert_eq!(ppm.lines().count(), 3 + 11);
+
+        let mut rng = Rng::new(0);
+        for _ in 0..100 {
+            let map = parse(&Day03.generate(&mut rng, 12)).unwrap();
+            let slope = Slope::new(rng.range(0, 41) as isize - 20, rng.range(1, 4));
+            let hit = map.render_text(slope).matches('O').count();
+            assert_eq!(hit, map.count_trees(slope), "{}", slope);
+        }
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day03, 20);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day03, 3, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let map = Day03.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(Day03.part1(&map), Answer::Number(7));
+        assert_eq!(Day03.part2(&map), Answer::Number(336));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "..#\n.#.\n#.O\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid square `O`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (3, 3));
+    }
+}
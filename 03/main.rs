@@ -1,85 +1,73 @@
-use std::collections::HashSet;
-use std::env;
 use std::fs;
-use std::path::Path;
 use std::process;
 
-type Point = (usize, usize);
+use common::{or_exit, parse_args, take_option, Printer};
+use day03::{parse_input, ranking_to_string, Slope, PART_1_SLOPE};
 
-struct Map {
-    ncols: usize,
-    trees: HashSet<Point>,
+fn parse_slope(s: &str) -> Slope {
+    s.parse().unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    })
 }
 
-impl Map {
-    fn new(ncols: usize, trees: HashSet<Point>) -> Self {
-        Map { ncols, trees }
-    }
-
-    fn count_trees_part1(&self) -> usize {
-        self.count_trees(&(1, 3))
-    }
+fn main() {
+    let (mut args, format) = parse_args(&[
+        "FILE",
+        "[--search RIGHT,DOWN]",
+        "[--render OUTPUT]",
+        "[SLOPE...]",
+    ]);
+    let (search, render) = match (
+        take_option(&mut args, "--search"),
+        take_option(&mut args, "--render"),
+    ) {
+        (Ok(bounds), Ok(render)) => (bounds.map(|x| parse_slope(&x)), render),
+        (Err(err), _) | (_, Err(err)) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    };
+    let slopes = args[1..].iter().map(|x| parse_slope(x)).collect::<Vec<_>>();
+    let map = or_exit(parse_input(&args[0]));
 
-    fn count_trees_part2(&self) -> usize {
-        [(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)]
-            .iter()
-            .map(|m| self.count_trees(m))
-            .product()
+    if let Some(output) = render {
+        let slope = slopes.first().copied().unwrap_or(PART_1_SLOPE);
+        if output == "-" {
+            print!("{}", map.render_text(slope));
+        } else {
+            let image = if output.ends_with(".ppm") {
+                map.render_ppm(slope)
+            } else {
+                map.render_text(slope)
+            };
+            if let Err(err) = fs::write(&output, image) {
+                eprintln!("error: {}: {}", output, err);
+                process::exit(1);
+            }
+        }
+        return;
     }
 
-    fn count_trees(&self, mov: &Point) -> usize {
-        self.trees
-            .iter()
-            .filter(|t| t.0 % mov.0 == 0 && t.1 == (t.0 / mov.0 * mov.1) % self.ncols)
-            .count()
+    if let Some(bounds) = search {
+        print!("{}", ranking_to_string(&map.rank_slopes(bounds), format));
     }
-}
 
-fn parse_input(file_name: impl AsRef<Path>) -> Map {
-    let content = fs::read_to_string(file_name).unwrap();
+    let mut printer = Printer::new(3, format);
 
-    let trees = content
-        .lines()
-        .enumerate()
-        .flat_map(|(row, line)| {
-            line.chars()
-                .enumerate()
-                .filter(|&(_, ch)| ch == '#')
-                .map(move |(col, _)| (row, col))
-        })
-        .collect();
-
-    Map::new(content.lines().next().unwrap().chars().count(), trees)
-}
-
-fn main() {
-    if env::args().count() != 2 {
-        eprintln!("USAGE: {} FILE", env::args().next().unwrap());
-        process::exit(1);
+    if search.is_none() && slopes.is_empty() {
+        printer.solve(1, || map.count_trees_part1());
+        printer.solve(2, || map.count_trees_part2());
+        return;
     }
 
-    let map = parse_input(env::args().nth(1).unwrap());
-    let part1 = map.count_trees_part1();
-    let part2 = map.count_trees_part2();
-    println!("Result (Part 1): {}", part1);
-    println!("Result (Part 2): {}", part2);
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_example_input() {
-        let map = parse_input("example.txt");
-        assert_eq!(map.count_trees_part1(), 7);
-        assert_eq!(map.count_trees_part2(), 336);
-    }
-
-    #[test]
-    fn test_puzzle_input() {
-        let map = parse_input("input.txt");
-        assert_eq!(map.count_trees_part1(), 207);
-        assert_eq!(map.count_trees_part2(), 2655892800);
+    if let Some(&first) = slopes.first() {
+        let counts = slopes
+            .iter()
+            .map(|&slope| (slope, map.count_trees(slope)))
+            .collect::<Vec<_>>();
+        print!("{}", ranking_to_string(&counts, format));
+        printer.solve(1, || map.count_trees(first));
+        printer.solve(2, || map.multiply_trees(&slopes));
     }
 }
@@ -0,0 +1,286 @@
+//! Solution to the puzzle of day 4, _Passport Processing_.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+mod report;
+mod schema;
+
+pub use report::{diagnose, diagnostics_to_string, export_csv, export_jsonl, Problem};
+pub use schema::{
+    default_schema, parse_schema, parse_schema_input, parse_schema_source, Check, Field, Pattern,
+    Schema, SchemaError, DEFAULT_SCHEMA,
+};
+
+/// Checks if all the fields required by `schema` are present in a passport.
+pub fn validate_passport_part1(passport: &str, schema: &Schema) -> bool {
+    schema.is_complete(passport)
+}
+
+/// An error found while parsing the batch file.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the batch file into passports, separated by blank lines. Tokens
+/// that aren't `key:value` pairs are kept, for [`diagnose`] to report them.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<Vec<String>, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Vec<String>, ParseError> {
+    Ok(source.paragraphs().map(|x| x.to_owned()).collect())
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// Counts the passports that have all the required fields.
+pub fn count_valid_passports_part1(passports: &[String]) -> usize {
+    let schema = default_schema();
+    passports
+        .iter()
+        .filter(|x| validate_passport_part1(x, &schema))
+        .count()
+}
+
+/// Counts the passports that have all the required fields with valid
+/// values.
+pub fn count_valid_passports_part2(passports: &[String]) -> usize {
+    count_valid_documents(passports, &default_schema())
+}
+
+/// Counts the documents that have all the fields required by `schema`.
+pub fn count_complete_documents(documents: &[String], schema: &Schema) -> usize {
+    documents.iter().filter(|x| schema.is_complete(x)).count()
+}
+
+/// Counts the documents that are valid under `schema`.
+pub fn count_valid_documents(documents: &[String], schema: &Schema) -> usize {
+    documents.iter().filter(|x| schema.is_valid(x)).count()
+}
+
+/// The puzzle of day 4.
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Vec<String>;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, passports: &Self::Input) -> Answer {
+        count_valid_passports_part1(passports).into()
+    }
+
+    fn part2(&self, passports: &Self::Input) -> Answer {
+        count_valid_passports_part2(passports).into()
+    }
+}
+
+/// The fields of a passport, with the optional one last.
+const FIELDS: [&str; 8] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
+
+fn random_field(rng: &mut Rng, key: &str) -> String {
+    let valid = rng.ratio(3, 4);
+    let year = |rng: &mut Rng, low: usize, high: usize| {
+        if valid {
+            rng.range(low, high + 1).to_string()
+        } else {
+            let other = rng.range(1000, 10000);
+            rng.choose(&[low - 1, high + 1, other]).to_string()
+        }
+    };
+    let value = match key {
+        "byr" => year(rng, 1920, 2002),
+        "iyr" => year(rng, 2010, 2020),
+        "eyr" => year(rng, 2020, 2030),
+        "hgt" => match (valid, rng.ratio(1, 2)) {
+            (true, true) => format!("{}cm", rng.range(150, 194)),
+            (true, false) => format!("{}in", rng.range(59, 77)),
+            (false, true) => format!("{}cm", rng.choose(&[149, 194, 65])),
+            (false, false) => rng.choose(&["58in", "77in", "170", "170mm"]).to_string(),
+        },
+        "hcl" => {
+            let digits = "0123456789abcdef".chars().collect::<Vec<_>>();
+            let color = (0..6).map(|_| rng.choose(&digits)).collect::<String>();
+            match valid {
+                true => format!("#{}", color),
+                false => rng
+                    .choose(&[
+                        color.clone(),
+                        format!("#{}z", &color[1..]),
+                        format!("#{}", &color[1..]),
+                    ])
+                    .clone(),
+            }
+        }
+        "ecl" => match valid {
+            true => rng
+                .choose(&["amb", "blu", "brn", "gry", "grn", "hzl", "oth"])
+                .to_string(),
+            false => rng.choose(&["xry", "blue", "gr", "#123abc"]).to_string(),
+        },
+        "pid" => {
+            let len = if valid { 9 } else { *rng.choose(&[8, 10]) };
+            (0..len).map(|_| rng.range(0, 10).to_string()).collect()
+        }
+        _ => rng.range(1, 1000).to_string(),
+    };
+    format!("{}:{}", key, value)
+}
+
+impl Oracle for Day04 {
+    /// Generates `size` passports, each with most of the fields, and most
+    /// of them valid.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let mut passports = Vec::new();
+        for _ in 0..size {
+            let mut fields = Vec::new();
+            for key in FIELDS.iter() {
+                if rng.ratio(9, 10) {
+                    fields.push(random_field(rng, key));
+                }
+            }
+            rng.shuffle(&mut fields);
+            let mut passport = String::new();
+            for (i, field) in fields.iter().enumerate() {
+                if i > 0 {
+                    passport.push(if rng.ratio(1, 3) { '\n' } else { ' ' });
+                }
+                passport += field;
+            }
+            passports.push(passport);
+        }
+        passports.join("\n\n") + "\n"
+    }
+
+    fn oracle(&self, passports: &Self::Input, part: usize) -> Answer {
+        let in_range = |value: &str, digits: usize, low: u32, high: u32| {
+            value.len() == digits
+                && value.bytes().all(|b| b.is_ascii_digit())
+                && (low..=high).contains(&value.parse().unwrap())
+        };
+        let valid = |key: &str, value: &str| match key {
+            "byr" => in_range(value, 4, 1920, 2002),
+            "iyr" => in_range(value, 4, 2010, 2020),
+            "eyr" => in_range(value, 4, 2020, 2030),
+            "hgt" => match value.strip_suffix("cm") {
+                Some(cm) => in_range(cm, 3, 150, 193),
+                None => value
+                    .strip_suffix("in")
+                    .is_some_and(|x| in_range(x, 2, 59, 76)),
+            },
+            "hcl" => {
+                value.len() == 7
+                    && value.starts_with('#')
+                    && value[1..]
+                        .bytes()
+                        .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+            }
+            "ecl" => ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"].contains(&value),
+            "pid" => value.len() == 9 && value.bytes().all(|b| b.is_ascii_digit()),
+            _ => true,
+        };
+
+        let count = passports
+            .iter()
+            .filter(|passport| {
+                let fields = passport
+                    .split_whitespace()
+                    .filter_map(|x| x.split_once(':'))
+                    .collect::<HashMap<_, _>>();
+                FIELDS[..7].iter().all(|key| match fields.get(key) {
+                    Some(value) => part == 1 || valid(key, value),
+                    None => false,
+                })
+            })
+            .count();
+        count.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+    use std::fs;
+
+    #[test]
+    fn test_example_input() {
+        let passports = parse_input("example.txt").unwrap();
+        assert_eq!(count_valid_passports_part1(&passports), 2);
+        assert_eq!(count_valid_passports_part2(&passports), 2);
+        let schema = default_schema();
+        assert!(validate_passport_part1(&passports[0], &schema));
+        assert!(!validate_passport_part1(&passports[1], &schema));
+    }
+
+    #[test]
+    fn test_crlf_input() {
+        let content = fs::read_to_string("example.txt").unwrap();
+        let content = format!("\u{feff}{}\n\n", content).replace('\n', "\r\n");
+        let passports = parse(&content).unwrap();
+        assert_eq!(count_valid_passports_part1(&passports), 2);
+        assert_eq!(count_valid_passports_part2(&passports), 2);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day04, 20);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day04, 4, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let passports = Day04.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(Day04.part1(&passports), Answer::Number(2));
+        assert_eq!(Day04.part2(&passports), Answer::Number(2));
+    }
+
+    #[test]
+    fn test_malformed_field() {
+        let passports = parse("byr:1937 iyr2017 ecl:gry\n\npid:860033327\n").unwrap();
+        assert_eq!(passports[0], "byr:1937 iyr2017 ecl:gry");
+        let problems = diagnose(&passports[0], &default_schema());
+        assert_eq!(problems[0], Problem::Malformed("iyr2017".to_owned()));
+        assert_eq!(count_valid_passports_part1(&passports), 0);
+    }
+}
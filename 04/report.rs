@@ -0,0 +1,313 @@
+//! Diagnostics of the fields of the documents, and their export as JSON
+//! Lines or CSV.
+
+use std::fmt;
+
+use common::{csv_field, json_string, Format};
+
+use crate::Schema;
+
+/// A problem found with a field of a document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Problem {
+    /// A required field is missing.
+    Missing(String),
+    /// The value of a field doesn't pass the check of the schema, given as
+    /// in a schema file.
+    Invalid {
+        field: String,
+        value: String,
+        check: String,
+    },
+    /// A field isn't described in the schema.
+    Unknown(String),
+    /// A field appears more than once.
+    Duplicate(String),
+    /// A `key:value` token has an empty key or value.
+    Malformed(String),
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::Missing(field) => write!(f, "missing field `{}`", field),
+            Problem::Invalid {
+                field,
+                value,
+                check,
+            } => write!(f, "invalid {} `{}`, expected {}", field, value, check),
+            Problem::Unknown(field) => write!(f, "unknown field `{}`", field),
+            Problem::Duplicate(field) => write!(f, "duplicate field `{}`", field),
+            Problem::Malformed(token) => write!(f, "malformed field `{}`", token),
+        }
+    }
+}
+
+impl Problem {
+    /// Returns the kind of problem, like `missing`, and the field, value
+    /// and check involved, if any.
+    fn parts(&self) -> (&str, Option<&str>, Option<&str>, Option<&str>) {
+        match self {
+            Problem::Missing(field) => ("missing", Some(field), None, None),
+            Problem::Invalid {
+                field,
+                value,
+                check,
+            } => ("invalid", Some(field), Some(value), Some(check)),
+            Problem::Unknown(field) => ("unknown", Some(field), None, None),
+            Problem::Duplicate(field) => ("duplicate", Some(field), None, None),
+            Problem::Malformed(token) => ("malformed", None, Some(token), None),
+        }
+    }
+}
+
+/// Returns the well-formed fields of a document, keeping only the first of
+/// the duplicate ones.
+fn fields(document: &str) -> Vec<(&str, &str)> {
+    let mut fields: Vec<(&str, &str)> = Vec::new();
+    for (key, value) in document
+        .split_whitespace()
+        .filter_map(|token| token.split_once(':'))
+    {
+        if !key.is_empty() && !value.is_empty() && fields.iter().all(|&(x, _)| x != key) {
+            fields.push((key, value));
+        }
+    }
+    fields
+}
+
+/// Lists the problems of `document` under `schema`, in the order of its
+/// fields, followed by the missing ones in the order of the schema.
+pub fn diagnose(document: &str, schema: &Schema) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut seen = Vec::new();
+
+    for token in document.split_whitespace() {
+        let (key, value) = match token.split_once(':') {
+            Some((key, value)) if !key.is_empty() && !value.is_empty() => (key, value),
+            _ => {
+                problems.push(Problem::Malformed(token.to_owned()));
+                continue;
+            }
+        };
+        let first = !seen.contains(&key);
+        if first {
+            seen.push(key);
+        } else {
+            let duplicate = Problem::Duplicate(key.to_owned());
+            if !problems.contains(&duplicate) {
+                problems.push(duplicate);
+            }
+        }
+        match schema.fields.iter().find(|field| field.name == key) {
+            Some(field) if !field.check.matches(value) => problems.push(Problem::Invalid {
+                field: key.to_owned(),
+                value: value.to_owned(),
+                check: field.check.to_string(),
+            }),
+            Some(_) => (),
+            None if first => problems.push(Problem::Unknown(key.to_owned())),
+            None => (),
+        }
+    }
+
+    for field in &schema.fields {
+        if field.required && !seen.contains(&field.name.as_str()) {
+            problems.push(Problem::Missing(field.name.clone()));
+        }
+    }
+    problems
+}
+
+/// Formats the problems of every document, numbered from 1, as a
+/// `passport N: PROBLEM` line per problem, or as a record per problem with
+/// its kind, field, value and expected check: one JSON object per line,
+/// with `null` for what doesn't apply, or TSV with a header line and empty
+/// cells instead.
+pub fn diagnostics_to_string(documents: &[String], schema: &Schema, format: Format) -> String {
+    let mut s = match format {
+        Format::Tsv => String::from("passport\tkind\tfield\tvalue\texpected\n"),
+        _ => String::new(),
+    };
+    for (i, document) in documents.iter().enumerate() {
+        for problem in diagnose(document, schema) {
+            let (kind, field, value, check) = problem.parts();
+            s += &match format {
+                Format::Text => format!("passport {}: {}\n", i + 1, problem),
+                Format::Json => {
+                    let json = |x: Option<&str>| x.map_or_else(|| "null".to_owned(), json_string);
+                    format!(
+                        "{{\"passport\":{},\"kind\":\"{}\",\"field\":{},\"value\":{},\"expected\":{}}}\n",
+                        i + 1,
+                        kind,
+                        json(field),
+                        json(value),
+                        json(check)
+                    )
+                }
+                Format::Tsv => {
+                    let cell =
+                        |x: Option<&str>| x.unwrap_or_default().replace(&['\t', '\n'][..], " ");
+                    format!(
+                        "{}\t{}\t{}\t{}\t{}\n",
+                        i + 1,
+                        kind,
+                        cell(field),
+                        cell(value),
+                        cell(check)
+                    )
+                }
+            };
+        }
+    }
+    s
+}
+
+/// Formats the documents as JSON Lines, one object per document with its
+/// fields in order.
+pub fn export_jsonl(documents: &[String]) -> String {
+    let mut jsonl = String::new();
+    for document in documents {
+        let fields = fields(document)
+            .iter()
+            .map(|(key, value)| format!("{}:{}", json_string(key), json_string(value)))
+            .collect::<Vec<_>>();
+        jsonl += &format!("{{{}}}\n", fields.join(","));
+    }
+    jsonl
+}
+
+/// Formats the documents as CSV, with a header line and a column per
+/// field: those of `schema` first, and then the unknown ones in the order
+/// they are found. Missing fields are left empty.
+pub fn export_csv(documents: &[String], schema: &Schema) -> String {
+    let fields = documents.iter().map(|x| fields(x)).collect::<Vec<_>>();
+    let mut columns = schema
+        .fields
+        .iter()
+        .map(|field| field.name.as_str())
+        .collect::<Vec<_>>();
+    for &(key, _) in fields.iter().flatten() {
+        if !columns.contains(&key) {
+            columns.push(key);
+        }
+    }
+
+    let mut csv = columns
+        .iter()
+        .map(|x| csv_field(x))
+        .collect::<Vec<_>>()
+        .join(",")
+        + "\n";
+    for document in &fields {
+        let row = columns
+            .iter()
+            .map(|column| {
+                document
+                    .iter()
+                    .find(|(key, _)| key == column)
+                    .map_or_else(String::new, |(_, value)| csv_field(value))
+            })
+            .collect::<Vec<_>>();
+        csv += &row.join(",");
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{default_schema, parse_input};
+
+    #[test]
+    fn test_diagnose() {
+        let schema = default_schema();
+        let passports = parse_input("example.txt").unwrap();
+        assert_eq!(diagnose(&passports[0], &schema), vec![]);
+        assert_eq!(
+            diagnose(&passports[1], &schema),
+            vec![Problem::Missing("hgt".to_owned())]
+        );
+
+        let problems = diagnose("byr:1900 foo:1 byr:1990 :x hgt: pid:1 foo:2", &schema);
+        let problems = problems.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            problems,
+            vec![
+                "invalid byr `1900`, expected int 1920 2002",
+                "unknown field `foo`",
+                "duplicate field `byr`",
+                "malformed field `:x`",
+                "malformed field `hgt:`",
+                "invalid pid `1`, expected regex [0-9]{9}",
+                "duplicate field `foo`",
+                "missing field `iyr`",
+                "missing field `eyr`",
+                "missing field `hgt`",
+                "missing field `hcl`",
+                "missing field `ecl`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let schema = default_schema();
+        let documents = vec![
+            "byr:1900 iyr:2010 eyr:2020 hcl:#123abc ecl:brn pid:000000001 :x".to_owned(),
+            "hgt:59in foo:1".to_owned(),
+        ];
+        let text = diagnostics_to_string(&documents[..1], &schema, Format::Text);
+        assert_eq!(
+            text,
+            "passport 1: invalid byr `1900`, expected int 1920 2002\n\
+             passport 1: malformed field `:x`\n\
+             passport 1: missing field `hgt`\n"
+        );
+
+        let json = diagnostics_to_string(&documents, &schema, Format::Json);
+        let lines = json.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 10);
+        assert_eq!(
+            lines[0],
+            "{\"passport\":1,\"kind\":\"invalid\",\"field\":\"byr\",\"value\":\"1900\",\
+             \"expected\":\"int 1920 2002\"}"
+        );
+        assert_eq!(
+            lines[1],
+            "{\"passport\":1,\"kind\":\"malformed\",\"field\":null,\"value\":\":x\",\
+             \"expected\":null}"
+        );
+        assert_eq!(
+            lines[3],
+            "{\"passport\":2,\"kind\":\"unknown\",\"field\":\"foo\",\"value\":null,\
+             \"expected\":null}"
+        );
+
+        let tsv = diagnostics_to_string(&documents, &schema, Format::Tsv);
+        let lines = tsv.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "passport\tkind\tfield\tvalue\texpected");
+        assert_eq!(lines[3], "1\tmissing\thgt\t\t");
+        assert_eq!(lines.len(), 11);
+    }
+
+    #[test]
+    fn test_export() {
+        let documents = vec![
+            "pid:1 note:\"a,b\" byr:1990".to_owned(),
+            "byr:2000 byr:2001 eye:x".to_owned(),
+        ];
+        assert_eq!(
+            export_jsonl(&documents),
+            "{\"pid\":\"1\",\"note\":\"\\\"a,b\\\"\",\"byr\":\"1990\"}\n\
+             {\"byr\":\"2000\",\"eye\":\"x\"}\n"
+        );
+        assert_eq!(
+            export_csv(&documents, &default_schema()),
+            "byr,iyr,eyr,hgt,hcl,ecl,pid,cid,note,eye\n\
+             1990,,,,,,1,,\"\"\"a,b\"\"\",\n\
+             2000,,,,,,,,,x\n"
+        );
+    }
+}
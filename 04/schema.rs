@@ -0,0 +1,422 @@
+//! Schemas describing the fields of a document, and the checks on their
+//! values.
+//!
+//! A schema file has one field per line, with its name, whether it's
+//! `required` or `optional`, and the check of its value:
+//!
+//! ```text
+//! # Birth year, a number between 1920 and 2002.
+//! byr required int 1920 2002
+//! hgt required unit cm 150 193 in 59 76
+//! ecl required enum amb blu brn gry grn hzl oth
+//! pid required regex [0-9]{9}
+//! cid optional any
+//! ```
+//!
+//! The checks are:
+//!
+//! - `any`, accepting any value;
+//! - `int LOW HIGH`, a whole number between `LOW` and `HIGH`;
+//! - `unit UNIT LOW HIGH...`, a whole number followed by one of the units,
+//!   between the bounds of that unit;
+//! - `enum VALUE...`, one of the values;
+//! - `regex PATTERN`, a value matching the whole pattern, which takes the
+//!   rest of the line, in the syntax of the `regex` crate.
+//!
+//! Blank lines and lines starting with `#` are ignored.
+
+use std::fmt;
+use std::path::Path;
+
+use common::{Diagnostic, IoError, Location, Source};
+
+use regex::Regex;
+
+/// The schema of the passports, with the rules of the puzzle.
+pub const DEFAULT_SCHEMA: &str = include_str!("passport.schema");
+
+/// A check on the value of a field.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Check {
+    Any,
+    Int(i64, i64),
+    /// Units with the bounds of the number before them.
+    Unit(Vec<(String, i64, i64)>),
+    Enum(Vec<String>),
+    Regex(Pattern),
+}
+
+/// A regular expression that must match the whole value.
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    source: String,
+    regex: Regex,
+}
+
+impl Pattern {
+    /// Compiles `source`, returning a description of the problem if it's
+    /// invalid.
+    pub fn new(source: &str) -> Result<Self, String> {
+        let regex = Regex::new(&format!("^(?:{})$", source)).map_err(|err| match err {
+            // Keep the last line of the message, without the pattern.
+            regex::Error::Syntax(message) => message
+                .lines()
+                .last()
+                .unwrap_or_default()
+                .trim_start_matches("error: ")
+                .to_owned(),
+            err => err.to_string(),
+        })?;
+        Ok(Pattern {
+            source: source.to_owned(),
+            regex,
+        })
+    }
+
+    /// Returns the pattern as written in the schema.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Checks if the pattern matches the whole of `value`.
+    pub fn is_match(&self, value: &str) -> bool {
+        self.regex.is_match(value)
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+fn parse_int(value: &str) -> Option<i64> {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+impl Check {
+    /// Checks if `value` passes the check.
+    pub fn matches(&self, value: &str) -> bool {
+        let in_range = |value: &str, low: i64, high: i64| {
+            parse_int(value).is_some_and(|x| low <= x && x <= high)
+        };
+        match self {
+            Check::Any => true,
+            Check::Int(low, high) => in_range(value, *low, *high),
+            Check::Unit(units) => units.iter().any(|(unit, low, high)| {
+                value
+                    .strip_suffix(unit.as_str())
+                    .is_some_and(|x| in_range(x, *low, *high))
+            }),
+            Check::Enum(values) => values.iter().any(|x| x == value),
+            Check::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+impl fmt::Display for Check {
+    /// Formats the check as in a schema file.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Check::Any => write!(f, "any"),
+            Check::Int(low, high) => write!(f, "int {} {}", low, high),
+            Check::Unit(units) => {
+                write!(f, "unit")?;
+                for (unit, low, high) in units {
+                    write!(f, " {} {} {}", unit, low, high)?;
+                }
+                Ok(())
+            }
+            Check::Enum(values) => write!(f, "enum {}", values.join(" ")),
+            Check::Regex(regex) => write!(f, "regex {}", regex.as_str()),
+        }
+    }
+}
+
+/// The description of a field of a document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub required: bool,
+    pub check: Check,
+}
+
+/// The fields of a type of document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schema {
+    pub fields: Vec<Field>,
+}
+
+/// Returns the `key:value` fields of a document.
+fn fields(document: &str) -> impl Iterator<Item = (&str, &str)> {
+    document
+        .split_whitespace()
+        .filter_map(|field| field.split_once(':'))
+}
+
+impl Schema {
+    /// Checks if all the required fields are present in `document`.
+    pub fn is_complete(&self, document: &str) -> bool {
+        self.fields
+            .iter()
+            .filter(|field| field.required)
+            .all(|field| fields(document).any(|(key, _)| key == field.name))
+    }
+
+    /// Checks if all the required fields are present in `document`, and if
+    /// the values of all the fields it describes are valid. Fields missing
+    /// from the schema are ignored.
+    pub fn is_valid(&self, document: &str) -> bool {
+        self.is_complete(document)
+            && fields(document).all(|(key, value)| {
+                self.fields
+                    .iter()
+                    .find(|field| field.name == key)
+                    .is_none_or(|field| field.check.matches(value))
+            })
+    }
+}
+
+/// An error found while parsing a schema file.
+#[derive(Debug)]
+pub enum SchemaError {
+    /// The schema file couldn't be read.
+    Io(IoError),
+    /// A field is described more than once.
+    DuplicateField(Location),
+    /// A field is neither `required` nor `optional`.
+    InvalidRequirement(Location),
+    /// A check isn't one of the known ones.
+    UnknownCheck(Location),
+    /// A bound isn't a whole number.
+    InvalidNumber(Location),
+    /// A regex can't be compiled, for the given reason.
+    InvalidRegex(Location, String),
+    /// The line ends before a part of the field description.
+    MissingArgument(Location),
+    /// A token is left after the check.
+    UnexpectedToken(Location),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaError::Io(err) => write!(f, "{}", err),
+            SchemaError::DuplicateField(loc) => write!(f, "duplicate field `{}`", loc.token),
+            SchemaError::InvalidRequirement(loc) => {
+                write!(f, "invalid requirement `{}`", loc.token)
+            }
+            SchemaError::UnknownCheck(loc) => write!(f, "unknown check `{}`", loc.token),
+            SchemaError::InvalidNumber(loc) => write!(f, "invalid number `{}`", loc.token),
+            SchemaError::InvalidRegex(loc, reason) => {
+                write!(f, "invalid regex `{}`: {}", loc.token, reason)
+            }
+            SchemaError::MissingArgument(_) => write!(f, "missing argument"),
+            SchemaError::UnexpectedToken(loc) => write!(f, "unexpected `{}`", loc.token),
+        }
+    }
+}
+
+impl Diagnostic for SchemaError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            SchemaError::Io(_) => None,
+            SchemaError::DuplicateField(loc)
+            | SchemaError::InvalidRequirement(loc)
+            | SchemaError::UnknownCheck(loc)
+            | SchemaError::InvalidNumber(loc)
+            | SchemaError::InvalidRegex(loc, _)
+            | SchemaError::MissingArgument(loc)
+            | SchemaError::UnexpectedToken(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for SchemaError {
+    fn from(err: IoError) -> Self {
+        SchemaError::Io(err)
+    }
+}
+
+fn parse_field(source: &Source, line: &str) -> Result<Field, SchemaError> {
+    let mut tokens = line.split_whitespace();
+    let mut next = || {
+        tokens
+            .next()
+            .ok_or_else(|| SchemaError::MissingArgument(source.locate_end(line)))
+    };
+    let parse_number =
+        |x: &str| parse_int(x).ok_or_else(|| SchemaError::InvalidNumber(source.locate(x)));
+
+    let name = next()?.to_owned();
+    let required = match next()? {
+        "required" => true,
+        "optional" => false,
+        token => return Err(SchemaError::InvalidRequirement(source.locate(token))),
+    };
+    let check = match next()? {
+        "any" => Check::Any,
+        "int" => Check::Int(parse_number(next()?)?, parse_number(next()?)?),
+        "unit" => {
+            let mut units = Vec::new();
+            let mut unit = next()?;
+            loop {
+                let (low, high) = (parse_number(next()?)?, parse_number(next()?)?);
+                units.push((unit.to_owned(), low, high));
+                match next() {
+                    Ok(token) => unit = token,
+                    Err(_) => break,
+                }
+            }
+            Check::Unit(units)
+        }
+        "enum" => {
+            let mut values = vec![next()?.to_owned()];
+            while let Ok(value) = next() {
+                values.push(value.to_owned());
+            }
+            Check::Enum(values)
+        }
+        "regex" => {
+            let start = next()?;
+            let offset = start.as_ptr() as usize - line.as_ptr() as usize;
+            let pattern = line[offset..].trim_end();
+            while tokens.next().is_some() {}
+            let regex = Pattern::new(pattern)
+                .map_err(|reason| SchemaError::InvalidRegex(source.locate(pattern), reason))?;
+            Check::Regex(regex)
+        }
+        token => return Err(SchemaError::UnknownCheck(source.locate(token))),
+    };
+    if let Some(token) = tokens.next() {
+        return Err(SchemaError::UnexpectedToken(source.locate(token)));
+    }
+
+    Ok(Field {
+        name,
+        required,
+        check,
+    })
+}
+
+/// Parses a schema file, one field per line.
+pub fn parse_schema_input(file_name: impl AsRef<Path>) -> Result<Schema, SchemaError> {
+    parse_schema_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_schema_input`], but parsing an in-memory source.
+pub fn parse_schema_source(source: &Source) -> Result<Schema, SchemaError> {
+    let mut fields: Vec<Field> = Vec::new();
+    for line in source.content().lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let field = parse_field(source, line)?;
+        if fields.iter().any(|x| x.name == field.name) {
+            let name = trimmed.split_whitespace().next().unwrap_or(trimmed);
+            return Err(SchemaError::DuplicateField(source.locate(name)));
+        }
+        fields.push(field);
+    }
+    Ok(Schema { fields })
+}
+
+/// Same as [`parse_schema_input`], but parsing a string.
+pub fn parse_schema(input: &str) -> Result<Schema, SchemaError> {
+    parse_schema_source(&Source::from(input))
+}
+
+/// Returns the schema of the passports, with the rules of the puzzle.
+pub fn default_schema() -> Schema {
+    parse_schema_source(&Source::new("passport.schema", DEFAULT_SCHEMA))
+        .expect("the default schema is valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_schema() {
+        let schema = default_schema();
+        assert_eq!(schema.fields.len(), 8);
+        let check = |name: &str, value: &str| {
+            let field = schema.fields.iter().find(|x| x.name == name).unwrap();
+            field.check.matches(value)
+        };
+        assert!(check("byr", "2002"));
+        assert!(!check("byr", "2003"));
+        assert!(!check("byr", "+2000"));
+        assert!(check("hgt", "60in"));
+        assert!(check("hgt", "190cm"));
+        assert!(!check("hgt", "190in"));
+        assert!(!check("hgt", "190"));
+        assert!(check("hcl", "#123abc"));
+        assert!(!check("hcl", "#123abz"));
+        assert!(!check("hcl", "123abc"));
+        assert!(check("ecl", "brn"));
+        assert!(!check("ecl", "wat"));
+        assert!(check("pid", "000000001"));
+        assert!(!check("pid", "0123456789"));
+
+        let checks = schema
+            .fields
+            .iter()
+            .map(|x| format!("{} {}", x.name, x.check))
+            .collect::<Vec<_>>();
+        assert_eq!(checks[3], "hgt unit cm 150 193 in 59 76");
+        assert_eq!(checks[4], "hcl regex #[0-9a-f]{6}");
+        assert_eq!(checks[5], "ecl enum amb blu brn gry grn hzl oth");
+        assert_eq!(checks[7], "cid any");
+    }
+
+    #[test]
+    fn test_document() {
+        let schema = parse_schema("id required int 1 9\nname optional regex [a-z ]+\n").unwrap();
+        assert!(schema.is_valid("id:3"));
+        assert!(schema.is_valid("id:3 name:bob other:x"));
+        assert!(!schema.is_valid("id:3 name:Bob"));
+        assert!(schema.is_complete("id:30 name:Bob"));
+        assert!(!schema.is_complete("name:bob"));
+        let schema = parse_schema("dir required regex (north|south) pole\n").unwrap();
+        assert!(schema.fields[0].check.matches("south pole"));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let cases = [
+            ("byr required int 1920", "missing argument", (1, 22)),
+            ("byr required int 1920 x", "invalid number `x`", (1, 23)),
+            (
+                "byr mandatory any",
+                "invalid requirement `mandatory`",
+                (1, 5),
+            ),
+            ("byr required date", "unknown check `date`", (1, 14)),
+            ("hgt required unit cm 1 2 in 3", "missing argument", (1, 30)),
+            (
+                "pid required regex [0-9",
+                "invalid regex `[0-9`: unclosed character class",
+                (1, 20),
+            ),
+            ("cid optional any extra", "unexpected `extra`", (1, 18)),
+            (
+                "cid optional any\ncid required any",
+                "duplicate field `cid`",
+                (2, 1),
+            ),
+        ];
+        for (input, message, position) in cases.iter() {
+            let source = Source::new("test.schema", format!("# comment\n\n{}\n", input));
+            let err = parse_schema_source(&source).err().unwrap();
+            assert_eq!(err.to_string(), *message);
+            let loc = err.location().unwrap();
+            assert_eq!((loc.line - 2, loc.column), *position);
+        }
+    }
+}
@@ -0,0 +1,424 @@
+//! Solution to the puzzle of day 5, _Binary Boarding_.
+
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+mod seatmap;
+
+pub use seatmap::{RowStats, Seat, SeatMap};
+
+/// The number of rows of the plane of the puzzle.
+pub const ROWS: usize = 128;
+
+/// The number of columns of the plane of the puzzle.
+pub const COLS: usize = 8;
+
+/// The largest number of seats of a plane, as the seats are kept in memory
+/// one by one.
+pub const MAX_SEATS: usize = 1 << 24;
+
+/// A problem with a boarding pass.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PassError {
+    /// The boarding pass doesn't have as many characters as expected.
+    InvalidLength { expected: usize, found: usize },
+    /// The character at this index isn't `F` or `B`.
+    InvalidRow(usize),
+    /// The character at this index isn't `L` or `R`.
+    InvalidColumn(usize),
+}
+
+impl fmt::Display for PassError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PassError::InvalidLength { expected, found } => {
+                write!(f, "expected {} characters, found {}", expected, found)
+            }
+            PassError::InvalidRow(i) => write!(f, "invalid row character at {}", i),
+            PassError::InvalidColumn(i) => write!(f, "invalid column character at {}", i),
+        }
+    }
+}
+
+/// Encodes and decodes the boarding passes of a plane.
+///
+/// A boarding pass is the binary representation of its seat ID, with `F`
+/// and `B` for the bits of the row, and `L` and `R` for the bits of the
+/// column, so the numbers of rows and columns must be powers of two. The
+/// plane can't have more than [`MAX_SEATS`] seats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Codec {
+    rows: usize,
+    cols: usize,
+    row_bits: usize,
+    col_bits: usize,
+}
+
+impl Codec {
+    /// Creates the codec of a plane of `rows` by `cols` seats.
+    pub fn new(rows: usize, cols: usize) -> Result<Self, String> {
+        if !rows.is_power_of_two() || !cols.is_power_of_two() {
+            return Err(format!("{}x{} isn't a power of two", rows, cols));
+        }
+        if rows.checked_mul(cols).is_none_or(|x| x > MAX_SEATS) {
+            return Err(format!(
+                "{}x{} seats are too many, the maximum is {}",
+                rows, cols, MAX_SEATS
+            ));
+        }
+        Ok(Codec {
+            rows,
+            cols,
+            row_bits: rows.trailing_zeros() as usize,
+            col_bits: cols.trailing_zeros() as usize,
+        })
+    }
+
+    /// Returns the number of rows of the plane.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns of the plane.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the number of seats of the plane.
+    pub fn num_seats(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    /// Returns the number of characters of a boarding pass.
+    pub fn pass_len(&self) -> usize {
+        self.row_bits + self.col_bits
+    }
+
+    /// Returns the ID of the seat at `row` and `col`.
+    pub fn seat_id(&self, row: usize, col: usize) -> usize {
+        row * self.cols + col
+    }
+
+    /// Returns the boarding pass of the seat `id`, or `None` if there's no
+    /// such seat.
+    pub fn encode(&self, id: usize) -> Option<String> {
+        if id >= self.num_seats() {
+            return None;
+        }
+        let pass = (0..self.pass_len())
+            .rev()
+            .map(|bit| {
+                let upper = id >> bit & 1 == 1;
+                match (bit >= self.col_bits, upper) {
+                    (true, false) => 'F',
+                    (true, true) => 'B',
+                    (false, false) => 'L',
+                    (false, true) => 'R',
+                }
+            })
+            .collect();
+        Some(pass)
+    }
+
+    /// Returns the seat ID of a boarding pass.
+    pub fn decode(&self, pass: &str) -> Result<usize, PassError> {
+        let len = pass.chars().count();
+        if len != self.pass_len() {
+            return Err(PassError::InvalidLength {
+                expected: self.pass_len(),
+                found: len,
+            });
+        }
+
+        let mut id = 0;
+        for (i, c) in pass.chars().enumerate() {
+            let bit = match (i < self.row_bits, c) {
+                (true, 'F') | (false, 'L') => 0,
+                (true, 'B') | (false, 'R') => 1,
+                (true, _) => return Err(PassError::InvalidRow(i)),
+                (false, _) => return Err(PassError::InvalidColumn(i)),
+            };
+            id = id << 1 | bit;
+        }
+        Ok(id)
+    }
+}
+
+impl Default for Codec {
+    /// Returns the codec of the plane of the puzzle.
+    fn default() -> Self {
+        Codec::new(ROWS, COLS).unwrap()
+    }
+}
+
+/// An error found while parsing the boarding passes.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A boarding pass doesn't have the expected number of characters.
+    InvalidLength(Location),
+    /// A row character isn't `F` or `B`.
+    InvalidRow(Location),
+    /// A column character isn't `L` or `R`.
+    InvalidColumn(Location),
+    /// A boarding pass is for a seat taken by another one.
+    DuplicateSeat(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidLength(loc) => write!(f, "invalid boarding pass `{}`", loc.token),
+            ParseError::InvalidRow(loc) => write!(f, "invalid row character `{}`", loc.token),
+            ParseError::InvalidColumn(loc) => write!(f, "invalid column character `{}`", loc.token),
+            ParseError::DuplicateSeat(loc) => write!(f, "duplicate boarding pass `{}`", loc.token),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidLength(loc)
+            | ParseError::InvalidRow(loc)
+            | ParseError::InvalidColumn(loc)
+            | ParseError::DuplicateSeat(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the boarding passes into the occupancy of each seat, indexed
+/// by seat ID.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<Vec<bool>, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Vec<bool>, ParseError> {
+    parse_source_with(source, &Codec::default())
+}
+
+/// Same as [`parse_source`], but for the plane of `codec`.
+pub fn parse_source_with(source: &Source, codec: &Codec) -> Result<Vec<bool>, ParseError> {
+    let mut seats = vec![false; codec.num_seats()];
+
+    for line in source.content().lines() {
+        let char_at = |i: usize| {
+            let (start, c) = line.char_indices().nth(i).unwrap();
+            source.locate(&line[start..start + c.len_utf8()])
+        };
+        let id = codec.decode(line).map_err(|err| match err {
+            PassError::InvalidLength { .. } => ParseError::InvalidLength(source.locate(line)),
+            PassError::InvalidRow(i) => ParseError::InvalidRow(char_at(i)),
+            PassError::InvalidColumn(i) => ParseError::InvalidColumn(char_at(i)),
+        })?;
+        if seats[id] {
+            return Err(ParseError::DuplicateSeat(source.locate(line)));
+        }
+        seats[id] = true;
+    }
+
+    Ok(seats)
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Vec<bool>, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// Returns the highest seat ID in use, or `None` if every seat is free.
+pub fn find_highest_seat(seats: &[bool]) -> Option<usize> {
+    seats.iter().rposition(|&x| x)
+}
+
+/// Returns the ID of the first free seat after the first occupied one, or
+/// `None` if there's no such seat.
+pub fn find_free_seat(seats: &[bool]) -> Option<usize> {
+    let first_occupied = seats.iter().position(|&x| x)?;
+    let first_free = seats.iter().skip(first_occupied).position(|&x| !x)?;
+    Some(first_occupied + first_free)
+}
+
+/// Returns the highest seat ID and the ID of the free seat, if any.
+pub fn solve(file_name: impl AsRef<Path>) -> Result<(Option<usize>, Option<usize>), ParseError> {
+    let seats = parse_input(file_name)?;
+    Ok((find_highest_seat(&seats), find_free_seat(&seats)))
+}
+
+/// The puzzle of day 5.
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = Vec<bool>;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, seats: &Self::Input) -> Answer {
+        find_highest_seat(seats).into()
+    }
+
+    fn part2(&self, seats: &Self::Input) -> Answer {
+        find_free_seat(seats).into()
+    }
+}
+
+impl Oracle for Day05 {
+    /// Generates the boarding passes of `size` consecutive seats but one.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let len = size.clamp(3, ROWS * COLS);
+        let first = rng.range(0, ROWS * COLS - len + 1);
+        let free = rng.range(first + 1, first + len - 1);
+        let mut ids = (first..first + len)
+            .filter(|&x| x != free)
+            .collect::<Vec<_>>();
+        rng.shuffle(&mut ids);
+
+        let mut input = String::new();
+        for id in ids {
+            for bit in (0..10).rev() {
+                let upper = id >> bit & 1 == 1;
+                input.push(match (bit >= 3, upper) {
+                    (true, false) => 'F',
+                    (true, true) => 'B',
+                    (false, false) => 'L',
+                    (false, true) => 'R',
+                });
+            }
+            input.push('\n');
+        }
+        input
+    }
+
+    fn oracle(&self, seats: &Self::Input, part: usize) -> Answer {
+        let ids = (0..seats.len()).filter(|&id| seats[id]);
+        if part == 1 {
+            ids.max().into()
+        } else {
+            (1..seats.len() - 1)
+                .find(|&id| !seats[id] && seats[id - 1] && seats[id + 1])
+                .into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input() {
+        assert_eq!(solve("example.txt").unwrap(), (Some(357), Some(358)));
+    }
+
+    #[test]
+    fn test_unsolved() {
+        let seats = parse("").unwrap();
+        assert_eq!(Day05.part1(&seats), Answer::Unsolved);
+        assert_eq!(Day05.part2(&seats), Answer::Unsolved);
+
+        let codec = Codec::new(2, 2).unwrap();
+        let seats = parse_source_with(&Source::from("BR\nBL\n"), &codec).unwrap();
+        assert_eq!(find_highest_seat(&seats), Some(3));
+        assert_eq!(find_free_seat(&seats), None);
+    }
+
+    #[test]
+    fn test_codec() {
+        let codec = Codec::default();
+        assert_eq!(codec.decode("FBFBBFFRLR"), Ok(357));
+        assert_eq!(codec.encode(357), Some("FBFBBFFRLR".to_owned()));
+        assert_eq!(codec.encode(1024), None);
+        assert_eq!(codec.seat_id(44, 5), 357);
+        for id in 0..codec.num_seats() {
+            assert_eq!(codec.decode(&codec.encode(id).unwrap()), Ok(id));
+        }
+
+        let codec = Codec::new(4, 1).unwrap();
+        assert_eq!(codec.encode(2), Some("BF".to_owned()));
+        assert_eq!(codec.decode("FB"), Ok(1));
+        let codec = Codec::new(2, 16).unwrap();
+        assert_eq!(codec.encode(17), Some("BLLLR".to_owned()));
+        assert_eq!(codec.decode("BRLRL"), Ok(26));
+        assert_eq!(
+            codec.decode("FRLRLL"),
+            Err(PassError::InvalidLength {
+                expected: 5,
+                found: 6
+            })
+        );
+        assert_eq!(codec.decode("RRLRL"), Err(PassError::InvalidRow(0)));
+        assert_eq!(codec.decode("FRLBL"), Err(PassError::InvalidColumn(3)));
+        assert!(Codec::new(100, 8).is_err());
+        assert!(Codec::new(128, 0).is_err());
+        assert!(Codec::new(1 << 12, 1 << 12).is_ok());
+        assert_eq!(
+            Codec::new(1 << 30, 1 << 30),
+            Err("1073741824x1073741824 seats are too many, the maximum is 16777216".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_duplicate_seat() {
+        let source = Source::new("test.txt", "FBFBBFFRLR\nBBFFBBFRLL\nFBFBBFFRLR\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "duplicate boarding pass `FBFBBFFRLR`");
+        assert_eq!(err.location().unwrap().line, 3);
+
+        let source = Source::new("test.txt", "BLR\nFRR\nFÄR\n");
+        let seats = parse_source_with(
+            &Source::new("test.txt", "BLR\nFRR\n"),
+            &Codec::new(2, 4).unwrap(),
+        );
+        assert_eq!(
+            seats.unwrap(),
+            [false, false, false, true, false, true, false, false]
+        );
+        let err = parse_source_with(&source, &Codec::new(2, 4).unwrap())
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "invalid column character `Ä`");
+        assert_eq!(err.location().unwrap().column, 2);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day05, 100);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day05, 5, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let seats = Day05.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(Day05.part1(&seats), Answer::Number(357));
+        assert_eq!(Day05.part2(&seats), Answer::Number(358));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "FBFBBFFRLR\nFBFBBFFRLX\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid column character `X`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (2, 10));
+    }
+}
@@ -0,0 +1,174 @@
+//! The map of the seats of the plane, with its free seats and the statistics
+//! of each row.
+
+use std::fmt;
+
+use crate::Codec;
+
+/// The state of a seat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Seat {
+    /// The seat of a boarding pass.
+    Occupied,
+    /// A seat without boarding pass.
+    Free,
+    /// A seat in the rows at the very front or back, before the first or
+    /// after the last occupied row, which don't exist on the plane.
+    Missing,
+}
+
+impl Seat {
+    /// Returns the character drawing the seat.
+    pub fn symbol(self) -> char {
+        match self {
+            Seat::Occupied => '#',
+            Seat::Free => '.',
+            Seat::Missing => '-',
+        }
+    }
+}
+
+/// The statistics of a row of seats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RowStats {
+    pub row: usize,
+    pub occupied: usize,
+    pub free: usize,
+    pub missing: bool,
+}
+
+impl fmt::Display for RowStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.missing {
+            write!(f, "row {}: missing", self.row)
+        } else {
+            write!(
+                f,
+                "row {}: {} occupied, {} free",
+                self.row, self.occupied, self.free
+            )
+        }
+    }
+}
+
+/// The seats of the plane, row by row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SeatMap {
+    cols: usize,
+    seats: Vec<Seat>,
+}
+
+impl SeatMap {
+    /// Creates the map of the occupied `seats` of the plane of `codec`,
+    /// indexed by seat ID.
+    pub fn new(seats: &[bool], codec: &Codec) -> Self {
+        assert_eq!(seats.len(), codec.num_seats());
+        let cols = codec.cols();
+        let first_row = seats.iter().position(|&x| x).map_or(0, |id| id / cols);
+        let end_row = seats.iter().rposition(|&x| x).map_or(0, |id| id / cols + 1);
+        let seats = seats
+            .iter()
+            .enumerate()
+            .map(|(id, &occupied)| {
+                if occupied {
+                    Seat::Occupied
+                } else if (first_row..end_row).contains(&(id / cols)) {
+                    Seat::Free
+                } else {
+                    Seat::Missing
+                }
+            })
+            .collect();
+        SeatMap { cols, seats }
+    }
+
+    /// Returns the state of the seat `id`.
+    pub fn seat(&self, id: usize) -> Seat {
+        self.seats[id]
+    }
+
+    /// Returns the seats of each row.
+    pub fn rows(&self) -> impl Iterator<Item = &[Seat]> {
+        self.seats.chunks(self.cols)
+    }
+
+    /// Returns the free seats whose neighbours, the seats with the previous
+    /// and next IDs, are both occupied.
+    pub fn gaps(&self) -> Vec<usize> {
+        (1..self.seats.len().saturating_sub(1))
+            .filter(|&id| {
+                self.seats[id] == Seat::Free
+                    && self.seats[id - 1] == Seat::Occupied
+                    && self.seats[id + 1] == Seat::Occupied
+            })
+            .collect()
+    }
+
+    /// Returns the statistics of each row.
+    pub fn row_stats(&self) -> Vec<RowStats> {
+        self.rows()
+            .enumerate()
+            .map(|(row, seats)| RowStats {
+                row,
+                occupied: seats.iter().filter(|&&x| x == Seat::Occupied).count(),
+                free: seats.iter().filter(|&&x| x == Seat::Free).count(),
+                missing: seats.iter().all(|&x| x == Seat::Missing),
+            })
+            .collect()
+    }
+
+    /// Draws the map, a line per row starting with the row number, with `#`
+    /// for the occupied seats, `.` for the free ones and `-` for the missing
+    /// ones.
+    pub fn render(&self) -> String {
+        let nrows = self.seats.len() / self.cols;
+        let width = (nrows.max(1) - 1).to_string().len();
+        let mut s = String::new();
+        for (row, seats) in self.rows().enumerate() {
+            let line = seats.iter().map(|x| x.symbol()).collect::<String>();
+            s += &format!("{:>width$} {}\n", row, line, width = width);
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seat_map(occupied: &[usize]) -> SeatMap {
+        let codec = Codec::new(8, 4).unwrap();
+        let mut seats = vec![false; codec.num_seats()];
+        for &id in occupied {
+            seats[id] = true;
+        }
+        SeatMap::new(&seats, &codec)
+    }
+
+    #[test]
+    fn test_seat_map() {
+        let map = seat_map(&[5, 6, 8, 9, 11, 12, 13, 15, 17, 18, 20]);
+        assert_eq!(map.seat(0), Seat::Missing);
+        assert_eq!(map.seat(4), Seat::Free);
+        assert_eq!(map.seat(5), Seat::Occupied);
+        assert_eq!(map.seat(24), Seat::Missing);
+        assert_eq!(map.gaps(), [7, 10, 14, 16, 19]);
+        assert_eq!(
+            map.render(),
+            "0 ----\n1 .##.\n2 ##.#\n3 ##.#\n4 .##.\n5 #...\n6 ----\n7 ----\n"
+        );
+
+        let stats = map.row_stats();
+        assert_eq!(stats.len(), 8);
+        assert_eq!(stats[0].to_string(), "row 0: missing");
+        assert_eq!(stats[2].to_string(), "row 2: 3 occupied, 1 free");
+        assert_eq!(stats[5].to_string(), "row 5: 1 occupied, 3 free");
+    }
+
+    #[test]
+    fn test_empty_seat_map() {
+        let map = seat_map(&[]);
+        assert!(map.gaps().is_empty());
+        assert!(map.row_stats().iter().all(|x| x.missing));
+    }
+}
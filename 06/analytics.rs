@@ -0,0 +1,227 @@
+//! The answers of the groups, for questions from any alphabet, and the
+//! queries aggregating them.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The questions the answers may be.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alphabet {
+    /// The allowed questions, or `None` for any non-whitespace character.
+    questions: Option<BTreeSet<char>>,
+}
+
+impl Alphabet {
+    /// Creates an alphabet of the given questions.
+    pub fn new(questions: impl IntoIterator<Item = char>) -> Self {
+        Alphabet {
+            questions: Some(questions.into_iter().collect()),
+        }
+    }
+
+    /// Creates an alphabet of any non-whitespace character.
+    pub fn any() -> Self {
+        Alphabet { questions: None }
+    }
+
+    /// Checks if `c` is a question of the alphabet.
+    pub fn contains(&self, c: char) -> bool {
+        match &self.questions {
+            Some(questions) => questions.contains(&c),
+            None => !c.is_whitespace(),
+        }
+    }
+}
+
+impl Default for Alphabet {
+    /// Returns the alphabet of the puzzle, from `a` to `z`.
+    fn default() -> Self {
+        Alphabet::new('a'..='z')
+    }
+}
+
+/// The answers of a group, as the number of people answering each
+/// question.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Group {
+    people: usize,
+    counts: BTreeMap<char, usize>,
+}
+
+impl Group {
+    /// Creates the group of the answers of each person, one per line. A
+    /// person repeating a question answers it once.
+    pub fn new(answers: &str) -> Self {
+        let mut group = Group::default();
+        for person in answers.lines() {
+            let questions = person
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect::<BTreeSet<_>>();
+            for question in questions {
+                *group.counts.entry(question).or_insert(0) += 1;
+            }
+            group.people += 1;
+        }
+        group
+    }
+
+    /// Returns the number of people of the group.
+    pub fn people(&self) -> usize {
+        self.people
+    }
+
+    /// Returns the number of people answering `question`.
+    pub fn count(&self, question: char) -> usize {
+        self.counts.get(&question).copied().unwrap_or(0)
+    }
+
+    /// Returns the questions answered, with the number of people answering
+    /// each of them.
+    pub fn counts(&self) -> impl Iterator<Item = (char, usize)> + '_ {
+        self.counts
+            .iter()
+            .map(|(&question, &count)| (question, count))
+    }
+
+    /// Returns the number of questions answered by at least `k` people.
+    pub fn answered_by_at_least(&self, k: usize) -> usize {
+        self.counts.values().filter(|&&x| x >= k).count()
+    }
+
+    /// Returns the number of questions answered by exactly `k` people.
+    pub fn answered_by_exactly(&self, k: usize) -> usize {
+        self.counts.values().filter(|&&x| x == k).count()
+    }
+
+    /// Returns the number of questions answered by anyone.
+    pub fn answered_by_anyone(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns the number of questions answered by everyone.
+    pub fn answered_by_everyone(&self) -> usize {
+        self.answered_by_exactly(self.people)
+    }
+}
+
+/// The popularity of a question across the groups.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Popularity {
+    pub question: char,
+    /// The number of people answering the question.
+    pub people: usize,
+    /// The number of groups where someone answered the question.
+    pub groups: usize,
+    /// The number of groups where everyone answered the question.
+    pub unanimous: usize,
+}
+
+/// Returns the popularity of every question answered, by question.
+pub fn popularity(groups: &[Group]) -> Vec<Popularity> {
+    let mut popularity = BTreeMap::new();
+    for group in groups {
+        for (question, count) in group.counts() {
+            let entry = popularity.entry(question).or_insert(Popularity {
+                question,
+                people: 0,
+                groups: 0,
+                unanimous: 0,
+            });
+            entry.people += count;
+            entry.groups += 1;
+            if count == group.people {
+                entry.unanimous += 1;
+            }
+        }
+    }
+    popularity.into_values().collect()
+}
+
+/// Returns the consensus of a group, as the number of questions answered
+/// by everyone and the number of questions answered by anyone.
+pub fn consensus(group: &Group) -> (usize, usize) {
+    (group.answered_by_everyone(), group.answered_by_anyone())
+}
+
+/// Returns the indices of the groups with the highest consensus, if
+/// `most`, or the lowest one. Groups answering nothing are left out.
+pub fn rank_consensus(groups: &[Group], most: bool) -> Vec<usize> {
+    let mut best: Option<(usize, usize)> = None;
+    let mut indices = Vec::new();
+    for (i, group) in groups.iter().enumerate() {
+        let (agreed, answered) = consensus(group);
+        if answered == 0 {
+            continue;
+        }
+        // Compare the ratios `agreed / answered` without dividing.
+        let ordering = best.map(|(x, y)| (agreed * y).cmp(&(x * answered)));
+        match ordering {
+            Some(ordering) if ordering.is_eq() => indices.push(i),
+            Some(ordering) if ordering.is_gt() != most => {}
+            _ => {
+                best = Some((agreed, answered));
+                indices = vec![i];
+            }
+        }
+    }
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group() {
+        let group = Group::new("añb\nñé\nññ é\n");
+        assert_eq!(group.people(), 3);
+        assert_eq!(group.count('ñ'), 3);
+        assert_eq!(group.count('z'), 0);
+        assert_eq!(group.answered_by_anyone(), 4);
+        assert_eq!(group.answered_by_everyone(), 1);
+        assert_eq!(group.answered_by_at_least(2), 2);
+        assert_eq!(group.answered_by_exactly(1), 2);
+    }
+
+    #[test]
+    fn test_popularity() {
+        let groups = ["ab\nb", "b\nc", "a"]
+            .iter()
+            .map(|x| Group::new(x))
+            .collect::<Vec<_>>();
+        let popularity = popularity(&groups);
+        assert_eq!(popularity.len(), 3);
+        assert_eq!(
+            popularity[0],
+            Popularity {
+                question: 'a',
+                people: 2,
+                groups: 2,
+                unanimous: 1
+            }
+        );
+        assert_eq!((popularity[1].people, popularity[1].unanimous), (3, 1));
+        assert_eq!((popularity[2].question, popularity[2].groups), ('c', 1));
+    }
+
+    #[test]
+    fn test_rank_consensus() {
+        let groups = ["abc", "ab\nbc", "a\nb", "", "xy\nyx"]
+            .iter()
+            .map(|x| Group::new(x))
+            .collect::<Vec<_>>();
+        assert_eq!(consensus(&groups[1]), (1, 3));
+        assert_eq!(rank_consensus(&groups, true), [0, 4]);
+        assert_eq!(rank_consensus(&groups, false), [2]);
+        assert!(rank_consensus(&[], true).is_empty());
+    }
+
+    #[test]
+    fn test_alphabet() {
+        assert!(Alphabet::default().contains('q'));
+        assert!(!Alphabet::default().contains('Q'));
+        assert!(Alphabet::new("αβγ".chars()).contains('β'));
+        assert!(Alphabet::any().contains('✓'));
+        assert!(!Alphabet::any().contains(' '));
+    }
+}
@@ -0,0 +1,234 @@
+//! Solution to the puzzle of day 6, _Custom Customs_.
+
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+mod analytics;
+
+pub use analytics::{consensus, popularity, rank_consensus, Alphabet, Group, Popularity};
+
+/// An error found while parsing the answers.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// An answer isn't a question of the alphabet.
+    InvalidAnswer(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidAnswer(loc) => write!(f, "invalid answer `{}`", loc.token),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidAnswer(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the answers of each group, separated by blank lines.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<Vec<String>, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Vec<String>, ParseError> {
+    parse_source_with(source, &Alphabet::default())
+}
+
+/// Same as [`parse_source`], but for the questions of `alphabet`.
+pub fn parse_source_with(source: &Source, alphabet: &Alphabet) -> Result<Vec<String>, ParseError> {
+    let content = source.content();
+    let invalid = content
+        .char_indices()
+        .find(|&(_, c)| !alphabet.contains(c) && !c.is_whitespace());
+    if let Some((i, c)) = invalid {
+        let answer = &content[i..i + c.len_utf8()];
+        return Err(ParseError::InvalidAnswer(source.locate(answer)));
+    }
+
+    Ok(source.paragraphs().map(|x| x.to_owned()).collect())
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// Returns the answers of each group.
+pub fn groups(groups: &[String]) -> Vec<Group> {
+    groups.iter().map(|x| Group::new(x)).collect()
+}
+
+/// Sums the number of questions answered by anyone in each group.
+pub fn count_answered_part1(groups: &[String]) -> usize {
+    count_answered_by_at_least(groups, 1)
+}
+
+/// Sums the number of questions answered by everyone in each group.
+pub fn count_answered_part2(groups: &[String]) -> usize {
+    groups
+        .iter()
+        .map(|x| Group::new(x).answered_by_everyone())
+        .sum()
+}
+
+/// Sums the number of questions answered by at least `k` people in each
+/// group.
+pub fn count_answered_by_at_least(groups: &[String], k: usize) -> usize {
+    groups
+        .iter()
+        .map(|x| Group::new(x).answered_by_at_least(k))
+        .sum()
+}
+
+/// Sums the number of questions answered by exactly `k` people in each
+/// group.
+pub fn count_answered_by_exactly(groups: &[String], k: usize) -> usize {
+    groups
+        .iter()
+        .map(|x| Group::new(x).answered_by_exactly(k))
+        .sum()
+}
+
+/// The puzzle of day 6.
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Vec<String>;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, groups: &Self::Input) -> Answer {
+        count_answered_part1(groups).into()
+    }
+
+    fn part2(&self, groups: &Self::Input) -> Answer {
+        count_answered_part2(groups).into()
+    }
+}
+
+impl Oracle for Day06 {
+    /// Generates `size` groups of up to 5 people, each answering "yes" to
+    /// distinct questions.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let mut questions = ('a'..='z').collect::<Vec<_>>();
+        let mut groups = Vec::new();
+        for _ in 0..size {
+            let mut group = String::new();
+            for _ in 0..rng.range(1, 6) {
+                rng.shuffle(&mut questions);
+                group.extend(&questions[..rng.range(1, questions.len() + 1)]);
+                group.push('\n');
+            }
+            groups.push(group);
+        }
+        groups.join("\n")
+    }
+
+    fn oracle(&self, groups: &Self::Input, part: usize) -> Answer {
+        let mut count = 0;
+        for group in groups {
+            let people = group.lines().collect::<Vec<_>>();
+            for question in 'a'..='z' {
+                let answered = people.iter().filter(|x| x.contains(question)).count();
+                if (part == 1 && answered > 0) || (part == 2 && answered == people.len()) {
+                    count += 1;
+                }
+            }
+        }
+        count.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+    use std::fs;
+
+    #[test]
+    fn test_example_input() {
+        let groups = parse_input("example.txt").unwrap();
+        assert_eq!(count_answered_part1(&groups), 11);
+        assert_eq!(count_answered_part2(&groups), 6);
+    }
+
+    #[test]
+    fn test_crlf_input() {
+        let content = fs::read_to_string("example.txt").unwrap();
+        let content = format!("\u{feff}{}\n\n", content).replace('\n', "\r\n");
+        let groups = parse(&content).unwrap();
+        assert_eq!(count_answered_part1(&groups), 11);
+        assert_eq!(count_answered_part2(&groups), 6);
+    }
+
+    #[test]
+    fn test_aggregates() {
+        let groups = parse_input("example.txt").unwrap();
+        assert_eq!(count_answered_by_at_least(&groups, 2), 2);
+        assert_eq!(count_answered_by_exactly(&groups, 1), 9);
+        assert_eq!(count_answered_by_exactly(&groups, 4), 1);
+    }
+
+    #[test]
+    fn test_unicode_input() {
+        let source = Source::new("test.txt", "αβ\nβγ\n\nδ\n\n✓\n");
+        let groups = parse_source_with(&source, &Alphabet::any()).unwrap();
+        assert_eq!(count_answered_part1(&groups), 5);
+        assert_eq!(count_answered_part2(&groups), 3);
+
+        let err = parse_source_with(&source, &Alphabet::new("αβγδ".chars()))
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "invalid answer `✓`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (6, 1));
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day06, 20);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day06, 6, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let groups = Day06.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(Day06.part1(&groups), Answer::Number(11));
+        assert_eq!(Day06.part2(&groups), Answer::Number(6));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "abc\n\na\nB\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid answer `B`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (4, 1));
+    }
+}
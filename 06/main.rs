@@ -1,84 +1,89 @@
-use std::env;
-use std::fs;
-use std::path::Path;
 use std::process;
 
-const NUM_QUESTIONS: usize = 26;
-
-// NB: another way to tackle this problem would be to use set union and
-// set intersection to solve part 1 and part 2. However, as the max size
-// of the results is fixed (26), it does not provide advantages.
-
-fn char_index(c: char) -> usize {
-    c as usize - 'a' as usize
-}
-
-fn count_group_answers_part1(group_answers: &str) -> usize {
-    let mut results = [false; NUM_QUESTIONS];
-
-    group_answers
-        .chars()
-        .filter(|c| !c.is_whitespace())
-        .for_each(|answer| results[char_index(answer)] = true);
-
-    results.iter().filter(|&&r| r).count()
-}
-
-fn count_group_answers_part2(group_answers: &str) -> usize {
-    let mut results = [0; NUM_QUESTIONS];
-
-    group_answers
-        .lines()
-        .flat_map(|x| x.chars())
-        .for_each(|answer| results[char_index(answer)] += 1);
-
-    results
-        .iter()
-        .filter(|&&r| r == group_answers.lines().count())
-        .count()
-}
-
-fn count_answered_part1(file_name: impl AsRef<Path>) -> usize {
-    fs::read_to_string(file_name)
-        .unwrap()
-        .split("\n\n")
-        .map(|x| count_group_answers_part1(x))
-        .sum()
-}
-
-fn count_answered_part2(file_name: impl AsRef<Path>) -> usize {
-    fs::read_to_string(file_name)
-        .unwrap()
-        .split("\n\n")
-        .map(|x| count_group_answers_part2(x))
-        .sum()
-}
+use common::{or_exit, parse_args, take_flag, take_option, Printer, Source};
+use day06::{
+    count_answered_by_at_least, count_answered_by_exactly, count_answered_part1,
+    count_answered_part2, groups, parse_source_with, popularity, rank_consensus, Alphabet,
+};
 
 fn main() {
-    if env::args().count() != 2 {
-        eprintln!("USAGE: {} FILE", env::args().next().unwrap());
-        process::exit(1);
+    let (mut args, format) = parse_args(&[
+        "FILE",
+        "[--alphabet CHARS]",
+        "[--unicode]",
+        "[--at-least K]",
+        "[--exactly K]",
+        "[--popularity]",
+        "[--consensus]",
+    ]);
+    let options = ["--alphabet", "--at-least", "--exactly"]
+        .iter()
+        .map(|name| take_option(&mut args, name))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        });
+    let unicode = take_flag(&mut args, "--unicode");
+    let show_popularity = take_flag(&mut args, "--popularity");
+    let show_consensus = take_flag(&mut args, "--consensus");
+    let count = |k: &Option<String>| {
+        k.as_ref().map(|k| {
+            k.parse::<usize>().unwrap_or_else(|_| {
+                eprintln!("error: invalid number of people: {}", k);
+                process::exit(1);
+            })
+        })
+    };
+    let (at_least, exactly) = (count(&options[1]), count(&options[2]));
+
+    let alphabet = match &options[0] {
+        Some(chars) => Alphabet::new(chars.chars()),
+        None if unicode => Alphabet::any(),
+        None => Alphabet::default(),
+    };
+    let answers = or_exit(parse_source_with(
+        &or_exit(Source::read(&args[0])),
+        &alphabet,
+    ));
+
+    if at_least.is_none() && exactly.is_none() && !show_popularity && !show_consensus {
+        let mut printer = Printer::new(6, format);
+        printer.solve(1, || count_answered_part1(&answers));
+        printer.solve(2, || count_answered_part2(&answers));
+        return;
     }
 
-    let part1 = count_answered_part1(env::args().nth(1).unwrap());
-    let part2 = count_answered_part2(env::args().nth(1).unwrap());
-    println!("Result (Part 1): {}", part1);
-    println!("Result (Part 2): {}", part2);
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_example_input() {
-        assert_eq!(count_answered_part1("example.txt"), 11);
-        assert_eq!(count_answered_part2("example.txt"), 6);
+    if let Some(k) = at_least {
+        let count = count_answered_by_at_least(&answers, k);
+        println!("answered by at least {}: {}", k, count);
     }
-
-    #[test]
-    fn test_puzzle_input() {
-        assert_eq!(count_answered_part1("input.txt"), 6534);
-        assert_eq!(count_answered_part2("input.txt"), 3402);
+    if let Some(k) = exactly {
+        let count = count_answered_by_exactly(&answers, k);
+        println!("answered by exactly {}: {}", k, count);
+    }
+    let groups = groups(&answers);
+    if show_popularity {
+        for x in popularity(&groups) {
+            println!(
+                "{}: {} people, {} groups, {} unanimous",
+                x.question, x.people, x.groups, x.unanimous
+            );
+        }
+    }
+    if show_consensus {
+        for (label, most) in [("most", true), ("least", false)].iter() {
+            for i in rank_consensus(&groups, *most) {
+                let agreed = groups[i].answered_by_everyone();
+                let answered = groups[i].answered_by_anyone();
+                println!(
+                    "{} consensus: group {} ({}/{})",
+                    label,
+                    i + 1,
+                    agreed,
+                    answered
+                );
+            }
+        }
     }
 }
@@ -0,0 +1,175 @@
+//! The export of the containment graph of the rules, as Graphviz DOT or as
+//! JSON adjacency lists.
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use common::json_string;
+
+use crate::Bags;
+
+/// The part of the graph to export, relative to a bag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scope {
+    /// Every bag.
+    All,
+    /// The bag and the bags that can eventually contain it.
+    Ancestors,
+    /// The bag and the bags it eventually contains.
+    Descendants,
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Scope::All),
+            "ancestors" => Ok(Scope::Ancestors),
+            "descendants" => Ok(Scope::Descendants),
+            _ => Err(format!("invalid scope: {}", s)),
+        }
+    }
+}
+
+/// Returns the bags in the scope of `bag`, sorted.
+fn nodes<'a>(bags: &'a Bags, bag: &'a str, scope: Scope) -> BTreeSet<&'a str> {
+    let mut nodes = match scope {
+        Scope::All => return bags.colors().into_iter().collect(),
+        Scope::Ancestors => bags.ancestors(bag),
+        Scope::Descendants => bags.descendants(bag),
+    };
+    nodes.insert(bag);
+    nodes
+}
+
+/// Returns the bags `bag` directly contains within `nodes`.
+fn edges<'a>(bags: &'a Bags, bag: &str, nodes: &BTreeSet<&str>) -> Vec<(&'a str, usize)> {
+    let mut edges = bags.inner_bags(bag);
+    edges.retain(|(inner_bag, _)| nodes.contains(inner_bag));
+    edges
+}
+
+/// Quotes `s` as a DOT identifier.
+fn dot_id(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Exports the rules in the scope of `bag` as a Graphviz digraph, with an
+/// edge from each bag to the bags it contains, labelled with their number.
+/// The node of `bag` is highlighted.
+pub fn export_dot(bags: &Bags, bag: &str, scope: Scope) -> String {
+    let nodes = nodes(bags, bag, scope);
+    let mut dot = String::from("digraph bags {\n");
+    for &node in &nodes {
+        match node == bag {
+            true => dot += &format!("    {} [style=filled, fillcolor=gold];\n", dot_id(node)),
+            false => dot += &format!("    {};\n", dot_id(node)),
+        }
+    }
+    for &node in &nodes {
+        for (inner_bag, count) in edges(bags, node, &nodes) {
+            dot += &format!(
+                "    {} -> {} [label={}];\n",
+                dot_id(node),
+                dot_id(inner_bag),
+                count
+            );
+        }
+    }
+    dot += "}\n";
+    dot
+}
+
+/// Exports the rules in the scope of `bag` as a JSON object mapping each
+/// bag to the list of the bags it directly contains, with their number.
+pub fn export_json(bags: &Bags, bag: &str, scope: Scope) -> String {
+    let nodes = nodes(bags, bag, scope);
+    let lines = nodes
+        .iter()
+        .map(|node| {
+            let edges = edges(bags, node, &nodes)
+                .iter()
+                .map(|(inner_bag, count)| {
+                    format!(
+                        "{{\"bag\": {}, \"count\": {}}}",
+                        json_string(inner_bag),
+                        count
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!("  {}: [{}]", json_string(node), edges.join(", "))
+        })
+        .collect::<Vec<_>>();
+    match lines.is_empty() {
+        true => "{}\n".to_owned(),
+        false => format!("{{\n{}\n}}\n", lines.join(",\n")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    fn bags() -> Bags {
+        let input = "\
+light red bags contain 1 bright white bag, 2 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag.
+dark olive bags contain no other bags.
+faded blue bags contain no other bags.
+";
+        let (bags, counts) = parse(input).unwrap();
+        Bags::new(bags, counts)
+    }
+
+    #[test]
+    fn test_export_dot() {
+        let dot = export_dot(&bags(), "shiny gold", Scope::Descendants);
+        assert_eq!(
+            dot,
+            "\
+digraph bags {
+    \"dark olive\";
+    \"shiny gold\" [style=filled, fillcolor=gold];
+    \"shiny gold\" -> \"dark olive\" [label=1];
+}
+"
+        );
+
+        let dot = export_dot(&bags(), "shiny gold", Scope::Ancestors);
+        assert!(dot.contains("\"light red\" -> \"muted yellow\" [label=2];"));
+        assert!(dot.contains("\"muted yellow\" -> \"shiny gold\" [label=2];"));
+        assert!(!dot.contains("faded blue"));
+        assert!(!dot.contains("dark olive"));
+
+        let dot = export_dot(&bags(), "shiny gold", Scope::All);
+        assert_eq!(dot.matches(" -> ").count(), 6);
+        assert_eq!(dot_id("a \"b\" \\"), "\"a \\\"b\\\" \\\\\"");
+    }
+
+    #[test]
+    fn test_export_json() {
+        let json = export_json(&bags(), "muted yellow", Scope::Descendants);
+        assert_eq!(
+            json,
+            "\
+{
+  \"dark olive\": [],
+  \"faded blue\": [],
+  \"muted yellow\": [{\"bag\": \"faded blue\", \"count\": 9}, {\"bag\": \"shiny gold\", \"count\": 2}],
+  \"shiny gold\": [{\"bag\": \"dark olive\", \"count\": 1}]
+}
+"
+        );
+        assert_eq!(export_json(&bags(), "x", Scope::All).lines().count(), 8);
+    }
+
+    #[test]
+    fn test_scope() {
+        assert_eq!("ancestors".parse(), Ok(Scope::Ancestors));
+        assert_eq!("up".parse::<Scope>(), Err("invalid scope: up".to_owned()));
+    }
+}
@@ -0,0 +1,472 @@
+//! Solution to the puzzle of day 7, _Handy Haversacks_.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+use regex::Regex;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+mod export;
+mod query;
+
+pub use export::{export_dot, export_json, Scope};
+pub use query::Chain;
+
+/// The bag the puzzle asks about.
+pub const BAG: &str = "shiny gold";
+
+/// Maps each bag to the bags that can directly contain it.
+pub type OuterBags = HashMap<String, Vec<String>>;
+/// Maps each bag to the number of each bag it must directly contain.
+pub type InnerBagsCount = HashMap<String, HashMap<String, usize>>;
+
+/// The luggage rules, indexed in both directions.
+pub struct Bags {
+    outer_bags: OuterBags,
+    inner_bags_count: InnerBagsCount,
+}
+
+impl Bags {
+    /// Creates the rules from the two indexes returned by [`parse_input`].
+    pub fn new(outer_bags: OuterBags, inner_bags_count: InnerBagsCount) -> Self {
+        Bags {
+            outer_bags,
+            inner_bags_count,
+        }
+    }
+
+    /// Returns every bag color of the rules, sorted.
+    pub fn colors(&self) -> Vec<&str> {
+        let colors = self
+            .inner_bags_count
+            .keys()
+            .chain(self.outer_bags.keys())
+            .map(|x| x.as_str())
+            .collect::<BTreeSet<_>>();
+        colors.into_iter().collect()
+    }
+
+    /// Returns the bags `bag` must directly contain, with their number,
+    /// sorted by color.
+    pub fn inner_bags(&self, bag: &str) -> Vec<(&str, usize)> {
+        let mut inner_bags = self.inner_bags_count.get(bag).map_or_else(Vec::new, |x| {
+            x.iter()
+                .map(|(bag, &count)| (bag.as_str(), count))
+                .collect()
+        });
+        inner_bags.sort_unstable();
+        inner_bags
+    }
+
+    /// Returns the bag colors that can eventually contain `bag`.
+    pub fn ancestors(&self, bag: &str) -> BTreeSet<&str> {
+        let mut bags = BTreeSet::new();
+        let mut stack = vec![bag];
+        while let Some(bag) = stack.pop() {
+            for outer_bag in self.outer_bags.get(bag).into_iter().flatten() {
+                if bags.insert(outer_bag.as_str()) {
+                    stack.push(outer_bag);
+                }
+            }
+        }
+        bags
+    }
+
+    /// Returns the bag colors that `bag` eventually contains.
+    pub fn descendants(&self, bag: &str) -> BTreeSet<&str> {
+        let mut bags = BTreeSet::new();
+        let mut stack = vec![bag];
+        while let Some(bag) = stack.pop() {
+            for inner_bag in self
+                .inner_bags_count
+                .get(bag)
+                .into_iter()
+                .flat_map(|x| x.keys())
+            {
+                if bags.insert(inner_bag.as_str()) {
+                    stack.push(inner_bag);
+                }
+            }
+        }
+        bags
+    }
+
+    /// Counts the bag colors that can eventually contain `bag`.
+    pub fn count_bag_colors(&self, bag: &str) -> usize {
+        self.ancestors(bag).len()
+    }
+
+    /// Counts the bags required inside `bag`.
+    pub fn count_bags_required(&self, bag: &str) -> Result<usize, CountError> {
+        let mut totals: HashMap<&str, usize> = HashMap::new();
+        self.visit_post_order(bag, |bag| {
+            let mut total = 0usize;
+            for (inner_bag, &count) in self.inner_bags_count.get(bag).into_iter().flatten() {
+                total = totals[inner_bag.as_str()]
+                    .checked_add(1)
+                    .and_then(|x| x.checked_mul(count))
+                    .and_then(|x| x.checked_add(total))
+                    .ok_or_else(|| CountError::Overflow(bag.to_owned()))?;
+            }
+            totals.insert(bag, total);
+            Ok(())
+        })?;
+        Ok(totals[bag])
+    }
+
+    /// Visits `bag` and the bags it eventually contains in depth-first
+    /// order, each of them once and after the bags inside it, so it takes
+    /// time linear in the number of rules. Fails if the rules are cyclic.
+    fn visit_post_order<'a>(
+        &'a self,
+        bag: &'a str,
+        mut visit: impl FnMut(&'a str) -> Result<(), CountError>,
+    ) -> Result<(), CountError> {
+        let inner_bags = |bag: &str| {
+            self.inner_bags_count
+                .get(bag)
+                .into_iter()
+                .flat_map(|x| x.keys())
+        };
+
+        let mut visited = HashSet::new();
+        let mut path: Vec<&str> = Vec::new();
+        let mut on_path = HashSet::new();
+        // Each bag is pushed again to be visited once its inner bags are.
+        let mut stack = vec![(bag, false)];
+        while let Some((bag, unpacked)) = stack.pop() {
+            if unpacked {
+                visit(bag)?;
+                visited.insert(bag);
+                on_path.remove(path.pop().unwrap());
+                continue;
+            }
+            if visited.contains(bag) {
+                continue;
+            }
+            if on_path.contains(bag) {
+                let start = path.iter().position(|&x| x == bag).unwrap();
+                let mut cycle = path[start..]
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>();
+                cycle.push(bag.to_owned());
+                return Err(CountError::Cycle(cycle));
+            }
+            path.push(bag);
+            on_path.insert(bag);
+            stack.push((bag, true));
+            for inner_bag in inner_bags(bag) {
+                if !visited.contains(inner_bag.as_str()) {
+                    stack.push((inner_bag, false));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error found while unpacking the bags inside a bag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CountError {
+    /// The rules contain a cycle, given from a bag back to itself.
+    Cycle(Vec<String>),
+    /// The number of bags inside this bag doesn't fit in a `usize`.
+    Overflow(String),
+}
+
+impl fmt::Display for CountError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CountError::Cycle(bags) => write!(f, "cyclic rules: {}", bags.join(" -> ")),
+            CountError::Overflow(bag) => write!(f, "too many bags inside `{}`", bag),
+        }
+    }
+}
+
+/// An error found while parsing the luggage rules.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A line isn't a rule like `X bags contain N Y bags.`.
+    InvalidRule(Location),
+    /// The number of bags is too large.
+    InvalidCount(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidRule(loc) => write!(f, "invalid rule `{}`", loc.token),
+            ParseError::InvalidCount(loc) => write!(f, "invalid number of bags `{}`", loc.token),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidRule(loc) | ParseError::InvalidCount(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the luggage rules into both indexes.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<(OuterBags, InnerBagsCount), ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<(OuterBags, InnerBagsCount), ParseError> {
+    let re1 = Regex::new(r"(?P<bag>.+) bags contain (?P<inner_bags>.+)\.").unwrap();
+    let re2 = Regex::new(r"(?P<count>\d+) (?P<inner_bag>.+?) bags?").unwrap();
+
+    let mut outer_bags = HashMap::new();
+    let mut inner_bags_count = HashMap::new();
+
+    for line in source.content().lines() {
+        let caps = re1
+            .captures(line)
+            .ok_or_else(|| ParseError::InvalidRule(source.locate(line)))?;
+        let (bag, inner_bags) = (caps["bag"].to_string(), &caps["inner_bags"]);
+        inner_bags_count
+            .entry(bag.clone())
+            .or_insert_with(HashMap::new);
+        for cap in re2.captures_iter(inner_bags) {
+            let count = cap["count"]
+                .parse()
+                .map_err(|_| ParseError::InvalidCount(source.locate(&cap["count"])))?;
+            let inner_bag = cap["inner_bag"].to_string();
+            outer_bags
+                .entry(inner_bag.clone())
+                .or_insert_with(Vec::new)
+                .push(bag.clone());
+            inner_bags_count
+                .entry(bag.clone())
+                .or_insert_with(HashMap::new)
+                .insert(inner_bag, count);
+        }
+    }
+
+    Ok((outer_bags, inner_bags_count))
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<(OuterBags, InnerBagsCount), ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The puzzle of day 7.
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Bags;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        let (bags, counts) = parse_source(source)?;
+        Ok(Bags::new(bags, counts))
+    }
+
+    fn part1(&self, bags_manager: &Self::Input) -> Answer {
+        bags_manager.count_bag_colors(BAG).into()
+    }
+
+    fn part2(&self, bags_manager: &Self::Input) -> Answer {
+        bags_manager.count_bags_required(BAG).ok().into()
+    }
+}
+
+impl Oracle for Day07 {
+    /// Generates the rules of `size` bags, shiny gold included, which
+    /// contain at most 2 bags of at most 2 other colors each.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let adjectives = [
+            "light", "dark", "bright", "muted", "shiny", "faded", "dotted",
+        ];
+        let colors = [
+            "red", "orange", "white", "yellow", "gold", "olive", "plum", "black",
+        ];
+        let mut bags = vec![BAG.to_owned()];
+        while bags.len() < size.min(adjectives.len() * colors.len()) {
+            let bag = format!("{} {}", rng.choose(&adjectives), rng.choose(&colors));
+            if !bags.contains(&bag) {
+                bags.push(bag);
+            }
+        }
+        // Bags can only contain the ones after them, so there are no cycles.
+        rng.shuffle(&mut bags);
+
+        let mut input = String::new();
+        for (i, bag) in bags.iter().enumerate() {
+            let mut inner_bags = bags[i + 1..].to_vec();
+            rng.shuffle(&mut inner_bags);
+            let contents = inner_bags
+                .iter()
+                .take(rng.range(0, 3))
+                .map(|inner_bag| match rng.range(1, 3) {
+                    1 => format!("1 {} bag", inner_bag),
+                    n => format!("{} {} bags", n, inner_bag),
+                })
+                .collect::<Vec<_>>();
+            let contents = match contents.is_empty() {
+                true => "no other bags".to_owned(),
+                false => contents.join(", "),
+            };
+            input += &format!("{} bags contain {}.\n", bag, contents);
+        }
+        input
+    }
+
+    fn oracle(&self, bags: &Self::Input, part: usize) -> Answer {
+        let no_bags = HashMap::new();
+        let inner_bags = |bag: &str| bags.inner_bags_count.get(bag).unwrap_or(&no_bags);
+
+        if part == 1 {
+            let contains_bag = |outer_bag: &str| {
+                let mut stack = vec![outer_bag.to_owned()];
+                while let Some(bag) = stack.pop() {
+                    for inner_bag in inner_bags(&bag).keys() {
+                        if inner_bag == BAG {
+                            return true;
+                        }
+                        stack.push(inner_bag.clone());
+                    }
+                }
+                false
+            };
+            let outer_bags = bags.inner_bags_count.keys();
+            outer_bags.filter(|x| contains_bag(x)).count().into()
+        } else {
+            // Unpack the bags one by one.
+            let mut count = 0;
+            let mut stack = vec![BAG.to_owned()];
+            while let Some(bag) = stack.pop() {
+                for (inner_bag, &n) in inner_bags(&bag) {
+                    for _ in 0..n {
+                        count += 1;
+                        stack.push(inner_bag.clone());
+                    }
+                }
+            }
+            count.into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input_1() {
+        let (bags, counts) = parse_input("example1.txt").unwrap();
+        let bags_manager = Bags::new(bags, counts);
+        assert_eq!(bags_manager.count_bag_colors(BAG), 4);
+        assert_eq!(bags_manager.count_bags_required(BAG), Ok(32));
+    }
+
+    #[test]
+    fn test_example_input_2() {
+        let (bags, counts) = parse_input("example2.txt").unwrap();
+        let bags_manager = Bags::new(bags, counts);
+        assert_eq!(bags_manager.count_bag_colors(BAG), 0);
+        assert_eq!(bags_manager.count_bags_required(BAG), Ok(126));
+    }
+
+    #[test]
+    fn test_parse_string() {
+        let input = "\
+shiny gold bags contain 2 dark red bags.
+dark red bags contain 2 dark orange bags.
+dark orange bags contain no other bags.
+";
+        let (bags, counts) = parse(input).unwrap();
+        let bags_manager = Bags::new(bags, counts);
+        assert_eq!(bags_manager.count_bag_colors(BAG), 0);
+        assert_eq!(bags_manager.count_bags_required(BAG), Ok(6));
+    }
+
+    #[test]
+    fn test_count_deep_rules() {
+        // Every bag contains the next one twice, by two different paths.
+        let mut input = String::new();
+        for i in 0..200 {
+            input += &format!("c{} bags contain 1 a{} bag, 1 b{} bag.\n", i, i, i);
+            input += &format!("a{} bags contain 1 c{} bag.\n", i, i + 1);
+            input += &format!("b{} bags contain 1 c{} bag.\n", i, i + 1);
+        }
+        input += "c200 bags contain no other bags.\n";
+        let (bags, counts) = parse(&input).unwrap();
+        let bags_manager = Bags::new(bags, counts);
+        assert_eq!(
+            bags_manager.count_bags_required("c190"),
+            Ok(4 * (1 << 10) - 4)
+        );
+        assert_eq!(
+            bags_manager.count_bags_required("c0"),
+            Err(CountError::Overflow("c137".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_count_cyclic_rules() {
+        let input = "\
+shiny gold bags contain 2 dark red bags.
+dark red bags contain 2 dark orange bags, 1 faded blue bag.
+dark orange bags contain 1 shiny gold bag.
+faded blue bags contain no other bags.
+";
+        let (bags, counts) = parse(input).unwrap();
+        let bags_manager = Bags::new(bags, counts);
+        let err = bags_manager.count_bags_required("dark red").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "cyclic rules: dark red -> dark orange -> shiny gold -> dark red"
+        );
+        assert_eq!(bags_manager.count_bags_required("faded blue"), Ok(0));
+        assert_eq!(bags_manager.count_bag_colors("faded blue"), 3);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day07, 8);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day07, 7, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let bags_manager = Day07.parse(Path::new("example1.txt")).unwrap();
+        assert_eq!(Day07.part1(&bags_manager), Answer::Number(4));
+        assert_eq!(Day07.part2(&bags_manager), Answer::Number(32));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new(
+            "test.txt",
+            "light red bags contain 1 bright white bag.\ndark orange bags\n",
+        );
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid rule `dark orange bags`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (2, 1));
+    }
+}
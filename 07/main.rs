@@ -1,121 +1,84 @@
-use std::collections::HashMap;
-use std::collections::HashSet;
-use std::env;
-use std::fs;
-use std::path::Path;
 use std::process;
 
-use regex::Regex;
+use common::{or_exit, parse_args, take_option, Printer};
+use day07::{export_dot, export_json, parse_input, Bags, Chain, Scope, BAG};
 
-const BAG: &str = "shiny gold";
-
-type OuterBags = HashMap<String, Vec<String>>;
-type InnerBagsCount = HashMap<String, HashMap<String, usize>>;
-
-struct Bags {
-    outer_bags: OuterBags,
-    inner_bags_count: InnerBagsCount,
+fn exit_with(err: impl ToString) -> ! {
+    eprintln!("error: {}", err.to_string());
+    process::exit(1);
 }
 
-impl Bags {
-    fn new(outer_bags: OuterBags, inner_bags_count: InnerBagsCount) -> Self {
-        Bags {
-            outer_bags,
-            inner_bags_count,
-        }
-    }
-
-    fn count_bag_colors(&self, bag: &str) -> usize {
-        let mut bags = HashSet::new();
-        self.count_bag_colors_util(bag, &mut bags);
-        bags.len()
-    }
-
-    fn count_bag_colors_util(&self, bag: &str, mut bags: &mut HashSet<String>) {
-        if let Some(outer_bags) = self.outer_bags.get(&bag.to_owned()) {
-            for outer_bag in outer_bags {
-                bags.insert(outer_bag.to_owned());
-                self.count_bag_colors_util(&outer_bag, &mut bags);
-            }
-        }
-    }
-
-    fn count_bags_required(&self, bag: &str) -> usize {
-        self.inner_bags_count
-            .get(&bag.to_owned())
-            .unwrap_or(&HashMap::new())
-            .iter()
-            .map(|(inner_bag, count)| count + count * self.count_bags_required(inner_bag))
-            .sum()
+fn format_chain(chain: &Chain) -> String {
+    match chain.multiplicity() {
+        Some(count) => format!("{}, {} in total", chain, count),
+        None => format!("{}, too many in total", chain),
     }
 }
 
-fn parse_input(file_name: impl AsRef<Path>) -> (OuterBags, InnerBagsCount) {
-    let re1 = Regex::new(r"(?P<bag>.+) bags contain (?P<inner_bags>.+)\.").unwrap();
-    let re2 = Regex::new(r"(?P<count>\d+) (?P<inner_bag>.+?) bags?").unwrap();
-
-    let content = fs::read_to_string(&file_name).unwrap();
-    let mut outer_bags = HashMap::new();
-    let mut inner_bags_count = HashMap::new();
-
-    for line in content.lines() {
-        let caps = re1.captures(&line).unwrap();
-        let (bag, inner_bags) = (caps["bag"].to_string(), &caps["inner_bags"]);
-        for cap in re2.captures_iter(&inner_bags) {
-            let (count, inner_bag) = (cap["count"].parse().unwrap(), cap["inner_bag"].to_string());
-            outer_bags
-                .entry(inner_bag.clone())
-                .or_insert_with(Vec::new)
-                .push(bag.clone());
-            inner_bags_count
-                .entry(bag.clone())
-                .or_insert_with(HashMap::new)
-                .insert(inner_bag, count);
+/// Answers a query about the bags, returning the lines to print.
+fn query(bags: &Bags, words: &[&str]) -> Vec<String> {
+    match words {
+        ["inside", bag, outer] => {
+            let answer = if bags.can_contain(outer, bag) {
+                "yes"
+            } else {
+                "no"
+            };
+            vec![answer.to_owned()]
+        }
+        ["chain", bag, outer] => match bags.shortest_chain(outer, bag) {
+            Some(chain) => vec![format_chain(&chain)],
+            None => vec!["none".to_owned()],
+        },
+        ["chains", bag, outer] => bags.chains(outer, bag).iter().map(format_chain).collect(),
+        ["depth", bag] => {
+            let depth = bags.nesting_depth(bag).unwrap_or_else(|err| exit_with(err));
+            vec![depth.to_string()]
         }
+        ["empty"] => bags.empty_bags().iter().map(|x| x.to_string()).collect(),
+        _ => exit_with(format!("invalid query: {}", words.join(" "))),
     }
-
-    (outer_bags, inner_bags_count)
 }
 
 fn main() {
-    if env::args().count() != 2 {
-        eprintln!("USAGE: {} FILE", env::args().next().unwrap());
-        process::exit(1);
-    }
-
-    let (bags, counts) = parse_input(env::args().nth(1).unwrap());
+    let (mut args, format) = parse_args(&[
+        "FILE",
+        "[--export dot|json]",
+        "[--bag COLOR]",
+        "[--scope all|ancestors|descendants]",
+        "[QUERY...]",
+    ]);
+    let options = ["--export", "--bag", "--scope"]
+        .iter()
+        .map(|name| take_option(&mut args, name))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_or_else(|err| exit_with(err));
+    let bag = options[1].as_deref().unwrap_or(BAG);
+    let scope = options[2]
+        .as_deref()
+        .map_or(Ok(Scope::All), str::parse)
+        .unwrap_or_else(|err| exit_with(err));
+    let (bags, counts) = or_exit(parse_input(&args[0]));
     let bags_manager = Bags::new(bags, counts);
-    let part1 = bags_manager.count_bag_colors(BAG);
-    let part2 = bags_manager.count_bags_required(BAG);
-    println!("Result (Part 1): {}", part1);
-    println!("Result (Part 2): {}", part2);
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_example_input_1() {
-        let (bags, counts) = parse_input("example1.txt");
-        let bags_manager = Bags::new(bags, counts);
-        assert_eq!(bags_manager.count_bag_colors(BAG), 4);
-        assert_eq!(bags_manager.count_bags_required(BAG), 32);
-    }
-
-    #[test]
-    fn test_example_input_2() {
-        let (bags, counts) = parse_input("example2.txt");
-        let bags_manager = Bags::new(bags, counts);
-        assert_eq!(bags_manager.count_bag_colors(BAG), 0);
-        assert_eq!(bags_manager.count_bags_required(BAG), 126);
-    }
-
-    #[test]
-    fn test_puzzle_input() {
-        let (bags, counts) = parse_input("input.txt");
-        let bags_manager = Bags::new(bags, counts);
-        assert_eq!(bags_manager.count_bag_colors(BAG), 259);
-        assert_eq!(bags_manager.count_bags_required(BAG), 45018);
+    let words = args[1..].iter().map(|x| x.as_str()).collect::<Vec<_>>();
+
+    match options[0].as_deref() {
+        Some("dot") => print!("{}", export_dot(&bags_manager, bag, scope)),
+        Some("json") => print!("{}", export_json(&bags_manager, bag, scope)),
+        Some(export) => exit_with(format!("invalid export format: {}", export)),
+        None if !words.is_empty() => {
+            for line in query(&bags_manager, &words) {
+                println!("{}", line);
+            }
+        }
+        None => {
+            let mut printer = Printer::new(7, format);
+            printer.solve(1, || bags_manager.count_bag_colors(bag));
+            printer.solve(2, || {
+                bags_manager
+                    .count_bags_required(bag)
+                    .unwrap_or_else(|err| exit_with(err))
+            });
+        }
     }
 }
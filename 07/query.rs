@@ -0,0 +1,202 @@
+//! Queries about the relationships between the bags of the rules.
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::fmt;
+
+use crate::{Bags, CountError};
+
+/// A chain of bags, each of them directly containing the next one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Chain {
+    /// The bags, from the outermost one.
+    pub bags: Vec<String>,
+    /// The number of each bag, but the first one, inside the previous one.
+    pub counts: Vec<usize>,
+}
+
+impl Chain {
+    /// Returns the number of the last bag inside the first one along the
+    /// chain, or `None` if it doesn't fit in a `usize`.
+    pub fn multiplicity(&self) -> Option<usize> {
+        self.counts
+            .iter()
+            .try_fold(1usize, |x, &n| x.checked_mul(n))
+    }
+}
+
+impl fmt::Display for Chain {
+    /// Formats the chain like `light red -> 2 muted yellow -> 2 shiny gold`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.bags[0])?;
+        for (bag, count) in self.bags[1..].iter().zip(&self.counts) {
+            write!(f, " -> {} {}", count, bag)?;
+        }
+        Ok(())
+    }
+}
+
+impl Bags {
+    /// Creates the chain of `bags`, each one directly inside the previous
+    /// one.
+    fn chain(&self, bags: Vec<&str>) -> Chain {
+        let counts = bags
+            .windows(2)
+            .map(|pair| self.inner_bags_count[pair[0]][pair[1]])
+            .collect();
+        Chain {
+            bags: bags.into_iter().map(|x| x.to_owned()).collect(),
+            counts,
+        }
+    }
+
+    /// Checks if `inner` is allowed, eventually, inside `outer`.
+    pub fn can_contain(&self, outer: &str, inner: &str) -> bool {
+        self.descendants(outer).contains(inner)
+    }
+
+    /// Returns a chain with the fewest bags from `outer` to `inner`, if
+    /// any.
+    pub fn shortest_chain(&self, outer: &str, inner: &str) -> Option<Chain> {
+        let mut parents = HashMap::new();
+        let mut queue = VecDeque::from(vec![outer]);
+        while let Some(bag) = queue.pop_front() {
+            for (inner_bag, _) in self.inner_bags(bag) {
+                if inner_bag == inner {
+                    let mut bags = vec![inner_bag, bag];
+                    while bags[bags.len() - 1] != outer {
+                        bags.push(parents[bags[bags.len() - 1]]);
+                    }
+                    bags.reverse();
+                    return Some(self.chain(bags));
+                }
+                if inner_bag != outer && !parents.contains_key(inner_bag) {
+                    parents.insert(inner_bag, bag);
+                    queue.push_back(inner_bag);
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns every chain from `outer` to `inner` that doesn't go through
+    /// a bag twice, sorted by color.
+    pub fn chains(&self, outer: &str, inner: &str) -> Vec<Chain> {
+        let ancestors = self.ancestors(inner);
+        let mut chains = Vec::new();
+        let mut path = vec![outer];
+        self.chains_util(inner, &ancestors, &mut path, &mut chains);
+        chains
+    }
+
+    fn chains_util<'a>(
+        &'a self,
+        inner: &str,
+        ancestors: &BTreeSet<&str>,
+        path: &mut Vec<&'a str>,
+        chains: &mut Vec<Chain>,
+    ) {
+        for (inner_bag, _) in self.inner_bags(path[path.len() - 1]) {
+            if inner_bag == inner {
+                let mut bags = path.clone();
+                bags.push(inner_bag);
+                chains.push(self.chain(bags));
+            } else if ancestors.contains(inner_bag) && !path.contains(&inner_bag) {
+                path.push(inner_bag);
+                self.chains_util(inner, ancestors, path, chains);
+                path.pop();
+            }
+        }
+    }
+
+    /// Returns how many levels of bags are nested inside `bag`.
+    pub fn nesting_depth(&self, bag: &str) -> Result<usize, CountError> {
+        let mut depths: HashMap<&str, usize> = HashMap::new();
+        self.visit_post_order(bag, |bag| {
+            let depth = self
+                .inner_bags(bag)
+                .iter()
+                .map(|(inner_bag, _)| depths[*inner_bag] + 1)
+                .max()
+                .unwrap_or(0);
+            depths.insert(bag, depth);
+            Ok(())
+        })?;
+        Ok(depths[bag])
+    }
+
+    /// Returns the bags that contain no other bags, sorted.
+    pub fn empty_bags(&self) -> Vec<&str> {
+        let mut bags = self.colors();
+        bags.retain(|bag| self.inner_bags(bag).is_empty());
+        bags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, BAG};
+
+    fn bags(input: &str) -> Bags {
+        let (bags, counts) = parse(input).unwrap();
+        Bags::new(bags, counts)
+    }
+
+    #[test]
+    fn test_chains() {
+        let bags = bags(include_str!("example1.txt"));
+        assert!(bags.can_contain("light red", BAG));
+        assert!(!bags.can_contain(BAG, "light red"));
+        assert!(!bags.can_contain(BAG, BAG));
+
+        let chain = bags.shortest_chain("light red", "faded blue").unwrap();
+        assert_eq!(
+            chain.to_string(),
+            "light red -> 2 muted yellow -> 9 faded blue"
+        );
+        assert_eq!(chain.multiplicity(), Some(18));
+        assert_eq!(bags.shortest_chain("faded blue", "light red"), None);
+
+        let chains = bags.chains("light red", "dark olive");
+        let chains = chains.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            chains,
+            [
+                "light red -> 1 bright white -> 1 shiny gold -> 1 dark olive",
+                "light red -> 2 muted yellow -> 2 shiny gold -> 1 dark olive",
+            ]
+        );
+        assert!(bags.chains("dark olive", "light red").is_empty());
+    }
+
+    #[test]
+    fn test_cyclic_chains() {
+        let bags = bags(
+            "\
+shiny gold bags contain 2 dark red bags.
+dark red bags contain 3 shiny gold bags, 1 faded blue bag.
+faded blue bags contain no other bags.
+",
+        );
+        let chain = bags.shortest_chain(BAG, BAG).unwrap();
+        assert_eq!(
+            chain.to_string(),
+            "shiny gold -> 2 dark red -> 3 shiny gold"
+        );
+        assert_eq!(bags.chains(BAG, BAG), [chain]);
+        assert_eq!(bags.chains(BAG, "faded blue").len(), 1);
+        assert_eq!(
+            bags.nesting_depth(BAG).err().unwrap().to_string(),
+            "cyclic rules: shiny gold -> dark red -> shiny gold"
+        );
+    }
+
+    #[test]
+    fn test_nesting_depth() {
+        let bags = bags(include_str!("example1.txt"));
+        assert_eq!(bags.nesting_depth("light red"), Ok(4));
+        assert_eq!(bags.nesting_depth(BAG), Ok(2));
+        assert_eq!(bags.nesting_depth("faded blue"), Ok(0));
+        assert_eq!(bags.empty_bags(), ["dotted black", "faded blue"]);
+    }
+}
@@ -0,0 +1,455 @@
+//! A step debugger of the boot code, with breakpoints, reverse steps and
+//! traces, and an interactive prompt to drive it.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+use crate::Instruction;
+
+/// Where the debugger stops when continuing.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Breakpoint {
+    /// Before the instruction at this address.
+    Address(usize),
+    /// Before any instruction with this operation.
+    Opcode(String),
+}
+
+impl Breakpoint {
+    fn matches(&self, ip: usize, ins: &Instruction) -> bool {
+        match self {
+            Breakpoint::Address(address) => *address == ip,
+            Breakpoint::Opcode(opcode) => opcode == ins.opcode(),
+        }
+    }
+}
+
+impl FromStr for Breakpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "nop" | "acc" | "jmp" => Ok(Breakpoint::Opcode(s.to_owned())),
+            _ => s
+                .parse()
+                .map(Breakpoint::Address)
+                .map_err(|_| format!("invalid breakpoint `{}`", s)),
+        }
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Breakpoint::Address(address) => write!(f, "{}", address),
+            Breakpoint::Opcode(opcode) => write!(f, "{}", opcode),
+        }
+    }
+}
+
+/// An instruction executed by the debugger.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Executed {
+    pub ip: usize,
+    pub instruction: Instruction,
+    /// The accumulator after the instruction.
+    pub acc: i32,
+}
+
+impl fmt::Display for Executed {
+    /// Formats the step like `4: acc +3 (acc 5)`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} (acc {})", self.ip, self.instruction, self.acc)
+    }
+}
+
+/// Why the debugger stopped when continuing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Stop {
+    /// The boot code ran past its last instruction.
+    Terminated,
+    /// The next instruction matches a breakpoint.
+    Breakpoint(Breakpoint),
+    /// The next instruction was already executed.
+    Repeat,
+}
+
+/// An error of the debugger.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebugError {
+    /// The boot code already terminated.
+    Terminated,
+    /// The `jmp` at this address leaves the boot code.
+    OutOfBounds(usize),
+    /// There's no step to undo.
+    NoHistory,
+}
+
+impl fmt::Display for DebugError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DebugError::Terminated => write!(f, "the boot code terminated"),
+            DebugError::OutOfBounds(ip) => write!(f, "the jump at {} leaves the boot code", ip),
+            DebugError::NoHistory => write!(f, "no step to undo"),
+        }
+    }
+}
+
+/// A step debugger of the boot code.
+pub struct Debugger<'a> {
+    instructions: &'a [Instruction],
+    ip: usize,
+    acc: i32,
+    visited: Vec<bool>,
+    /// The address, accumulator and first visit of each step, to undo it.
+    history: Vec<(usize, i32, bool)>,
+    breakpoints: BTreeSet<Breakpoint>,
+}
+
+impl<'a> Debugger<'a> {
+    /// Creates a debugger at the first instruction.
+    pub fn new(instructions: &'a [Instruction]) -> Self {
+        Debugger {
+            instructions,
+            ip: 0,
+            acc: 0,
+            visited: vec![false; instructions.len()],
+            history: Vec::new(),
+            breakpoints: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the address of the next instruction.
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    /// Returns the accumulator.
+    pub fn acc(&self) -> i32 {
+        self.acc
+    }
+
+    /// Returns the next instruction, or `None` if the boot code terminated.
+    pub fn current(&self) -> Option<&Instruction> {
+        self.instructions.get(self.ip)
+    }
+
+    /// Returns the addresses of the instructions executed, sorted.
+    pub fn visited(&self) -> Vec<usize> {
+        (0..self.visited.len())
+            .filter(|&ip| self.visited[ip])
+            .collect()
+    }
+
+    /// Returns the number of steps executed and not undone.
+    pub fn steps(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Adds a breakpoint, returning `false` if it already existed.
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> bool {
+        self.breakpoints.insert(breakpoint)
+    }
+
+    /// Removes a breakpoint, returning `false` if it didn't exist.
+    pub fn remove_breakpoint(&mut self, breakpoint: &Breakpoint) -> bool {
+        self.breakpoints.remove(breakpoint)
+    }
+
+    /// Returns the breakpoints, sorted.
+    pub fn breakpoints(&self) -> impl Iterator<Item = &Breakpoint> {
+        self.breakpoints.iter()
+    }
+
+    /// Executes the next instruction.
+    pub fn step(&mut self) -> Result<Executed, DebugError> {
+        let instruction = self.current().ok_or(DebugError::Terminated)?.clone();
+        let (mut ip, mut acc) = (self.ip + 1, self.acc);
+        match instruction {
+            Instruction::Nop(_) => (),
+            Instruction::Acc(val) => acc += val,
+            Instruction::Jmp(val) => {
+                ip = (self.ip as isize)
+                    .checked_add(val as isize)
+                    .filter(|&x| x >= 0 && x as usize <= self.instructions.len())
+                    .ok_or(DebugError::OutOfBounds(self.ip))? as usize;
+            }
+        }
+
+        let executed = Executed {
+            ip: self.ip,
+            instruction,
+            acc,
+        };
+        self.history
+            .push((self.ip, self.acc, !self.visited[self.ip]));
+        self.visited[self.ip] = true;
+        self.ip = ip;
+        self.acc = acc;
+        Ok(executed)
+    }
+
+    /// Undoes the last step.
+    pub fn reverse_step(&mut self) -> Result<(), DebugError> {
+        let (ip, acc, first_visit) = self.history.pop().ok_or(DebugError::NoHistory)?;
+        if first_visit {
+            self.visited[ip] = false;
+        }
+        self.ip = ip;
+        self.acc = acc;
+        Ok(())
+    }
+
+    /// Executes instructions, passing each of them to `on_step`, until the
+    /// boot code terminates, or the next instruction matches a breakpoint
+    /// or was already executed. At least one instruction is executed.
+    pub fn resume(&mut self, mut on_step: impl FnMut(&Executed)) -> Result<Stop, DebugError> {
+        loop {
+            on_step(&self.step()?);
+            let (ip, ins) = match self.current() {
+                Some(ins) => (self.ip, ins),
+                None => return Ok(Stop::Terminated),
+            };
+            if let Some(breakpoint) = self.breakpoints.iter().find(|x| x.matches(ip, ins)) {
+                return Ok(Stop::Breakpoint(breakpoint.clone()));
+            }
+            if self.visited[ip] {
+                return Ok(Stop::Repeat);
+            }
+        }
+    }
+}
+
+const HELP: &str = "\
+commands:
+  step [N]        execute the next N instructions
+  back [N]        undo the last N steps
+  continue        run until a breakpoint, a repeated instruction or the end
+  break [WHERE]   stop before an address or an operation, or list breakpoints
+  delete WHERE    remove a breakpoint
+  info            show the next instruction, the accumulator and the visited set
+  trace on|off    print each executed instruction
+  quit            leave the debugger
+";
+
+/// Describes where the debugger is.
+fn location(debugger: &Debugger) -> String {
+    match debugger.current() {
+        Some(ins) => format!("at {}: {} (acc {})", debugger.ip(), ins, debugger.acc()),
+        None => format!("terminated (acc {})", debugger.acc()),
+    }
+}
+
+/// Parses the optional count of a command.
+fn count(arg: Option<&str>) -> Result<usize, String> {
+    arg.map_or(Ok(1), |x| {
+        x.parse().map_err(|_| format!("invalid count `{}`", x))
+    })
+}
+
+/// Runs a command, writing its output.
+fn run_command(
+    debugger: &mut Debugger,
+    trace: &mut bool,
+    words: &[&str],
+    output: &mut impl Write,
+) -> io::Result<Result<(), String>> {
+    match words {
+        ["step", n @ ..] | ["s", n @ ..] if n.len() <= 1 => {
+            let n = match count(n.first().copied()) {
+                Ok(n) => n,
+                Err(err) => return Ok(Err(err)),
+            };
+            for _ in 0..n {
+                match debugger.step() {
+                    Ok(executed) if *trace => writeln!(output, "{}", executed)?,
+                    Ok(_) => (),
+                    Err(err) => return Ok(Err(err.to_string())),
+                }
+            }
+            writeln!(output, "{}", location(debugger))?;
+        }
+        ["back", n @ ..] if n.len() <= 1 => {
+            let n = match count(n.first().copied()) {
+                Ok(n) => n,
+                Err(err) => return Ok(Err(err)),
+            };
+            for _ in 0..n {
+                if let Err(err) = debugger.reverse_step() {
+                    return Ok(Err(err.to_string()));
+                }
+            }
+            writeln!(output, "{}", location(debugger))?;
+        }
+        ["continue"] | ["c"] => {
+            let mut steps = Vec::new();
+            let stop = debugger.resume(|executed| {
+                if *trace {
+                    steps.push(executed.to_string());
+                }
+            });
+            for step in steps {
+                writeln!(output, "{}", step)?;
+            }
+            match stop {
+                Ok(Stop::Breakpoint(breakpoint)) => {
+                    writeln!(output, "breakpoint {}", breakpoint)?;
+                }
+                Ok(Stop::Repeat) => writeln!(output, "instruction {} runs again", debugger.ip())?,
+                Ok(Stop::Terminated) => (),
+                Err(err) => return Ok(Err(err.to_string())),
+            }
+            writeln!(output, "{}", location(debugger))?;
+        }
+        ["break"] => {
+            for breakpoint in debugger.breakpoints() {
+                writeln!(output, "breakpoint {}", breakpoint)?;
+            }
+        }
+        ["break", place] | ["delete", place] => {
+            let breakpoint = match place.parse::<Breakpoint>() {
+                Ok(breakpoint) => breakpoint,
+                Err(err) => return Ok(Err(err)),
+            };
+            if words[0] == "break" {
+                debugger.add_breakpoint(breakpoint);
+            } else if !debugger.remove_breakpoint(&breakpoint) {
+                return Ok(Err(format!("no breakpoint {}", breakpoint)));
+            }
+        }
+        ["info"] => {
+            writeln!(output, "{}", location(debugger))?;
+            writeln!(output, "steps: {}", debugger.steps())?;
+            let visited = debugger.visited();
+            let visited = visited.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+            writeln!(output, "visited: {}", visited.join(" "))?;
+        }
+        ["trace", "on"] => *trace = true,
+        ["trace", "off"] => *trace = false,
+        ["help"] => write!(output, "{}", HELP)?,
+        _ => return Ok(Err(format!("invalid command `{}`", words.join(" ")))),
+    }
+    Ok(Ok(()))
+}
+
+/// Debugs the `instructions`, reading commands from `input` and writing
+/// their output, until `quit` or the end of the input. Invalid commands are
+/// reported and ignored.
+pub fn repl(
+    instructions: &[Instruction],
+    input: impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<()> {
+    let mut debugger = Debugger::new(instructions);
+    let mut trace = false;
+    writeln!(output, "{}", location(&debugger))?;
+    write!(output, "> ")?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        match words.as_slice() {
+            [] => (),
+            ["quit"] | ["q"] => return Ok(()),
+            words => {
+                if let Err(err) = run_command(&mut debugger, &mut trace, words, output)? {
+                    writeln!(output, "error: {}", err)?;
+                }
+            }
+        }
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+    writeln!(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input;
+
+    #[test]
+    fn test_debugger() {
+        let instructions = parse_input("example.txt").unwrap();
+        let mut debugger = Debugger::new(&instructions);
+        assert_eq!(debugger.step().unwrap().to_string(), "0: nop +0 (acc 0)");
+        assert_eq!(debugger.step().unwrap().to_string(), "1: acc +1 (acc 1)");
+        assert!(debugger.add_breakpoint(Breakpoint::Opcode("jmp".to_owned())));
+        assert!(!debugger.add_breakpoint("jmp".parse().unwrap()));
+        // The jump at 2 is executed, as at least one instruction is.
+        assert_eq!(
+            debugger.resume(|_| ()),
+            Ok(Stop::Breakpoint("jmp".parse().unwrap()))
+        );
+        assert_eq!((debugger.ip(), debugger.acc()), (7, 2));
+
+        assert!(debugger.remove_breakpoint(&"jmp".parse().unwrap()));
+        let mut trace = Vec::new();
+        assert_eq!(debugger.resume(|x| trace.push(x.ip)), Ok(Stop::Repeat));
+        assert_eq!(trace, [7, 3, 4]);
+        assert_eq!((debugger.ip(), debugger.acc()), (1, 5));
+        assert_eq!(debugger.visited(), [0, 1, 2, 3, 4, 6, 7]);
+
+        debugger.reverse_step().unwrap();
+        debugger.reverse_step().unwrap();
+        assert_eq!((debugger.ip(), debugger.acc()), (3, 2));
+        assert_eq!(debugger.visited(), [0, 1, 2, 6, 7]);
+        for _ in 0..5 {
+            debugger.reverse_step().unwrap();
+        }
+        assert_eq!(debugger.reverse_step(), Err(DebugError::NoHistory));
+        assert!(debugger.visited().is_empty());
+    }
+
+    #[test]
+    fn test_debugger_errors() {
+        let instructions = [Instruction::Acc(2), Instruction::Jmp(-2)];
+        let mut debugger = Debugger::new(&instructions);
+        debugger.step().unwrap();
+        assert_eq!(debugger.step(), Err(DebugError::OutOfBounds(1)));
+        assert_eq!(debugger.steps(), 1);
+
+        let instructions = [Instruction::Jmp(1)];
+        let mut debugger = Debugger::new(&instructions);
+        assert_eq!(debugger.resume(|_| ()), Ok(Stop::Terminated));
+        assert_eq!(debugger.step(), Err(DebugError::Terminated));
+        assert_eq!(
+            "x1".parse::<Breakpoint>(),
+            Err("invalid breakpoint `x1`".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_repl() {
+        let instructions = parse_input("example.txt").unwrap();
+        let input = "break 4\ntrace on\ncontinue\nstep 2\nback\nbreak\ninfo\nfoo\nq\nstep\n";
+        let mut output = Vec::new();
+        repl(&instructions, input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output,
+            "\
+at 0: nop +0 (acc 0)
+> > > 0: nop +0 (acc 0)
+1: acc +1 (acc 1)
+2: jmp +4 (acc 1)
+6: acc +1 (acc 2)
+7: jmp -4 (acc 2)
+3: acc +3 (acc 5)
+breakpoint 4
+at 4: jmp -3 (acc 5)
+> 4: jmp -3 (acc 5)
+1: acc +1 (acc 6)
+at 2: jmp +4 (acc 6)
+> at 1: acc +1 (acc 5)
+> breakpoint 4
+> at 1: acc +1 (acc 5)
+steps: 7
+visited: 0 1 2 3 4 6 7
+> error: invalid command `foo`
+> "
+        );
+    }
+}
@@ -0,0 +1,367 @@
+//! Solution to the puzzle of day 8, _Handheld Halting_.
+
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+mod debugger;
+
+pub use debugger::{repl, Breakpoint, DebugError, Debugger, Executed, Stop};
+
+/// An instruction of the boot code.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Instruction {
+    Nop(i32),
+    Acc(i32),
+    Jmp(i32),
+}
+
+impl Instruction {
+    /// Returns the name of the operation.
+    pub fn opcode(&self) -> &'static str {
+        match self {
+            Instruction::Nop(_) => "nop",
+            Instruction::Acc(_) => "acc",
+            Instruction::Jmp(_) => "jmp",
+        }
+    }
+
+    /// Returns the argument of the operation.
+    pub fn argument(&self) -> i32 {
+        match *self {
+            Instruction::Nop(val) | Instruction::Acc(val) | Instruction::Jmp(val) => val,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    /// Formats the instruction as in the boot code, like `jmp -3`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {:+}", self.opcode(), self.argument())
+    }
+}
+
+/// The outcome of running the boot code, with the final accumulator.
+#[derive(Debug, PartialEq)]
+pub enum BootCodeResult {
+    Terminated(i32),
+    Cyclic(i32),
+}
+
+fn jmp_ip(ip: usize, jmp: i32) -> usize {
+    (ip as isize + jmp as isize) as usize
+}
+
+/// The boot code of the handheld game console.
+pub struct BootCode {
+    instructions: Vec<Instruction>,
+}
+
+impl BootCode {
+    /// Creates the boot code from its instructions.
+    pub fn new(instructions: Vec<Instruction>) -> Self {
+        BootCode { instructions }
+    }
+
+    /// Returns the instructions of the boot code.
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// Runs the boot code until it terminates or an instruction repeats.
+    pub fn run(&self) -> BootCodeResult {
+        let mut acc = 0;
+        let mut ip = 0;
+        let mut executed = vec![false; self.instructions.len()];
+
+        while ip != self.instructions.len() {
+            if executed[ip] {
+                return BootCodeResult::Cyclic(acc);
+            } else {
+                executed[ip] = true;
+            }
+
+            match self.instructions[ip] {
+                Instruction::Nop(_) => {
+                    ip += 1;
+                }
+                Instruction::Acc(val) => {
+                    acc += val;
+                    ip += 1;
+                }
+                Instruction::Jmp(val) => {
+                    ip = jmp_ip(ip, val);
+                }
+            }
+        }
+
+        BootCodeResult::Terminated(acc)
+    }
+
+    /// Runs the boot code, swapping one `jmp` or `nop` so that it
+    /// terminates.
+    pub fn run_with_fix(&self) -> BootCodeResult {
+        for (ip, ins) in self.instructions.iter().enumerate() {
+            match ins {
+                Instruction::Nop(val) => {
+                    let mut new_instructions = self.instructions.clone();
+                    new_instructions[ip] = Instruction::Jmp(*val);
+                    let r = BootCode::new(new_instructions).run();
+                    if let BootCodeResult::Terminated(_) = r {
+                        return r;
+                    }
+                }
+                Instruction::Jmp(val) => {
+                    let mut new_instructions = self.instructions.clone();
+                    new_instructions[ip] = Instruction::Nop(*val);
+                    let r = BootCode::new(new_instructions).run();
+                    if let BootCodeResult::Terminated(_) = r {
+                        return r;
+                    }
+                }
+                Instruction::Acc(_) => (),
+            }
+        }
+
+        self.run()
+    }
+}
+
+/// An error found while parsing the boot code.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// The operation isn't `nop`, `acc` or `jmp`.
+    InvalidOperation(Location),
+    /// The argument isn't a signed number.
+    InvalidArgument(Location),
+    /// The line ends before the argument.
+    MissingArgument(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidOperation(loc) => write!(f, "invalid operation `{}`", loc.token),
+            ParseError::InvalidArgument(loc) => write!(f, "invalid argument `{}`", loc.token),
+            ParseError::MissingArgument(_) => write!(f, "missing argument"),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidOperation(loc)
+            | ParseError::InvalidArgument(loc)
+            | ParseError::MissingArgument(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the boot code instructions.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<Vec<Instruction>, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Vec<Instruction>, ParseError> {
+    source
+        .content()
+        .lines()
+        .map(|line| {
+            let mut parts = line.split_whitespace();
+            let op = parts.next().unwrap_or(line);
+            let val = parts
+                .next()
+                .ok_or_else(|| ParseError::MissingArgument(source.locate_end(line)))?;
+            let val = val
+                .parse::<i32>()
+                .map_err(|_| ParseError::InvalidArgument(source.locate(val)))?;
+            match op {
+                "nop" => Ok(Instruction::Nop(val)),
+                "acc" => Ok(Instruction::Acc(val)),
+                "jmp" => Ok(Instruction::Jmp(val)),
+                _ => Err(ParseError::InvalidOperation(source.locate(op))),
+            }
+        })
+        .collect()
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The puzzle of day 8.
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = BootCode;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source).map(BootCode::new)
+    }
+
+    fn part1(&self, boot_code: &Self::Input) -> Answer {
+        match boot_code.run() {
+            BootCodeResult::Cyclic(acc) => acc.into(),
+            BootCodeResult::Terminated(_) => Answer::Unsolved,
+        }
+    }
+
+    fn part2(&self, boot_code: &Self::Input) -> Answer {
+        match boot_code.run_with_fix() {
+            BootCodeResult::Terminated(acc) => acc.into(),
+            BootCodeResult::Cyclic(_) => Answer::Unsolved,
+        }
+    }
+}
+
+/// Runs the instructions for at most `steps` steps, returning the
+/// accumulator if they terminate in time.
+fn run_for(instructions: &[Instruction], steps: usize) -> Option<i32> {
+    let (mut acc, mut ip) = (0, 0);
+    for _ in 0..steps {
+        if ip == instructions.len() {
+            return Some(acc);
+        }
+        match instructions[ip] {
+            Instruction::Nop(_) => ip += 1,
+            Instruction::Acc(val) => {
+                acc += val;
+                ip += 1;
+            }
+            Instruction::Jmp(val) => ip = jmp_ip(ip, val),
+        }
+    }
+    (ip == instructions.len()).then_some(acc)
+}
+
+/// Returns the instructions with the `jmp` or `nop` at `ip` swapped.
+fn swap(instructions: &[Instruction], ip: usize) -> Option<Vec<Instruction>> {
+    let ins = match instructions[ip] {
+        Instruction::Nop(val) => Instruction::Jmp(val),
+        Instruction::Jmp(val) => Instruction::Nop(val),
+        Instruction::Acc(_) => return None,
+    };
+    let mut instructions = instructions.to_vec();
+    instructions[ip] = ins;
+    Some(instructions)
+}
+
+impl Oracle for Day08 {
+    /// Generates `size` instructions that loop forever, unless exactly one
+    /// of them is swapped.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let n = size.max(2);
+        loop {
+            let instructions = (0..n)
+                .map(|ip| {
+                    // Jumps never leave the boot code.
+                    let val = rng.range(0, n + 1) as i32 - ip as i32;
+                    match rng.range(0, 3) {
+                        0 => Instruction::Nop(val),
+                        1 => Instruction::Acc(rng.range(0, 21) as i32 - 10),
+                        _ => Instruction::Jmp(val),
+                    }
+                })
+                .collect::<Vec<_>>();
+            let fixes = (0..n)
+                .filter_map(|ip| swap(&instructions, ip))
+                .filter(|fixed| run_for(fixed, n + 1).is_some())
+                .count();
+            if run_for(&instructions, n + 1).is_none() && fixes == 1 {
+                return instructions
+                    .iter()
+                    .map(|ins| format!("{}\n", ins))
+                    .collect();
+            }
+        }
+    }
+
+    fn oracle(&self, boot_code: &Self::Input, part: usize) -> Answer {
+        let instructions = boot_code.instructions();
+        // Without repeating an instruction, the boot code runs for at most
+        // as many steps as it has instructions.
+        let steps = instructions.len() + 1;
+        if part == 1 {
+            // Run one more step each time, until the next one would repeat.
+            let mut visited = vec![0];
+            let mut ip = 0;
+            loop {
+                ip = match instructions[ip] {
+                    Instruction::Jmp(val) => jmp_ip(ip, val),
+                    _ => ip + 1,
+                };
+                if ip == instructions.len() {
+                    return Answer::Unsolved;
+                } else if visited.contains(&ip) {
+                    let acc = visited.iter().map(|&ip| match instructions[ip] {
+                        Instruction::Acc(val) => val,
+                        _ => 0,
+                    });
+                    return acc.sum::<i32>().into();
+                }
+                visited.push(ip);
+            }
+        } else {
+            (0..instructions.len())
+                .filter_map(|ip| swap(instructions, ip))
+                .find_map(|fixed| run_for(&fixed, steps))
+                .into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input() {
+        let instructions = parse_input("example.txt").unwrap();
+        let boot_code = BootCode::new(instructions);
+        assert_eq!(boot_code.run(), BootCodeResult::Cyclic(5));
+        assert_eq!(boot_code.run_with_fix(), BootCodeResult::Terminated(8));
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day08, 10);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day08, 8, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let boot_code = Day08.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(Day08.part1(&boot_code), Answer::Number(5));
+        assert_eq!(Day08.part2(&boot_code), Answer::Number(8));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "nop +0\nacc +1\nhop -3\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid operation `hop`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (3, 1));
+    }
+}
@@ -0,0 +1,254 @@
+//! Solution to the puzzle of day 9, _Encoding Error_.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+/// The preamble length of the puzzle input.
+pub const PREAMBLE_LENGTH: usize = 25;
+
+/// Returns the first number that isn't the sum of two of the
+/// `preamble_length` numbers before it.
+pub fn find_encoding_error(numbers: &[usize], preamble_length: usize) -> Option<usize> {
+    let mut preamble = HashSet::new();
+
+    for (i, &num) in numbers.iter().enumerate() {
+        if preamble.len() >= preamble_length {
+            if !preamble
+                .iter()
+                .any(|&x| x < num && x * 2 != num && preamble.contains(&(num - x)))
+            {
+                return Some(num);
+            }
+            preamble.remove(&numbers[i - preamble_length]);
+        }
+        preamble.insert(num);
+    }
+
+    None
+}
+
+/// Returns the sum of the smallest and largest numbers of a contiguous
+/// range adding up to `error`.
+pub fn find_encryption_weakness(numbers: &[usize], error: usize) -> Option<usize> {
+    (2..numbers.len())
+        .flat_map(|x| numbers.windows(x))
+        .find_map(|w| {
+            if error == w.iter().sum() {
+                Some(w.iter().min().unwrap() + w.iter().max().unwrap())
+            } else {
+                None
+            }
+        })
+}
+
+/// An error found while parsing the XMAS data.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A line isn't a number.
+    InvalidNumber(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidNumber(loc) => write!(f, "invalid number `{}`", loc.token),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidNumber(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the XMAS data, one number per line.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<Vec<usize>, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Vec<usize>, ParseError> {
+    source
+        .content()
+        .lines()
+        .map(|x| {
+            x.parse()
+                .map_err(|_| ParseError::InvalidNumber(source.locate(x)))
+        })
+        .collect()
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The puzzle of day 9.
+pub struct Day09 {
+    /// Length of the preamble.
+    pub preamble_length: usize,
+}
+
+impl Default for Day09 {
+    fn default() -> Self {
+        Day09 {
+            preamble_length: PREAMBLE_LENGTH,
+        }
+    }
+}
+
+impl Solution for Day09 {
+    type Input = Vec<usize>;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, numbers: &Self::Input) -> Answer {
+        find_encoding_error(numbers, self.preamble_length).into()
+    }
+
+    fn part2(&self, numbers: &Self::Input) -> Answer {
+        find_encoding_error(numbers, self.preamble_length)
+            .and_then(|error| find_encryption_weakness(numbers, error))
+            .into()
+    }
+}
+
+/// Returns whether a number is the sum of two different numbers.
+fn is_sum(numbers: &[usize], num: usize) -> bool {
+    numbers
+        .iter()
+        .enumerate()
+        .any(|(i, a)| numbers[i + 1..].iter().any(|b| a != b && a + b == num))
+}
+
+/// Returns the contiguous sets of at least two numbers that add up to a
+/// number, as ranges.
+fn sum_ranges(numbers: &[usize], num: usize) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    for start in 0..numbers.len() {
+        for end in start + 2..=numbers.len() {
+            if numbers[start..end].iter().sum::<usize>() == num {
+                ranges.push((start, end));
+            }
+        }
+    }
+    ranges
+}
+
+impl Oracle for Day09 {
+    /// Generates `size` distinct numbers after the preamble, one of which
+    /// is the sum of a contiguous set of numbers but not of two of the ones
+    /// before it.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let p = self.preamble_length;
+        let len = p + size.max(1);
+        'retry: loop {
+            let mut numbers = Vec::new();
+            while numbers.len() < p {
+                let num = rng.range(1, 4 * p);
+                if !numbers.contains(&num) {
+                    numbers.push(num);
+                }
+            }
+            let error_index = rng.range(p, len);
+            while numbers.len() < len {
+                let i = numbers.len();
+                let num = if i == error_index {
+                    let start = rng.range(0, i - 1);
+                    numbers[start..rng.range(start + 2, i + 1)].iter().sum()
+                } else {
+                    let window = &numbers[i - p..];
+                    window[rng.range(0, p)] + window[rng.range(0, p)]
+                };
+                let valid = is_sum(&numbers[i - p..], num);
+                if numbers.contains(&num) || valid == (i == error_index) {
+                    continue 'retry;
+                }
+                numbers.push(num);
+            }
+            if sum_ranges(&numbers, numbers[error_index]).len() == 1 {
+                return numbers.iter().map(|x| format!("{}\n", x)).collect();
+            }
+        }
+    }
+
+    fn oracle(&self, numbers: &Self::Input, part: usize) -> Answer {
+        let p = self.preamble_length;
+        let error = (p..numbers.len())
+            .find(|&i| !is_sum(&numbers[i - p..i], numbers[i]))
+            .map(|i| numbers[i]);
+        if part == 1 {
+            error.into()
+        } else {
+            error
+                .and_then(|error| sum_ranges(numbers, error).first().copied())
+                .map(|(start, end)| {
+                    let range = &numbers[start..end];
+                    range.iter().min().unwrap() + range.iter().max().unwrap()
+                })
+                .into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input() {
+        let numbers = parse_input("example.txt").unwrap();
+        let error = find_encoding_error(&numbers, 5);
+        let encryption_weakness = find_encryption_weakness(&numbers, error.unwrap());
+        assert_eq!(error, Some(127));
+        assert_eq!(encryption_weakness, Some(62));
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day09 { preamble_length: 5 }, 20);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day09::default(), 9, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let day = Day09 { preamble_length: 5 };
+        let numbers = day.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(day.part1(&numbers), Answer::Number(127));
+        assert_eq!(day.part2(&numbers), Answer::Number(62));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "35\n20\n-15\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid number `-15`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (3, 1));
+    }
+}
@@ -0,0 +1,210 @@
+//! Solution to the puzzle of day 10, _Adapter Array_.
+
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+/// An error found while parsing the adapter ratings.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A line isn't a joltage rating.
+    InvalidRating(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidRating(loc) => write!(f, "invalid joltage rating `{}`", loc.token),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidRating(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the adapter ratings, adding the device.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<Vec<usize>, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Vec<usize>, ParseError> {
+    let mut jolts = source
+        .content()
+        .lines()
+        .map(|x| {
+            x.parse()
+                .map_err(|_| ParseError::InvalidRating(source.locate(x)))
+        })
+        .collect::<Result<Vec<usize>, _>>()?;
+
+    jolts.sort_unstable();
+    jolts.push(jolts.last().map_or(0, |x| *x) + 3);
+    Ok(jolts)
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// Multiplies the number of 1-jolt and 3-jolt differences.
+pub fn count_differences(jolts: &[usize]) -> Option<usize> {
+    let (mut prev_jolt, mut diff1, mut diff3) = (0, 0, 0);
+
+    for &jolt in jolts {
+        match jolt - prev_jolt {
+            1 => diff1 += 1,
+            3 => diff3 += 1,
+            2 => (),
+            _ => return None,
+        }
+        prev_jolt = jolt;
+    }
+
+    Some(diff1 * diff3)
+}
+
+/// Counts the ways the adapters can be arranged.
+pub fn count_arrangements(jolts: &[usize]) -> usize {
+    let mut counts = vec![0; *jolts.last().unwrap() + 1];
+    counts[0] = 1;
+
+    // Algorithm:
+    //   Q: How many arrangements are possible for jolt `j`?
+    //   A: As many as the sum of the arrangements for jolts `j - 1`,
+    //   `j - 2` and `j - 3`.
+    for &jolt in [0].iter().chain(jolts.iter().take(jolts.len() - 1)) {
+        counts[jolt + 1] += counts[jolt];
+        counts[jolt + 2] += counts[jolt];
+        counts[jolt + 3] += counts[jolt];
+    }
+
+    *counts.last().unwrap()
+}
+
+/// The puzzle of day 10.
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<usize>;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, jolts: &Self::Input) -> Answer {
+        count_differences(jolts).into()
+    }
+
+    fn part2(&self, jolts: &Self::Input) -> Answer {
+        count_arrangements(jolts).into()
+    }
+}
+
+impl Oracle for Day10 {
+    /// Generates `size` distinct adapters, 1 to 3 jolts apart, shuffled.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let mut jolt = 0;
+        let mut jolts = (0..size.max(1))
+            .map(|_| {
+                jolt += rng.range(1, 4);
+                jolt
+            })
+            .collect::<Vec<_>>();
+        rng.shuffle(&mut jolts);
+        jolts.iter().map(|x| format!("{}\n", x)).collect()
+    }
+
+    fn oracle(&self, jolts: &Self::Input, part: usize) -> Answer {
+        let (adapters, device) = jolts.split_at(jolts.len() - 1);
+        if part == 1 {
+            let mut chain = vec![0];
+            chain.extend(jolts);
+            let diffs = chain.windows(2).map(|w| w[1] - w[0]).collect::<Vec<_>>();
+            if diffs.iter().any(|&x| x > 3) {
+                return Answer::Unsolved;
+            }
+            let count = |diff| diffs.iter().filter(|&&x| x == diff).count();
+            (count(1) * count(3)).into()
+        } else {
+            // Try every subset of the adapters.
+            let count = (0..1usize << adapters.len())
+                .filter(|subset| {
+                    let mut chain = vec![0];
+                    chain.extend((0..adapters.len()).filter_map(|i| match subset >> i & 1 {
+                        1 => Some(adapters[i]),
+                        _ => None,
+                    }));
+                    chain.push(device[0]);
+                    chain.windows(2).all(|w| w[1] - w[0] <= 3)
+                })
+                .count();
+            count.into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input_1() {
+        let jolts = parse_input("example1.txt").unwrap();
+        assert_eq!(count_differences(&jolts), Some(35));
+        assert_eq!(count_arrangements(&jolts), 8);
+    }
+
+    #[test]
+    fn test_example_input_2() {
+        let jolts = parse_input("example2.txt").unwrap();
+        assert_eq!(count_differences(&jolts), Some(220));
+        assert_eq!(count_arrangements(&jolts), 19208);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day10, 12);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day10, 10, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let jolts = Day10.parse(Path::new("example1.txt")).unwrap();
+        assert_eq!(Day10.part1(&jolts), Answer::Number(35));
+        assert_eq!(Day10.part2(&jolts), Answer::Number(8));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "16\n10\n15\n5 1\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid joltage rating `5 1`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (4, 1));
+    }
+}
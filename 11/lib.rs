@@ -0,0 +1,330 @@
+//! Solution to the puzzle of day 11, _Seating System_.
+
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+#[derive(Clone, Copy)]
+struct Move(isize, isize);
+
+const MOVES: [Move; 8] = [
+    Move(-1, -1),
+    Move(-1, 0),
+    Move(-1, 1),
+    Move(0, 1),
+    Move(1, 1),
+    Move(1, 0),
+    Move(1, -1),
+    Move(0, -1),
+];
+
+/// The status of a position in the seat layout.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SeatStatus {
+    Empty,
+    Occupied,
+    Floor,
+}
+
+/// The seat layout and the rules for people to move around.
+pub struct SeatingSystem {
+    seats: Vec<Vec<SeatStatus>>,
+    nrows: isize,
+    ncols: isize,
+    swap_threshold: u32,
+    recurse: bool,
+}
+
+impl SeatingSystem {
+    /// Creates a seating system. People leave a seat when `swap_threshold`
+    /// seats are occupied, looking past the floor if `recurse` is set.
+    pub fn new(seats: Vec<Vec<SeatStatus>>, swap_threshold: u32, recurse: bool) -> Self {
+        SeatingSystem {
+            nrows: seats.len() as isize,
+            ncols: seats[0].len() as isize,
+            seats,
+            swap_threshold,
+            recurse,
+        }
+    }
+
+    /// Counts the occupied seats once the layout stabilizes.
+    pub fn count_occupied(&mut self) -> usize {
+        while self.iterate() {}
+        self.seats
+            .iter()
+            .flat_map(|x| x.iter())
+            .filter(|&&x| x == SeatStatus::Occupied)
+            .count()
+    }
+
+    /// Returns the current seat layout.
+    pub fn seats(&self) -> &[Vec<SeatStatus>] {
+        &self.seats
+    }
+
+    /// Applies the rules once and returns whether any seat changed.
+    pub fn iterate(&mut self) -> bool {
+        let mut copy = self.seats.clone();
+        let mut changed = false;
+
+        for (r, row) in copy.iter_mut().enumerate() {
+            for (c, seat_status) in row.iter_mut().enumerate() {
+                match seat_status {
+                    SeatStatus::Empty => {
+                        let num_occupied = self.count_occupied_neighbors(r, c);
+                        if num_occupied == 0 {
+                            *seat_status = SeatStatus::Occupied;
+                            changed = true;
+                        }
+                    }
+                    SeatStatus::Occupied => {
+                        let num_occupied = self.count_occupied_neighbors(r, c);
+                        if num_occupied >= self.swap_threshold {
+                            *seat_status = SeatStatus::Empty;
+                            changed = true;
+                        }
+                    }
+                    SeatStatus::Floor => (),
+                }
+            }
+        }
+
+        self.seats = copy;
+        changed
+    }
+
+    fn count_occupied_neighbors(&self, row: usize, col: usize) -> u32 {
+        MOVES
+            .iter()
+            .map(|&x| self.check_seat(row as isize, col as isize, x))
+            .sum()
+    }
+
+    fn check_seat(&self, row: isize, col: isize, mov: Move) -> u32 {
+        let (row, col) = (row + mov.0, col + mov.1);
+        if row < 0 || col < 0 || row == self.nrows || col == self.ncols {
+            return 0;
+        }
+
+        match self.seats[row as usize][col as usize] {
+            SeatStatus::Empty => 0,
+            SeatStatus::Occupied => 1,
+            SeatStatus::Floor => match self.recurse {
+                true => self.check_seat(row, col, mov),
+                false => 0,
+            },
+        }
+    }
+}
+
+/// An error found while parsing the seat layout.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A position is neither floor (`.`), an empty seat (`L`) nor an
+    /// occupied seat (`#`).
+    InvalidSeat(Location),
+    /// A row is not as wide as the first one.
+    InvalidWidth(Location),
+    /// The layout has no seats.
+    Empty,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidSeat(loc) => write!(f, "invalid seat `{}`", loc.token),
+            ParseError::InvalidWidth(_) => write!(f, "row width differs from the first row"),
+            ParseError::Empty => write!(f, "empty seat layout"),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) | ParseError::Empty => None,
+            ParseError::InvalidSeat(loc) | ParseError::InvalidWidth(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the seat layout.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<Vec<Vec<SeatStatus>>, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Vec<Vec<SeatStatus>>, ParseError> {
+    let content = source.content();
+    let ncols = content.lines().next().map_or(0, |x| x.chars().count());
+    if ncols == 0 {
+        return Err(ParseError::Empty);
+    }
+
+    content
+        .lines()
+        .map(|line| {
+            if line.chars().count() != ncols {
+                return Err(ParseError::InvalidWidth(source.locate(line)));
+            }
+            line.char_indices()
+                .map(|(i, ch)| match ch {
+                    'L' => Ok(SeatStatus::Empty),
+                    '#' => Ok(SeatStatus::Occupied),
+                    '.' => Ok(SeatStatus::Floor),
+                    _ => {
+                        let seat = &line[i..i + ch.len_utf8()];
+                        Err(ParseError::InvalidSeat(source.locate(seat)))
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Vec<Vec<SeatStatus>>, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The puzzle of day 11.
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Vec<Vec<SeatStatus>>;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, seats: &Self::Input) -> Answer {
+        let mut seating_system = SeatingSystem::new(seats.clone(), 4, false);
+        seating_system.count_occupied().into()
+    }
+
+    fn part2(&self, seats: &Self::Input) -> Answer {
+        let mut seating_system = SeatingSystem::new(seats.clone(), 5, true);
+        seating_system.count_occupied().into()
+    }
+}
+
+impl Oracle for Day11 {
+    /// Generates a layout of `size` by `size` empty seats and floor.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let mut input = String::new();
+        for _ in 0..size.max(1) {
+            for _ in 0..size.max(1) {
+                input.push(if rng.ratio(3, 4) { 'L' } else { '.' });
+            }
+            input.push('\n');
+        }
+        input
+    }
+
+    fn oracle(&self, seats: &Self::Input, part: usize) -> Answer {
+        let (nrows, ncols) = (seats.len() as isize, seats[0].len() as isize);
+        let (threshold, max_distance) = match part {
+            1 => (4, 1),
+            _ => (5, nrows.max(ncols)),
+        };
+        let mut seats = seats.to_vec();
+        loop {
+            // Look in every direction, as far as allowed, for the first seat.
+            let occupied = |r: isize, c: isize| {
+                let mut count = 0;
+                for (dr, dc) in MOVES.iter().map(|m| (m.0, m.1)) {
+                    for k in 1..=max_distance {
+                        let (r, c) = (r + k * dr, c + k * dc);
+                        if !(0..nrows).contains(&r) || !(0..ncols).contains(&c) {
+                            break;
+                        }
+                        match seats[r as usize][c as usize] {
+                            SeatStatus::Occupied => count += 1,
+                            SeatStatus::Empty => (),
+                            SeatStatus::Floor => continue,
+                        }
+                        break;
+                    }
+                }
+                count
+            };
+            let next = (0..nrows)
+                .map(|r| {
+                    (0..ncols)
+                        .map(|c| match seats[r as usize][c as usize] {
+                            SeatStatus::Empty if occupied(r, c) == 0 => SeatStatus::Occupied,
+                            SeatStatus::Occupied if occupied(r, c) >= threshold => {
+                                SeatStatus::Empty
+                            }
+                            seat => seat,
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            if next == seats {
+                break;
+            }
+            seats = next;
+        }
+        let occupied = seats.iter().flatten();
+        occupied
+            .filter(|&&x| x == SeatStatus::Occupied)
+            .count()
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input() {
+        let seats = parse_input("example.txt").unwrap();
+        let mut seating_system = SeatingSystem::new(seats.clone(), 4, false);
+        assert_eq!(seating_system.count_occupied(), 37);
+
+        let mut seating_system = SeatingSystem::new(seats, 5, true);
+        assert_eq!(seating_system.count_occupied(), 26);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day11, 8);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day11, 11, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let seats = Day11.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(Day11.part1(&seats), Answer::Number(37));
+        assert_eq!(Day11.part2(&seats), Answer::Number(26));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "L.LL\nLLLL\nL.X.\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid seat `X`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (3, 3));
+    }
+}
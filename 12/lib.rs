@@ -0,0 +1,272 @@
+//! Solution to the puzzle of day 12, _Rain Risk_.
+
+use std::fmt;
+use std::path::Path;
+
+use num::complex::Complex;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+/// A navigation instruction.
+pub enum Move {
+    North(isize),
+    East(isize),
+    South(isize),
+    West(isize),
+    Left(isize),
+    Right(isize),
+    Forward(isize),
+}
+
+fn re(n: isize) -> Complex<isize> {
+    Complex { re: n, im: 0 }
+}
+
+fn im(n: isize) -> Complex<isize> {
+    Complex { re: 0, im: n }
+}
+
+fn nrots(n: isize) -> u32 {
+    n as u32 / 90
+}
+
+/// Returns the Manhattan distance travelled moving the ship directly.
+pub fn solve_part1(moves: &[Move]) -> isize {
+    let mut coord = re(0);
+    let mut dir = re(1);
+
+    for mov in moves {
+        match mov {
+            Move::North(n) => coord += im(*n),
+            Move::East(n) => coord += re(*n),
+            Move::South(n) => coord += -im(*n),
+            Move::West(n) => coord += -re(*n),
+            Move::Left(r) => dir *= im(1).powu(nrots(*r)),
+            Move::Right(r) => dir *= im(-1).powu(nrots(*r)),
+            Move::Forward(n) => coord += dir * n,
+        }
+    }
+
+    coord.l1_norm()
+}
+
+/// Returns the Manhattan distance travelled moving the ship towards the
+/// waypoint.
+pub fn solve_part2(moves: &[Move]) -> isize {
+    let mut ship = re(0);
+    let mut waypoint = Complex { re: 10, im: 1 };
+
+    for mov in moves {
+        match mov {
+            Move::North(n) => waypoint += im(*n),
+            Move::East(n) => waypoint += re(*n),
+            Move::South(n) => waypoint += -im(*n),
+            Move::West(n) => waypoint += -re(*n),
+            Move::Left(r) => waypoint *= im(1).powu(nrots(*r)),
+            Move::Right(r) => waypoint *= im(-1).powu(nrots(*r)),
+            Move::Forward(n) => ship += waypoint * n,
+        }
+    }
+
+    ship.l1_norm()
+}
+
+/// An error found while parsing the navigation instructions.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// The action isn't one of `N`, `S`, `E`, `W`, `L`, `R` or `F`.
+    InvalidAction(Location),
+    /// The value isn't a number.
+    InvalidValue(Location),
+    /// The line is empty.
+    MissingAction(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidAction(loc) => write!(f, "invalid action `{}`", loc.token),
+            ParseError::InvalidValue(loc) => write!(f, "invalid value `{}`", loc.token),
+            ParseError::MissingAction(_) => write!(f, "missing action"),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidAction(loc)
+            | ParseError::InvalidValue(loc)
+            | ParseError::MissingAction(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the navigation instructions.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<Vec<Move>, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Vec<Move>, ParseError> {
+    source
+        .content()
+        .lines()
+        .map(|line| {
+            let action = match line.chars().next() {
+                Some(ch) => &line[..ch.len_utf8()],
+                None => return Err(ParseError::MissingAction(source.locate(line))),
+            };
+            let value = &line[action.len()..];
+            let num = value
+                .parse::<isize>()
+                .map_err(|_| ParseError::InvalidValue(source.locate(value)))?;
+            match action {
+                "N" => Ok(Move::North(num)),
+                "E" => Ok(Move::East(num)),
+                "S" => Ok(Move::South(num)),
+                "W" => Ok(Move::West(num)),
+                "R" => Ok(Move::Right(num)),
+                "L" => Ok(Move::Left(num)),
+                "F" => Ok(Move::Forward(num)),
+                _ => Err(ParseError::InvalidAction(source.locate(action))),
+            }
+        })
+        .collect()
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Vec<Move>, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The puzzle of day 12.
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Vec<Move>;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, moves: &Self::Input) -> Answer {
+        solve_part1(moves).into()
+    }
+
+    fn part2(&self, moves: &Self::Input) -> Answer {
+        solve_part2(moves).into()
+    }
+}
+
+impl Oracle for Day12 {
+    /// Generates `size` navigation instructions, turning by multiples of 90
+    /// degrees.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        (0..size)
+            .map(
+                |_| match *rng.choose(&['N', 'E', 'S', 'W', 'L', 'R', 'F']) {
+                    action @ ('L' | 'R') => format!("{}{}\n", action, 90 * rng.range(0, 4)),
+                    action => format!("{}{}\n", action, rng.range(0, 20)),
+                },
+            )
+            .collect()
+    }
+
+    fn oracle(&self, moves: &Self::Input, part: usize) -> Answer {
+        // East, south, west and north, in clockwise order.
+        const HEADINGS: [(isize, isize); 4] = [(1, 0), (0, -1), (-1, 0), (0, 1)];
+        let (mut x, mut y) = (0, 0);
+        let mut heading = 0;
+        let (mut wx, mut wy) = (10, 1);
+
+        // Moves the ship, or the waypoint in part 2, one unit at a time.
+        let step = |x: &mut isize, y: &mut isize, (dx, dy): (isize, isize), n: isize| {
+            for _ in 0..n {
+                *x += dx;
+                *y += dy;
+            }
+        };
+        for mov in moves {
+            let (target_x, target_y) = match part {
+                1 => (&mut x, &mut y),
+                _ => (&mut wx, &mut wy),
+            };
+            match *mov {
+                Move::North(n) => step(target_x, target_y, HEADINGS[3], n),
+                Move::East(n) => step(target_x, target_y, HEADINGS[0], n),
+                Move::South(n) => step(target_x, target_y, HEADINGS[1], n),
+                Move::West(n) => step(target_x, target_y, HEADINGS[2], n),
+                Move::Left(r) | Move::Right(r) => {
+                    let quarters = match mov {
+                        Move::Right(_) => r / 90,
+                        _ => 3 * r / 90,
+                    };
+                    for _ in 0..quarters {
+                        heading = (heading + 1) % 4;
+                        (wx, wy) = (wy, -wx);
+                    }
+                }
+                Move::Forward(n) => {
+                    let direction = match part {
+                        1 => HEADINGS[heading],
+                        _ => (wx, wy),
+                    };
+                    step(&mut x, &mut y, direction, n);
+                }
+            }
+        }
+        (x.abs() + y.abs()).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input() {
+        let moves = parse_input("example.txt").unwrap();
+        assert_eq!(solve_part1(&moves), 25);
+        assert_eq!(solve_part2(&moves), 286);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day12, 50);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day12, 12, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let moves = Day12.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(Day12.part1(&moves), Answer::Number(25));
+        assert_eq!(Day12.part2(&moves), Answer::Number(286));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "F10\nN3\nF7\nR9O\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid value `9O`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (4, 2));
+    }
+}
@@ -0,0 +1,260 @@
+//! Solution to the puzzle of day 13, _Shuttle Search_.
+
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+/// Multiplies the ID of the earliest bus departing after `timestamp` by
+/// the minutes to wait for it.
+pub fn solve_part1(timestamp: usize, schedule: &[(usize, usize)]) -> usize {
+    let mut times = schedule
+        .iter()
+        .map(|(x, _)| (x, (x - timestamp % x) % x))
+        .collect::<Vec<_>>();
+
+    times.sort_by_key(|(_, ts)| *ts);
+    times[0].1 * times[0].0
+}
+
+/// Returns the earliest timestamp at which each bus departs at its
+/// offset in the schedule.
+pub fn solve_part2(schedule: &[(usize, usize)]) -> usize {
+    /* The algorithm assumes that all the bus ids are co-prime, and as
+     * that implies that gcd(bus1, bus2) is 1, we can derive lcm(bus1,
+     * bus2) by simply multiplying the bus ids together: bus1 * bus2.
+     */
+    let mut num = 0;
+    let mut step = 1;
+    let mut rem = schedule;
+
+    while !rem.is_empty() {
+        let (bus_id, distance) = rem[0];
+        num = (num..)
+            .step_by(step)
+            .find(|x| (x + distance) % bus_id == 0)
+            .unwrap();
+        step *= bus_id;
+        rem = &rem[1..];
+    }
+
+    num
+}
+
+/// An error found while parsing the notes.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// The first line isn't a timestamp.
+    InvalidTimestamp(Location),
+    /// A bus ID isn't a positive number or `x`.
+    InvalidBusId(Location),
+    /// The notes end before the bus schedule.
+    MissingSchedule(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidTimestamp(loc) => write!(f, "invalid timestamp `{}`", loc.token),
+            ParseError::InvalidBusId(loc) => write!(f, "invalid bus ID `{}`", loc.token),
+            ParseError::MissingSchedule(_) => write!(f, "missing bus schedule"),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidTimestamp(loc)
+            | ParseError::InvalidBusId(loc)
+            | ParseError::MissingSchedule(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the earliest departure timestamp and the `(bus ID, offset)`
+/// pairs of the schedule.
+pub fn parse_input(
+    file_name: impl AsRef<Path>,
+) -> Result<(usize, Vec<(usize, usize)>), ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<(usize, Vec<(usize, usize)>), ParseError> {
+    let content = source.content();
+    let mut lines = content.lines();
+
+    let timestamp = lines.next().unwrap_or(content);
+    let timestamp = timestamp
+        .parse()
+        .map_err(|_| ParseError::InvalidTimestamp(source.locate(timestamp)))?;
+    let schedule = lines
+        .next()
+        .ok_or_else(|| ParseError::MissingSchedule(source.locate_end(content)))?
+        .split(',')
+        .enumerate()
+        .filter(|(_, b)| !b.starts_with('x'))
+        .map(|(i, b)| match b.parse() {
+            Ok(bus_id) if bus_id > 0 => Ok((bus_id, i)),
+            _ => Err(ParseError::InvalidBusId(source.locate(b))),
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok((timestamp, schedule))
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<(usize, Vec<(usize, usize)>), ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The puzzle of day 13.
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = (usize, Vec<(usize, usize)>);
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, (timestamp, schedule): &Self::Input) -> Answer {
+        solve_part1(*timestamp, schedule).into()
+    }
+
+    fn part2(&self, (_, schedule): &Self::Input) -> Answer {
+        solve_part2(schedule).into()
+    }
+}
+
+impl Oracle for Day13 {
+    /// Generates a schedule of `size` buses, with distinct prime IDs, none
+    /// of which departs as early as another after the timestamp.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        const PRIMES: [usize; 8] = [2, 3, 5, 7, 11, 13, 17, 19];
+        loop {
+            let timestamp = rng.range(0, 1000);
+            let mut bus_ids = PRIMES.to_vec();
+            rng.shuffle(&mut bus_ids);
+            bus_ids.truncate(size.clamp(1, PRIMES.len()));
+
+            let mut waits = bus_ids
+                .iter()
+                .map(|x| (x - timestamp % x) % x)
+                .collect::<Vec<_>>();
+            waits.sort_unstable();
+            if waits.windows(2).any(|w| w[0] == w[1]) {
+                continue;
+            }
+
+            let mut schedule = Vec::new();
+            for bus_id in bus_ids {
+                while rng.ratio(1, 3) {
+                    schedule.push("x".to_owned());
+                }
+                schedule.push(bus_id.to_string());
+            }
+            return format!("{}\n{}\n", timestamp, schedule.join(","));
+        }
+    }
+
+    fn oracle(&self, (timestamp, schedule): &Self::Input, part: usize) -> Answer {
+        if part == 1 {
+            (*timestamp..)
+                .find_map(|t| {
+                    let bus = schedule.iter().find(|(bus_id, _)| t % bus_id == 0);
+                    bus.map(|(bus_id, _)| bus_id * (t - timestamp))
+                })
+                .into()
+        } else {
+            (0..)
+                .find(|t| {
+                    let mut buses = schedule.iter();
+                    buses.all(|(bus_id, offset)| (t + offset) % bus_id == 0)
+                })
+                .into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input_1() {
+        let (timestamp, schedule) = parse_input("example1.txt").unwrap();
+        assert_eq!(solve_part1(timestamp, &schedule), 295);
+        assert_eq!(solve_part2(&schedule), 1068781);
+    }
+
+    #[test]
+    fn test_example_input_2() {
+        let (_, schedule) = parse_input("example2.txt").unwrap();
+        assert_eq!(solve_part2(&schedule), 3417);
+    }
+
+    #[test]
+    fn test_example_input_3() {
+        let (_, schedule) = parse_input("example3.txt").unwrap();
+        assert_eq!(solve_part2(&schedule), 754018);
+    }
+
+    #[test]
+    fn test_example_input_4() {
+        let (_, schedule) = parse_input("example4.txt").unwrap();
+        assert_eq!(solve_part2(&schedule), 779210);
+    }
+
+    #[test]
+    fn test_example_input_5() {
+        let (_, schedule) = parse_input("example5.txt").unwrap();
+        assert_eq!(solve_part2(&schedule), 1261476);
+    }
+
+    #[test]
+    fn test_example_input_6() {
+        let (_, schedule) = parse_input("example6.txt").unwrap();
+        assert_eq!(solve_part2(&schedule), 1202161486);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day13, 5);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day13, 13, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let notes = Day13.parse(Path::new("example1.txt")).unwrap();
+        assert_eq!(Day13.part1(&notes), Answer::Number(295));
+        assert_eq!(Day13.part2(&notes), Answer::Number(1068781));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "939\n7,13,x,x,59,x,31,0\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid bus ID `0`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (2, 18));
+    }
+}
@@ -0,0 +1,329 @@
+//! Solution to the puzzle of day 14, _Docking Data_.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+/// An instruction of the initialization program.
+#[derive(Debug)]
+pub enum Instruction {
+    Mask(String),
+    Mem(usize, usize),
+}
+
+fn bit2char(x: usize) -> char {
+    if x & 1 > 0 {
+        '1'
+    } else {
+        '0'
+    }
+}
+
+fn get_floating_address(floating_mask: &str, addr: usize) -> String {
+    let len = floating_mask.chars().count();
+    floating_mask
+        .chars()
+        .enumerate()
+        .map(|(i, c)| match c {
+            '0' => bit2char(addr >> (len - i - 1)),
+            _ => c,
+        })
+        .collect()
+}
+
+fn expand_address(floating_addr: &str, map: usize) -> usize {
+    let mut i = -1;
+    let expanded_addr = floating_addr
+        .chars()
+        .map(|c| match c {
+            'X' => {
+                i += 1;
+                bit2char(map >> i)
+            }
+            _ => c,
+        })
+        .collect::<String>();
+
+    usize::from_str_radix(&expanded_addr, 2).unwrap()
+}
+
+/// Sums the memory values after running the program with value masks.
+pub fn solve_part1(instructions: &[Instruction]) -> usize {
+    let mut mask_clear = 0;
+    let mut mask_set = 0;
+    let mut memory = HashMap::new();
+
+    for instruction in instructions {
+        match instruction {
+            Instruction::Mask(mask) => {
+                mask_clear = usize::from_str_radix(&mask.replace('X', "1"), 2).unwrap();
+                mask_set = usize::from_str_radix(&mask.replace('X', "0"), 2).unwrap();
+            }
+            Instruction::Mem(addr, val) => {
+                memory.insert(addr, val & mask_clear | mask_set);
+            }
+        }
+    }
+
+    memory.values().sum()
+}
+
+/// Sums the memory values after running the program with memory address
+/// decoders.
+pub fn solve_part2(instructions: &[Instruction]) -> usize {
+    let mut mask = String::new();
+    let mut memory = HashMap::new();
+
+    for instruction in instructions {
+        match instruction {
+            Instruction::Mask(m) => mask = m.to_string(),
+            Instruction::Mem(addr, val) => {
+                let floating_address = get_floating_address(&mask, *addr);
+                let x_count = floating_address.chars().filter(|&x| x == 'X').count();
+                (0..2usize.pow(x_count as u32))
+                    .map(|x| expand_address(&floating_address, x))
+                    .for_each(|x| {
+                        memory.insert(x, *val);
+                    });
+            }
+        }
+    }
+
+    memory.values().sum()
+}
+
+/// An error found while parsing the initialization program.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A line isn't a `mask = ...` or `mem[...] = ...` instruction.
+    InvalidInstruction(Location),
+    /// The mask isn't made of 36 `X`, `0` or `1` characters.
+    InvalidMask(Location),
+    /// The memory address isn't a number.
+    InvalidAddress(Location),
+    /// The value isn't a number.
+    InvalidValue(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidInstruction(loc) => write!(f, "invalid instruction `{}`", loc.token),
+            ParseError::InvalidMask(loc) => write!(f, "invalid mask `{}`", loc.token),
+            ParseError::InvalidAddress(loc) => write!(f, "invalid address `{}`", loc.token),
+            ParseError::InvalidValue(loc) => write!(f, "invalid value `{}`", loc.token),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidInstruction(loc)
+            | ParseError::InvalidMask(loc)
+            | ParseError::InvalidAddress(loc)
+            | ParseError::InvalidValue(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+fn parse_instruction(source: &Source, line: &str) -> Result<Instruction, ParseError> {
+    let (key, val) = line
+        .split_once(" = ")
+        .ok_or_else(|| ParseError::InvalidInstruction(source.locate(line)))?;
+    if key == "mask" {
+        if val.len() != 36 || !val.chars().all(|c| matches!(c, 'X' | '0' | '1')) {
+            return Err(ParseError::InvalidMask(source.locate(val)));
+        }
+        Ok(Instruction::Mask(val.to_owned()))
+    } else {
+        let addr = key
+            .strip_prefix("mem[")
+            .and_then(|x| x.strip_suffix(']'))
+            .ok_or_else(|| ParseError::InvalidInstruction(source.locate(key)))?;
+        let addr = addr
+            .parse::<usize>()
+            .map_err(|_| ParseError::InvalidAddress(source.locate(addr)))?;
+        let val = val
+            .parse::<usize>()
+            .map_err(|_| ParseError::InvalidValue(source.locate(val)))?;
+        Ok(Instruction::Mem(addr, val))
+    }
+}
+
+/// Parses the initialization program.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<Vec<Instruction>, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Vec<Instruction>, ParseError> {
+    source
+        .content()
+        .lines()
+        .map(|line| parse_instruction(source, line))
+        .collect()
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The puzzle of day 14.
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Vec<Instruction>;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, instructions: &Self::Input) -> Answer {
+        solve_part1(instructions).into()
+    }
+
+    fn part2(&self, instructions: &Self::Input) -> Answer {
+        solve_part2(instructions).into()
+    }
+}
+
+impl Oracle for Day14 {
+    /// Generates a program of `size` instructions, starting with a mask,
+    /// with at most 6 floating bits in each mask and a few addresses, so
+    /// that they get overwritten.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let mut input = String::new();
+        for i in 0..size.max(1) {
+            if i == 0 || rng.ratio(1, 4) {
+                let mut mask = (0..36)
+                    .map(|i| match i {
+                        0..=5 if rng.ratio(1, 2) => 'X',
+                        _ => *rng.choose(&['0', '1']),
+                    })
+                    .collect::<Vec<_>>();
+                rng.shuffle(&mut mask);
+                input += &format!("mask = {}\n", mask.iter().collect::<String>());
+            } else {
+                let addr = rng.range(0, 64);
+                let val = rng.range(0, 1 << 36);
+                input += &format!("mem[{}] = {}\n", addr, val);
+            }
+        }
+        input
+    }
+
+    fn oracle(&self, instructions: &Self::Input, part: usize) -> Answer {
+        let bits = |x: usize| (0..36).rev().map(|i| (x >> i) & 1).collect::<Vec<_>>();
+        let number = |bits: &[usize]| bits.iter().fold(0, |acc, bit| acc << 1 | bit);
+        let mut mask = "";
+        let mut memory = Vec::new();
+
+        for instruction in instructions {
+            match instruction {
+                Instruction::Mask(m) => mask = m,
+                Instruction::Mem(addr, val) if part == 1 => {
+                    let val = mask.chars().zip(bits(*val)).map(|(c, bit)| match c {
+                        '0' => 0,
+                        '1' => 1,
+                        _ => bit,
+                    });
+                    memory.push((*addr, number(&val.collect::<Vec<_>>())));
+                }
+                Instruction::Mem(addr, val) => {
+                    // Expand the floating bits one by one.
+                    let mut addrs = vec![Vec::new()];
+                    for (c, bit) in mask.chars().zip(bits(*addr)) {
+                        let choices = match c {
+                            '0' => vec![bit],
+                            '1' => vec![1],
+                            _ => vec![0, 1],
+                        };
+                        addrs = addrs
+                            .iter()
+                            .flat_map(|addr| {
+                                choices.iter().map(move |&bit| {
+                                    let mut addr = addr.clone();
+                                    addr.push(bit);
+                                    addr
+                                })
+                            })
+                            .collect();
+                    }
+                    memory.extend(addrs.iter().map(|addr| (number(addr), *val)));
+                }
+            }
+        }
+
+        // Only the last write to each address is kept.
+        let mut sum = 0;
+        for (i, (addr, val)) in memory.iter().enumerate() {
+            if !memory[i + 1..].iter().any(|(a, _)| a == addr) {
+                sum += val;
+            }
+        }
+        sum.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input_1() {
+        let instructions = parse_input("example1.txt").unwrap();
+        assert_eq!(solve_part1(&instructions), 165);
+    }
+
+    #[test]
+    fn test_example_input_2() {
+        let instructions = parse_input("example2.txt").unwrap();
+        assert_eq!(solve_part2(&instructions), 208);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day14, 12);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day14, 14, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let instructions = Day14.parse(Path::new("example2.txt")).unwrap();
+        assert_eq!(Day14.part1(&instructions), Answer::Number(51));
+        assert_eq!(Day14.part2(&instructions), Answer::Number(208));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new(
+            "test.txt",
+            "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X\nmem[8x] = 11\n",
+        );
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid address `8x`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (2, 5));
+    }
+}
@@ -0,0 +1,237 @@
+//! Solution to the puzzle of day 15, _Rambunctious Recitation_.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+/// The turn asked for in part 1.
+pub const PART_1_TARGET_TURN: usize = 2020;
+/// The turn asked for in part 2.
+pub const PART_2_TARGET_TURN: usize = 30_000_000;
+
+/// Returns the number spoken at `target_turn`.
+pub fn solve(numbers: &[usize], target_turn: usize) -> usize {
+    let mut spoken_numbers = numbers
+        .iter()
+        .enumerate()
+        .map(|(turn, num)| (*num, turn + 1))
+        .collect::<HashMap<_, _>>();
+
+    let mut last_turn_number = *numbers.last().unwrap();
+    for turn in spoken_numbers.len()..target_turn {
+        let this_turn_number = match spoken_numbers.get(&last_turn_number) {
+            None => 0,
+            Some(t) => turn - t,
+        };
+        spoken_numbers.insert(last_turn_number, turn);
+        last_turn_number = this_turn_number;
+    }
+
+    last_turn_number
+}
+
+/// An error found while parsing the starting numbers.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A starting number isn't a number.
+    InvalidNumber(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidNumber(loc) => write!(f, "invalid number `{}`", loc.token),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidNumber(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the comma-separated starting numbers.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<Vec<usize>, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Vec<usize>, ParseError> {
+    source
+        .content()
+        .trim()
+        .split(',')
+        .map(|x| {
+            x.parse()
+                .map_err(|_| ParseError::InvalidNumber(source.locate(x)))
+        })
+        .collect()
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The puzzle of day 15.
+pub struct Day15 {
+    /// Turn asked for in part 1.
+    pub part1_target_turn: usize,
+    /// Turn asked for in part 2.
+    pub part2_target_turn: usize,
+}
+
+impl Default for Day15 {
+    fn default() -> Self {
+        Day15 {
+            part1_target_turn: PART_1_TARGET_TURN,
+            part2_target_turn: PART_2_TARGET_TURN,
+        }
+    }
+}
+
+impl Solution for Day15 {
+    type Input = Vec<usize>;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, numbers: &Self::Input) -> Answer {
+        solve(numbers, self.part1_target_turn).into()
+    }
+
+    fn part2(&self, numbers: &Self::Input) -> Answer {
+        solve(numbers, self.part2_target_turn).into()
+    }
+}
+
+impl Oracle for Day15 {
+    /// Generates `size` distinct starting numbers.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let mut numbers = (0..size.max(1) * 2).collect::<Vec<_>>();
+        rng.shuffle(&mut numbers);
+        numbers.truncate(size.max(1));
+        let numbers = numbers.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        format!("{}\n", numbers.join(","))
+    }
+
+    fn oracle(&self, numbers: &Self::Input, part: usize) -> Answer {
+        let target_turn = match part {
+            1 => self.part1_target_turn,
+            _ => self.part2_target_turn,
+        };
+        let mut spoken = numbers.to_vec();
+        while spoken.len() < target_turn {
+            let (last, before) = spoken.split_last().unwrap();
+            let age = match before.iter().rposition(|x| x == last) {
+                Some(turn) => before.len() - turn,
+                None => 0,
+            };
+            spoken.push(age);
+        }
+        spoken[target_turn - 1].into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input_1() {
+        let numbers = parse_input("example1.txt").unwrap();
+        assert_eq!(solve(&numbers, 2020), 436);
+    }
+
+    #[test]
+    fn test_example_input_2() {
+        let numbers = parse_input("example2.txt").unwrap();
+        assert_eq!(solve(&numbers, 2020), 1);
+    }
+
+    #[test]
+    fn test_example_input_3() {
+        let numbers = parse_input("example3.txt").unwrap();
+        assert_eq!(solve(&numbers, 2020), 10);
+    }
+
+    #[test]
+    fn test_example_input_4() {
+        let numbers = parse_input("example4.txt").unwrap();
+        assert_eq!(solve(&numbers, 2020), 27);
+    }
+
+    #[test]
+    fn test_example_input_5() {
+        let numbers = parse_input("example5.txt").unwrap();
+        assert_eq!(solve(&numbers, 2020), 78);
+    }
+
+    #[test]
+    fn test_example_input_6() {
+        let numbers = parse_input("example6.txt").unwrap();
+        assert_eq!(solve(&numbers, 2020), 438);
+    }
+
+    #[test]
+    fn test_example_input_7() {
+        let numbers = parse_input("example7.txt").unwrap();
+        assert_eq!(solve(&numbers, 2020), 1836);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(
+            &Day15 {
+                part1_target_turn: 10,
+                part2_target_turn: 500,
+            },
+            4,
+        );
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        // Part 2 takes too long without optimizations.
+        check_answers(&Day15::default(), 15, &[1]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let day = Day15 {
+            part2_target_turn: 10,
+            ..Default::default()
+        };
+        let numbers = day.parse(Path::new("example1.txt")).unwrap();
+        assert_eq!(day.part1(&numbers), Answer::Number(436));
+        assert_eq!(day.part2(&numbers), Answer::Number(0));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "0,3,six\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid number `six`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (1, 5));
+    }
+}
@@ -0,0 +1,386 @@
+//! Solution to the puzzle of day 16, _Ticket Translation_.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+use regex::Regex;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+/// The two inclusive ranges of valid values of a ticket field.
+pub struct FieldRange(usize, usize, usize, usize);
+
+impl FieldRange {
+    /// Checks if `n` is within either of the ranges.
+    pub fn valid_range(&self, n: usize) -> bool {
+        (n >= self.0 && n <= self.1) || (n >= self.2 && n <= self.3)
+    }
+}
+
+/// Maps each field name to its valid ranges.
+pub type RangeMap = HashMap<String, FieldRange>;
+
+/// The field rules, your ticket and the nearby tickets.
+pub type Notes = (RangeMap, Vec<usize>, Vec<Vec<usize>>);
+
+fn valid_range(ranges: &RangeMap, n: usize) -> bool {
+    ranges.values().any(|r| r.valid_range(n))
+}
+
+/// Sums the values of the nearby tickets that are not valid for any
+/// field.
+pub fn solve_part1(ranges: &RangeMap, nearby_tickets: &[Vec<usize>]) -> usize {
+    nearby_tickets
+        .iter()
+        .flat_map(|y| y.iter())
+        .filter(|&&x| !valid_range(ranges, x))
+        .sum()
+}
+
+/// Multiplies the values of the `departure` fields of `ticket`.
+pub fn solve_part2(ranges: &RangeMap, ticket: &[usize], nearby_tickets: &[Vec<usize>]) -> usize {
+    let nearby_tickets = nearby_tickets
+        .iter()
+        .filter(|x| x.iter().all(|&y| valid_range(ranges, y)))
+        .collect::<Vec<_>>();
+
+    let mut cols = vec![vec![]; nearby_tickets[0].len()];
+    for ticket in &nearby_tickets {
+        for (col, val) in ticket.iter().enumerate() {
+            cols[col].push(val);
+        }
+    }
+
+    let mut candidates = HashMap::new();
+    let mut positions = HashMap::new();
+
+    for (field, range) in ranges {
+        cols.iter()
+            .enumerate()
+            .filter(|(_, values)| values.iter().all(|&&v| range.valid_range(v)))
+            .for_each(|(index, _)| {
+                candidates
+                    .entry(field.clone())
+                    .or_insert_with(HashSet::new)
+                    .insert(index);
+            });
+    }
+
+    while positions.len() != cols.len() {
+        let candidate = candidates
+            .iter()
+            .find(|(_, positions)| positions.len() == 1)
+            .unwrap();
+        let field = candidate.0.clone();
+        let position = *candidate.1.iter().next().unwrap();
+        candidates.remove(&field);
+        positions.insert(field, position);
+        for v in candidates.values_mut() {
+            v.remove(&position);
+        }
+    }
+
+    ranges
+        .keys()
+        .filter(|k| k.starts_with("departure"))
+        .map(|k| ticket[*positions.get(k).unwrap()])
+        .product()
+}
+
+/// An error found while parsing the notes.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A line isn't a rule like `class: 1-3 or 5-7`.
+    InvalidRule(Location),
+    /// A value is not a number.
+    InvalidNumber(Location),
+    /// The notes end before your ticket or the nearby tickets.
+    MissingSection(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidRule(loc) => write!(f, "invalid rule `{}`", loc.token),
+            ParseError::InvalidNumber(loc) => write!(f, "invalid number `{}`", loc.token),
+            ParseError::MissingSection(_) => write!(f, "missing ticket section"),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidRule(loc)
+            | ParseError::InvalidNumber(loc)
+            | ParseError::MissingSection(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the field rules, your ticket and the nearby tickets.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<Notes, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Notes, ParseError> {
+    let content = source.content();
+    let mut blocks = source.paragraphs();
+
+    let re = Regex::new(r"(?P<f>.+): (?P<s1>\d+)-(?P<e1>\d+) or (?P<s2>\d+)-(?P<e2>\d+)").unwrap();
+    let missing = || ParseError::MissingSection(source.locate_end(content.trim_end()));
+    let parse_number = |x: &str| {
+        x.parse::<usize>()
+            .map_err(|_| ParseError::InvalidNumber(source.locate(x)))
+    };
+
+    let ranges = blocks
+        .next()
+        .ok_or_else(missing)?
+        .lines()
+        .map(|x| {
+            let caps = re
+                .captures(x)
+                .ok_or_else(|| ParseError::InvalidRule(source.locate(x)))?;
+            let field = caps["f"].to_string();
+            let s1 = parse_number(&caps["s1"])?;
+            let e1 = parse_number(&caps["e1"])?;
+            let s2 = parse_number(&caps["s2"])?;
+            let e2 = parse_number(&caps["e2"])?;
+            Ok((field, FieldRange(s1, e1, s2, e2)))
+        })
+        .collect::<Result<_, ParseError>>()?;
+
+    let ticket = blocks
+        .next()
+        .and_then(|x| x.lines().nth(1))
+        .ok_or_else(missing)?
+        .trim()
+        .split(',')
+        .map(parse_number)
+        .collect::<Result<_, _>>()?;
+
+    let nearby_tickets = blocks
+        .next()
+        .ok_or_else(missing)?
+        .lines()
+        .skip(1)
+        .map(|x| x.split(',').map(parse_number).collect())
+        .collect::<Result<_, _>>()?;
+
+    Ok((ranges, ticket, nearby_tickets))
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Notes, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The puzzle of day 16.
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Notes;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, (ranges, _, nearby_tickets): &Self::Input) -> Answer {
+        solve_part1(ranges, nearby_tickets).into()
+    }
+
+    fn part2(&self, (ranges, ticket, nearby_tickets): &Self::Input) -> Answer {
+        solve_part2(ranges, ticket, nearby_tickets).into()
+    }
+}
+
+/// Returns the permutations of `0..n`.
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    let mut permutations = Vec::new();
+    for permutation in self::permutations(n - 1) {
+        for i in 0..n {
+            let mut permutation = permutation.clone();
+            permutation.insert(i, n - 1);
+            permutations.push(permutation);
+        }
+    }
+    permutations
+}
+
+/// Returns the field names in the order of the ticket values, for every
+/// order in which the valid tickets fit the rules.
+fn field_orders(ranges: &RangeMap, nearby_tickets: &[Vec<usize>]) -> Vec<Vec<String>> {
+    let fields = ranges.keys().collect::<Vec<_>>();
+    let valid_tickets = nearby_tickets
+        .iter()
+        .filter(|ticket| ticket.iter().all(|&x| valid_range(ranges, x)))
+        .collect::<Vec<_>>();
+    permutations(fields.len())
+        .into_iter()
+        .map(|order| order.iter().map(|&i| fields[i].clone()).collect::<Vec<_>>())
+        .filter(|order| {
+            valid_tickets.iter().all(|ticket| {
+                let mut values = ticket.iter().zip(order);
+                values.all(|(&x, field)| ranges[field].valid_range(x))
+            })
+        })
+        .collect()
+}
+
+impl Oracle for Day16 {
+    /// Generates notes with up to 6 fields and `size` nearby tickets, with
+    /// a single order of the fields that fits them.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let names = [
+            "departure a",
+            "departure b",
+            "arrival",
+            "class",
+            "row",
+            "seat",
+        ];
+        let num_fields = rng.range(1, names.len() + 1);
+        loop {
+            let mut rules = Vec::new();
+            for &name in names.iter().take(num_fields) {
+                let mut bounds = Vec::new();
+                while bounds.len() < 4 {
+                    let x = rng.range(1, 50);
+                    if !bounds.contains(&x) {
+                        bounds.push(x);
+                    }
+                }
+                bounds.sort_unstable();
+                rules.push((name, FieldRange(bounds[0], bounds[1], bounds[2], bounds[3])));
+            }
+            rng.shuffle(&mut rules);
+
+            // Draws a ticket, with an invalid value now and then.
+            let ticket = |rng: &mut Rng, nearby: bool| {
+                let values = rules.iter().map(|(_, range)| {
+                    if nearby && rng.ratio(1, 10) {
+                        let valid = |x| rules.iter().any(|(_, r)| r.valid_range(x));
+                        let invalid = (0..60).filter(|&x| !valid(x)).collect::<Vec<_>>();
+                        *rng.choose(&invalid)
+                    } else {
+                        let valid = (1..50).filter(|&x| range.valid_range(x));
+                        *rng.choose(&valid.collect::<Vec<_>>())
+                    }
+                });
+                let values = values.map(|x| x.to_string()).collect::<Vec<_>>();
+                values.join(",")
+            };
+            let your_ticket = ticket(rng, false);
+            let nearby_tickets = (0..size.max(1))
+                .map(|_| ticket(rng, true))
+                .collect::<Vec<_>>();
+
+            let mut input = String::new();
+            for (name, range) in &rules {
+                input += &format!(
+                    "{}: {}-{} or {}-{}\n",
+                    name, range.0, range.1, range.2, range.3
+                );
+            }
+            input += &format!("\nyour ticket:\n{}\n", your_ticket);
+            input += &format!("\nnearby tickets:\n{}\n", nearby_tickets.join("\n"));
+
+            let (ranges, _, nearby_tickets) = parse(&input).unwrap();
+            if field_orders(&ranges, &nearby_tickets).len() == 1 {
+                return input;
+            }
+        }
+    }
+
+    fn oracle(&self, (ranges, ticket, nearby_tickets): &Self::Input, part: usize) -> Answer {
+        if part == 1 {
+            let mut sum = 0;
+            for &x in nearby_tickets.iter().flatten() {
+                if !ranges.values().any(|range| range.valid_range(x)) {
+                    sum += x;
+                }
+            }
+            sum.into()
+        } else {
+            let orders = field_orders(ranges, nearby_tickets);
+            let values = ticket.iter().zip(&orders[0]);
+            let departure = values.filter(|(_, field)| field.starts_with("departure"));
+            departure.map(|(x, _)| x).product::<usize>().into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+    use std::fs;
+
+    #[test]
+    fn test_example_input_1() {
+        let (ranges, _, nearby_tickets) = parse_input("example1.txt").unwrap();
+        assert_eq!(solve_part1(&ranges, &nearby_tickets), 71);
+    }
+
+    #[test]
+    fn test_example_input_2() {
+        let (ranges, ticket, nearby_tickets) = parse_input("example2.txt").unwrap();
+        assert_eq!(solve_part2(&ranges, &ticket, &nearby_tickets), 1);
+    }
+
+    #[test]
+    fn test_crlf_input() {
+        let content = fs::read_to_string("example2.txt").unwrap();
+        let content = format!("\u{feff}{}\n\n", content).replace('\n', "\r\n");
+        let (ranges, ticket, nearby_tickets) = parse(&content).unwrap();
+        assert_eq!(nearby_tickets.len(), 3);
+        assert_eq!(solve_part2(&ranges, &ticket, &nearby_tickets), 1);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day16, 20);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day16, 16, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let notes = Day16.parse(Path::new("example1.txt")).unwrap();
+        assert_eq!(Day16.part1(&notes), Answer::Number(71));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new(
+            "test.txt",
+            "class: 1-3 or 5-7\n\nyour ticket:\n7,1,14\n\nnearby tickets:\n7,3,47\n40,4a,50\n",
+        );
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid number `4a`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (8, 4));
+    }
+}
@@ -0,0 +1,335 @@
+//! Solution to the puzzle of day 17, _Conway Cubes_.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+use itertools::iproduct;
+
+/// The number of cycles of the boot process.
+pub const NUM_CYCLES: usize = 6;
+
+struct Point(isize, isize, isize, isize);
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum State {
+    Active,
+    Inactive,
+}
+
+fn cartesian_product(hypercube: bool) -> Vec<Point> {
+    let wrange = if hypercube { -1..2 } else { 0..1 };
+    iproduct!(-1..2, -1..2, -1..2, wrange)
+        .filter(|&(x, y, z, w)| !(x == 0 && y == 0 && z == 0 && w == 0))
+        .map(|(x, y, z, w)| Point(x, y, z, w))
+        .collect()
+}
+
+/// The pocket dimension of Conway Cubes.
+pub struct ConwayCubeSystem {
+    cubes: Vec<Vec<Vec<Vec<State>>>>,
+    moves: Vec<Point>,
+    num_cycles: usize,
+}
+
+impl ConwayCubeSystem {
+    /// Creates the pocket dimension from the initial slice, where `true`
+    /// marks an active cube, in four dimensions if `hypercube` is set.
+    pub fn new(slice: &[Vec<bool>], num_cycles: usize, hypercube: bool) -> Self {
+        let slice_len = slice.iter().map(|x| x.len()).fold(slice.len(), usize::max);
+        let xylen = slice_len + (num_cycles * 2);
+        let zwlen = 1 + (num_cycles * 2);
+        let mut cubes = vec![vec![vec![vec![State::Inactive; zwlen]; zwlen]; xylen]; xylen];
+
+        for (x, row) in slice.iter().enumerate() {
+            for (y, &active) in row.iter().enumerate() {
+                if active {
+                    cubes[x + num_cycles][y + num_cycles][num_cycles][num_cycles] = State::Active;
+                }
+            }
+        }
+
+        let moves = cartesian_product(hypercube);
+
+        ConwayCubeSystem {
+            cubes,
+            moves,
+            num_cycles,
+        }
+    }
+
+    /// Simulates one cycle.
+    pub fn iterate(&mut self) {
+        let mut new_cubes = self.cubes.clone();
+
+        // NB: we could optmise here by scanning only for the points
+        // that might be active at certain cycle.
+        for (x, xdim) in self.cubes.iter().enumerate() {
+            for (y, ydim) in xdim.iter().enumerate() {
+                for (z, zdim) in ydim.iter().enumerate() {
+                    for (w, point) in zdim.iter().enumerate() {
+                        let num_active_neighbors = self.count_active_neighbors(&Point(
+                            x as isize, y as isize, z as isize, w as isize,
+                        ));
+                        match point {
+                            State::Active => {
+                                if !(num_active_neighbors == 2 || num_active_neighbors == 3) {
+                                    new_cubes[x][y][z][w] = State::Inactive;
+                                }
+                            }
+                            State::Inactive => {
+                                if num_active_neighbors == 3 {
+                                    new_cubes[x][y][z][w] = State::Active;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        self.cubes = new_cubes;
+    }
+
+    fn count_active_neighbors(&self, p: &Point) -> usize {
+        self.moves
+            .iter()
+            .filter(|neigh| self.is_active(p, neigh))
+            .count()
+    }
+
+    #[allow(clippy::many_single_char_names)]
+    fn is_active(&self, p: &Point, mov: &Point) -> bool {
+        let x = p.0 + mov.0;
+        let y = p.1 + mov.1;
+        let z = p.2 + mov.2;
+        let w = p.3 + mov.3;
+        if x < 0 || y < 0 || z < 0 || w < 0 {
+            return false;
+        }
+
+        let xylen = self.xylen() as isize;
+        let zwlen = self.zwlen() as isize;
+        if x >= xylen || y >= xylen || z >= zwlen || w >= zwlen {
+            return false;
+        }
+
+        self.cubes[x as usize][y as usize][z as usize][w as usize] == State::Active
+    }
+
+    /// Counts the active cubes after the boot process.
+    pub fn count_active_cubes(&mut self) -> usize {
+        for _ in 0..self.num_cycles {
+            self.iterate();
+        }
+        self.cubes
+            .iter()
+            .flat_map(|x| x.iter().flat_map(|y| y.iter().flat_map(|z| z.iter())))
+            .filter(|&&x| x == State::Active)
+            .count()
+    }
+
+    fn xylen(&self) -> usize {
+        self.cubes.len()
+    }
+
+    fn zwlen(&self) -> usize {
+        self.cubes[0][0].len()
+    }
+}
+
+/// An error found while parsing the initial slice.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A cube is neither active (`#`) nor inactive (`.`).
+    InvalidCube(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidCube(loc) => write!(f, "invalid cube `{}`", loc.token),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidCube(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the initial slice of active (`#`) and inactive (`.`) cubes.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<Vec<Vec<bool>>, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Vec<Vec<bool>>, ParseError> {
+    source
+        .content()
+        .lines()
+        .map(|line| {
+            line.char_indices()
+                .map(|(i, ch)| match ch {
+                    '#' => Ok(true),
+                    '.' => Ok(false),
+                    _ => {
+                        let cube = &line[i..i + ch.len_utf8()];
+                        Err(ParseError::InvalidCube(source.locate(cube)))
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Vec<Vec<bool>>, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The puzzle of day 17.
+pub struct Day17 {
+    /// Number of cycles of the boot process.
+    pub num_cycles: usize,
+}
+
+impl Default for Day17 {
+    fn default() -> Self {
+        Day17 {
+            num_cycles: NUM_CYCLES,
+        }
+    }
+}
+
+impl Solution for Day17 {
+    type Input = Vec<Vec<bool>>;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Answer {
+        let mut cube_system = ConwayCubeSystem::new(input, self.num_cycles, false);
+        cube_system.count_active_cubes().into()
+    }
+
+    fn part2(&self, input: &Self::Input) -> Answer {
+        let mut cube_system = ConwayCubeSystem::new(input, self.num_cycles, true);
+        cube_system.count_active_cubes().into()
+    }
+}
+
+impl Oracle for Day17 {
+    /// Generates an initial slice of `size` by `size` cubes.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let mut input = String::new();
+        for _ in 0..size.max(1) {
+            for _ in 0..size.max(1) {
+                input.push(if rng.ratio(1, 2) { '#' } else { '.' });
+            }
+            input.push('\n');
+        }
+        input
+    }
+
+    fn oracle(&self, slice: &Self::Input, part: usize) -> Answer {
+        // The active cubes only, in a boundless pocket dimension.
+        let mut active = HashSet::new();
+        for (x, row) in slice.iter().enumerate() {
+            for (y, &cube) in row.iter().enumerate() {
+                if cube {
+                    active.insert([x as isize, y as isize, 0, 0]);
+                }
+            }
+        }
+        let dims = if part == 1 { 3 } else { 4 };
+        let is_neighbor =
+            |a: &[isize; 4], b: &[isize; 4]| a != b && (0..4).all(|i| (a[i] - b[i]).abs() <= 1);
+
+        for _ in 0..self.num_cycles {
+            // Every cube that may change is next to an active one.
+            let mut candidates = HashSet::new();
+            for cube in &active {
+                for d in 0..3isize.pow(dims) {
+                    let mut neighbor = *cube;
+                    for (i, coord) in neighbor.iter_mut().enumerate().take(dims as usize) {
+                        *coord += d / 3isize.pow(i as u32) % 3 - 1;
+                    }
+                    candidates.insert(neighbor);
+                }
+            }
+            active = candidates
+                .into_iter()
+                .filter(|cube| {
+                    let neighbors = active.iter().filter(|x| is_neighbor(cube, x)).count();
+                    neighbors == 3 || neighbors == 2 && active.contains(cube)
+                })
+                .collect();
+        }
+        active.len().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input_part1() {
+        let input = parse_input("example.txt").unwrap();
+        let mut cube_system = ConwayCubeSystem::new(&input, 6, false);
+        assert_eq!(cube_system.count_active_cubes(), 112);
+    }
+
+    #[test]
+    fn test_example_input_part2() {
+        let input = parse_input("example.txt").unwrap();
+        let mut cube_system = ConwayCubeSystem::new(&input, 6, true);
+        assert_eq!(cube_system.count_active_cubes(), 848);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day17 { num_cycles: 2 }, 3);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day17::default(), 17, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let day = Day17 { num_cycles: 1 };
+        let input = day.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(day.part1(&input), Answer::Number(11));
+        assert_eq!(day.part2(&input), Answer::Number(29));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", ".#.\n..#\n#+#\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid cube `+`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (3, 2));
+    }
+}
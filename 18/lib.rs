@@ -0,0 +1,427 @@
+//! Solution to the puzzle of day 18, _Operation Order_.
+
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+/// A token of an expression.
+#[derive(PartialEq)]
+pub enum Token {
+    Digit(u8),
+    OpAdd,
+    OpMult,
+    LeftParens,
+    RightParens,
+}
+
+#[derive(PartialEq)]
+enum PrecedenceLevel {
+    Parens,
+    Mult,
+}
+
+/// Splits an expression into tokens, or returns the first token out of
+/// place, which is empty if the expression is incomplete.
+pub fn lex(s: &str) -> Result<Vec<Token>, &str> {
+    let mut tokens = vec![];
+    let mut depth = 0;
+    let mut expect_operand = true;
+
+    for (i, c) in s.char_indices() {
+        let token = match c {
+            ' ' => continue,
+            '0'..='9' if expect_operand => Token::Digit(c.to_digit(10).unwrap() as u8),
+            '(' if expect_operand => Token::LeftParens,
+            '+' if !expect_operand => Token::OpAdd,
+            '*' if !expect_operand => Token::OpMult,
+            ')' if !expect_operand && depth > 0 => Token::RightParens,
+            _ => return Err(&s[i..i + c.len_utf8()]),
+        };
+        match token {
+            Token::LeftParens => depth += 1,
+            Token::RightParens => depth -= 1,
+            _ => (),
+        }
+        expect_operand = matches!(token, Token::OpAdd | Token::OpMult | Token::LeftParens);
+        tokens.push(token);
+    }
+
+    if expect_operand || depth > 0 {
+        return Err(&s[s.len()..]);
+    }
+
+    Ok(tokens)
+}
+
+fn evaluate_reverse_polish(tokens: &[Token]) -> usize {
+    let mut stack = vec![];
+    for token in tokens {
+        match token {
+            Token::Digit(n) => stack.push(*n as usize),
+            Token::OpAdd => {
+                let a = stack.pop().unwrap();
+                let b = stack.pop().unwrap();
+                stack.push(a + b);
+            }
+            Token::OpMult => {
+                let a = stack.pop().unwrap();
+                let b = stack.pop().unwrap();
+                stack.push(a * b);
+            }
+            _ => unreachable!(),
+        }
+    }
+    *stack.first().unwrap()
+}
+
+/// Evaluates an expression with the same precedence for all operators.
+pub fn calculate_sum_part1(tokens: &[Token]) -> usize {
+    let add: fn(usize, usize) -> usize = |x, y| x + y;
+    let mul: fn(usize, usize) -> usize = |x, y| x * y;
+
+    let mut num = 0;
+    let mut op = add;
+    let mut stack = vec![];
+
+    for token in tokens {
+        match token {
+            Token::Digit(n) => num = op(num, *n as usize),
+            Token::OpAdd => op = add,
+            Token::OpMult => op = mul,
+            Token::LeftParens => {
+                stack.push((num, op));
+                num = 0;
+                op = add;
+            }
+            Token::RightParens => {
+                let (prev_num, prev_op) = stack.pop().unwrap();
+                num = prev_op(prev_num, num)
+            }
+        }
+    }
+
+    assert!(stack.is_empty());
+    num
+}
+
+/// Evaluates an expression with addition taking precedence, without
+/// converting it to postfix notation.
+pub fn calculate_sum_part2_inplace(tokens: &[Token]) -> usize {
+    let mut num = 0;
+    let mut stack = vec![];
+
+    for token in tokens {
+        match token {
+            Token::Digit(n) => num += *n as usize,
+            Token::OpAdd => (),
+            Token::OpMult => {
+                if let Some((n, PrecedenceLevel::Mult)) = stack.last() {
+                    num *= n;
+                    stack.pop();
+                }
+                stack.push((num, PrecedenceLevel::Mult));
+                num = 0;
+            }
+            Token::LeftParens => {
+                stack.push((num, PrecedenceLevel::Parens));
+                num = 0;
+            }
+            Token::RightParens => {
+                if let Some((n, PrecedenceLevel::Mult)) = stack.last() {
+                    num *= n;
+                    stack.pop();
+                }
+                let (prev_num, _stype) = stack.pop().unwrap();
+                num += prev_num;
+            }
+        }
+    }
+
+    if let Some((n, PrecedenceLevel::Mult)) = stack.pop() {
+        num *= n;
+    }
+
+    num
+}
+
+/// Evaluates an expression with addition taking precedence, using the
+/// shunting-yard algorithm.
+pub fn calculate_sum_part2_shunting_yard(tokens: &[Token]) -> usize {
+    let mut outqueue = vec![];
+    let mut opstack = vec![];
+
+    for token in tokens {
+        match token {
+            Token::Digit(n) => outqueue.push(Token::Digit(*n)),
+            Token::OpAdd => opstack.push(Token::OpAdd),
+            Token::OpMult => {
+                while let Some(Token::OpAdd) = opstack.last() {
+                    outqueue.push(opstack.pop().unwrap());
+                }
+                opstack.push(Token::OpMult);
+            }
+            Token::LeftParens => {
+                opstack.push(Token::LeftParens);
+            }
+            Token::RightParens => {
+                while let Some(t) = opstack.pop() {
+                    if t != Token::LeftParens {
+                        outqueue.push(t);
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    while let Some(t) = opstack.pop() {
+        outqueue.push(t);
+    }
+
+    evaluate_reverse_polish(&outqueue)
+}
+
+/// Sums the result of each expression with the rules of part 1.
+pub fn calculate_sums_part1(expressions: &[Vec<Token>]) -> usize {
+    expressions.iter().map(|x| calculate_sum_part1(x)).sum()
+}
+
+/// Sums the result of each expression with the rules of part 2.
+pub fn calculate_sums_part2_inplace(expressions: &[Vec<Token>]) -> usize {
+    expressions
+        .iter()
+        .map(|x| calculate_sum_part2_inplace(x))
+        .sum()
+}
+
+/// Sums the result of each expression with the rules of part 2.
+pub fn calculate_sums_part2_shunting_yard(expressions: &[Vec<Token>]) -> usize {
+    expressions
+        .iter()
+        .map(|x| calculate_sum_part2_shunting_yard(x))
+        .sum()
+}
+
+/// An error found while parsing the homework.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A token is out of place in an expression.
+    UnexpectedToken(Location),
+    /// An expression is incomplete.
+    UnexpectedEnd(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::UnexpectedToken(loc) => write!(f, "unexpected `{}`", loc.token),
+            ParseError::UnexpectedEnd(_) => write!(f, "incomplete expression"),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::UnexpectedToken(loc) | ParseError::UnexpectedEnd(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the homework, one expression per line.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<Vec<Vec<Token>>, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Vec<Vec<Token>>, ParseError> {
+    source
+        .content()
+        .trim()
+        .lines()
+        .map(|line| {
+            lex(line).map_err(|token| match token {
+                "" => ParseError::UnexpectedEnd(source.locate(token)),
+                _ => ParseError::UnexpectedToken(source.locate(token)),
+            })
+        })
+        .collect()
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Vec<Vec<Token>>, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The puzzle of day 18.
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Vec<Vec<Token>>;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, expressions: &Self::Input) -> Answer {
+        calculate_sums_part1(expressions).into()
+    }
+
+    fn part2(&self, expressions: &Self::Input) -> Answer {
+        calculate_sums_part2_shunting_yard(expressions).into()
+    }
+}
+
+/// Evaluates the expression at the start of `tokens` by recursive descent,
+/// returning its value and the tokens after it. Additions are evaluated
+/// before multiplications if `add_first` is set.
+fn descend(tokens: &[Token], add_first: bool) -> (usize, &[Token]) {
+    let (mut value, mut rest) = descend_operand(tokens, add_first);
+    loop {
+        match rest.first() {
+            Some(Token::OpAdd) => {
+                let (x, r) = descend_operand(&rest[1..], add_first);
+                value += x;
+                rest = r;
+            }
+            Some(Token::OpMult) => {
+                let (x, r) = descend_operand(&rest[1..], add_first);
+                value *= x;
+                rest = r;
+            }
+            _ => return (value, rest),
+        }
+    }
+}
+
+/// Evaluates an operand of [`descend`], summing a run of additions first if
+/// they go before multiplications.
+fn descend_operand(tokens: &[Token], add_first: bool) -> (usize, &[Token]) {
+    let (mut value, mut rest) = descend_primary(tokens, add_first);
+    while add_first && rest.first() == Some(&Token::OpAdd) {
+        let (x, r) = descend_primary(&rest[1..], add_first);
+        value += x;
+        rest = r;
+    }
+    (value, rest)
+}
+
+/// Evaluates a digit or an expression in parentheses.
+fn descend_primary(tokens: &[Token], add_first: bool) -> (usize, &[Token]) {
+    match &tokens[0] {
+        Token::Digit(n) => (*n as usize, &tokens[1..]),
+        _ => {
+            let (value, rest) = descend(&tokens[1..], add_first);
+            (value, &rest[1..])
+        }
+    }
+}
+
+/// Generates an expression with `depth` levels of parentheses at most.
+fn generate_expression(rng: &mut Rng, depth: usize) -> String {
+    let mut expression = String::new();
+    for i in 0..rng.range(1, 5) {
+        if i > 0 {
+            expression += if rng.ratio(1, 2) { " + " } else { " * " };
+        }
+        if depth > 0 && rng.ratio(1, 3) {
+            expression += &format!("({})", generate_expression(rng, depth - 1));
+        } else {
+            expression += &rng.range(1, 10).to_string();
+        }
+    }
+    expression
+}
+
+impl Oracle for Day18 {
+    /// Generates `size` expressions.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        (0..size.max(1))
+            .map(|_| format!("{}\n", generate_expression(rng, 3)))
+            .collect()
+    }
+
+    fn oracle(&self, expressions: &Self::Input, part: usize) -> Answer {
+        let values = expressions.iter().map(|x| descend(x, part == 2).0);
+        values.sum::<usize>().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input_1() {
+        let expressions = parse_input("example1.txt").unwrap();
+        assert_eq!(calculate_sums_part1(&expressions), 71);
+        assert_eq!(calculate_sums_part2_inplace(&expressions), 231);
+        assert_eq!(calculate_sums_part2_shunting_yard(&expressions), 231);
+    }
+
+    #[test]
+    fn test_example_input_2() {
+        let expressions = parse_input("example2.txt").unwrap();
+        assert_eq!(calculate_sums_part1(&expressions), 51);
+        assert_eq!(calculate_sums_part2_inplace(&expressions), 51);
+        assert_eq!(calculate_sums_part2_shunting_yard(&expressions), 51);
+    }
+
+    #[test]
+    fn test_example_input_3() {
+        let expressions = parse_input("example3.txt").unwrap();
+        assert_eq!(calculate_sums_part1(&expressions), 26335);
+        assert_eq!(calculate_sums_part2_inplace(&expressions), 693891);
+        assert_eq!(calculate_sums_part2_shunting_yard(&expressions), 693891);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day18, 10);
+
+        for seed in 0..100 {
+            let expressions = parse(&Day18.generate(&mut Rng::new(seed), 10)).unwrap();
+            let expected = Day18.oracle(&expressions, 2);
+            assert_eq!(
+                Answer::from(calculate_sums_part2_inplace(&expressions)),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day18, 18, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let expressions = Day18.parse(Path::new("example1.txt")).unwrap();
+        assert_eq!(Day18.part1(&expressions), Answer::Number(71));
+        assert_eq!(Day18.part2(&expressions), Answer::Number(231));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "1 + 2\n2 * (3 + 4))\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "unexpected `)`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (2, 12));
+    }
+}
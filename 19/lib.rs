@@ -0,0 +1,443 @@
+//! Solution to the puzzle of day 19, _Monster Messages_.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+/// A rule that messages must match.
+#[derive(Clone)]
+pub enum Rule {
+    Terminal(char),
+    MatchAll(Vec<usize>),
+    MatchEither(Vec<usize>, Vec<usize>),
+}
+
+fn merge(a: &[usize], b: &[usize]) -> Vec<usize> {
+    a.iter().chain(b.iter()).cloned().collect()
+}
+
+fn matches(queue: &[usize], input: &str, rules: &HashMap<usize, Rule>) -> bool {
+    match (queue.is_empty(), input.is_empty()) {
+        (true, true) => return true,
+        (true, _) => return false,
+        (_, true) => return false,
+        _ => (),
+    }
+
+    match rules.get(&queue[0]).unwrap() {
+        Rule::Terminal(t) => input.starts_with(*t) && matches(&queue[1..], &input[1..], rules),
+        Rule::MatchAll(c) => matches(&merge(c, &queue[1..]), input, rules),
+        Rule::MatchEither(a, b) => {
+            matches(&merge(a, &queue[1..]), input, rules)
+                || matches(&merge(b, &queue[1..]), input, rules)
+        }
+    }
+}
+
+/// An error found while parsing the rules and messages.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A line isn't a rule like `0: 4 1 5`.
+    InvalidRule(Location),
+    /// A rule number isn't a number.
+    InvalidRuleNumber(Location),
+    /// A quoted rule doesn't match a single character.
+    InvalidTerminal(Location),
+    /// A rule has more than two alternatives.
+    TooManyChoices(Location),
+    /// A rule refers to a rule that isn't defined.
+    UndefinedRule(Location),
+    /// There is no rule 0 to match the messages against.
+    MissingRuleZero,
+    /// The rules aren't followed by a blank line and the messages.
+    MissingMessages(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidRule(loc) => write!(f, "invalid rule `{}`", loc.token),
+            ParseError::InvalidRuleNumber(loc) => write!(f, "invalid rule number `{}`", loc.token),
+            ParseError::InvalidTerminal(loc) => write!(f, "invalid character rule `{}`", loc.token),
+            ParseError::TooManyChoices(loc) => {
+                write!(f, "too many alternatives in rule `{}`", loc.token)
+            }
+            ParseError::UndefinedRule(loc) => write!(f, "undefined rule `{}`", loc.token),
+            ParseError::MissingRuleZero => write!(f, "missing rule 0"),
+            ParseError::MissingMessages(_) => write!(f, "missing messages"),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) | ParseError::MissingRuleZero => None,
+            ParseError::InvalidRule(loc)
+            | ParseError::InvalidRuleNumber(loc)
+            | ParseError::InvalidTerminal(loc)
+            | ParseError::TooManyChoices(loc)
+            | ParseError::UndefinedRule(loc)
+            | ParseError::MissingMessages(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+fn parse_choice(source: &Source, choice: &str) -> Result<Vec<usize>, ParseError> {
+    choice
+        .split_whitespace()
+        .map(|x| {
+            x.parse()
+                .map_err(|_| ParseError::InvalidRuleNumber(source.locate(x)))
+        })
+        .collect()
+}
+
+fn parse_rule(source: &Source, line: &str) -> Result<(usize, Rule), ParseError> {
+    let (rule_num, rule_rhs) = line
+        .split_once(':')
+        .ok_or_else(|| ParseError::InvalidRule(source.locate(line)))?;
+    let rule_num = rule_num
+        .parse()
+        .map_err(|_| ParseError::InvalidRuleNumber(source.locate(rule_num)))?;
+    let rule_rhs = rule_rhs.trim();
+
+    if rule_rhs.starts_with('"') {
+        let mut chars = rule_rhs.chars().skip(1);
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(t), Some('"'), None) => Ok((rule_num, Rule::Terminal(t))),
+            _ => Err(ParseError::InvalidTerminal(source.locate(rule_rhs))),
+        }
+    } else {
+        let choices = rule_rhs
+            .split('|')
+            .map(|x| parse_choice(source, x))
+            .collect::<Result<Vec<_>, _>>()?;
+        let rule = match choices.len() {
+            1 => Rule::MatchAll(choices[0].clone()),
+            2 => Rule::MatchEither(choices[0].clone(), choices[1].clone()),
+            _ => return Err(ParseError::TooManyChoices(source.locate(rule_rhs))),
+        };
+        Ok((rule_num, rule))
+    }
+}
+
+/// Parses the rules and the received messages.
+pub fn parse_input(
+    file_name: impl AsRef<Path>,
+) -> Result<(HashMap<usize, Rule>, Vec<String>), ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<(HashMap<usize, Rule>, Vec<String>), ParseError> {
+    let content = source.content();
+    let mut blocks = source.paragraphs();
+    let (rules_block, messages_block) = match (blocks.next(), blocks.next()) {
+        (Some(rules_block), Some(messages_block)) => (rules_block, messages_block),
+        _ => {
+            return Err(ParseError::MissingMessages(
+                source.locate_end(content.trim_end()),
+            ))
+        }
+    };
+
+    let lines = rules_block.trim().lines().collect::<Vec<_>>();
+    let rules = lines
+        .iter()
+        .map(|line| parse_rule(source, line))
+        .collect::<Result<HashMap<_, _>, _>>()?;
+
+    // Check the references only once all the rules are known.
+    for line in lines {
+        let rule_rhs = line.split_once(':').map_or("", |x| x.1);
+        for token in rule_rhs.split_whitespace() {
+            match token.parse() {
+                Ok(rule_num) if !rules.contains_key(&rule_num) => {
+                    return Err(ParseError::UndefinedRule(source.locate(token)))
+                }
+                _ => (),
+            }
+        }
+    }
+
+    if !rules.contains_key(&0) {
+        return Err(ParseError::MissingRuleZero);
+    }
+
+    let strings = messages_block.lines().map(|x| x.to_string()).collect();
+
+    Ok((rules, strings))
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<(HashMap<usize, Rule>, Vec<String>), ParseError> {
+    parse_source(&Source::from(input))
+}
+
+fn count_matches(rules: &HashMap<usize, Rule>, strings: &[String]) -> usize {
+    strings.iter().filter(|m| matches(&[0], m, rules)).count()
+}
+
+fn replace_looping_rules(rules: &mut HashMap<usize, Rule>) {
+    rules.insert(8, Rule::MatchEither(vec![42], vec![42, 8]));
+    rules.insert(11, Rule::MatchEither(vec![42, 31], vec![42, 11, 31]));
+}
+
+/// Counts the messages that completely match rule 0.
+pub fn count_valid_strings(file_name: impl AsRef<Path>) -> Result<usize, ParseError> {
+    let (rules, strings) = parse_input(file_name)?;
+    Ok(count_matches(&rules, &strings))
+}
+
+/// Counts the messages that completely match rule 0, with rules 8 and 11
+/// replaced by their looping versions.
+pub fn count_valid_strings_part2(file_name: impl AsRef<Path>) -> Result<usize, ParseError> {
+    let (mut rules, strings) = parse_input(file_name)?;
+    replace_looping_rules(&mut rules);
+    Ok(count_matches(&rules, &strings))
+}
+
+/// The puzzle of day 19.
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = (HashMap<usize, Rule>, Vec<String>);
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, (rules, strings): &Self::Input) -> Answer {
+        count_matches(rules, strings).into()
+    }
+
+    fn part2(&self, (rules, strings): &Self::Input) -> Answer {
+        let mut rules = rules.clone();
+        replace_looping_rules(&mut rules);
+        count_matches(&rules, strings).into()
+    }
+}
+
+/// Returns every message that matches a rule without loops.
+fn language(rules: &HashMap<usize, Rule>, rule_num: usize) -> Vec<String> {
+    let sequence = |seq: &[usize]| {
+        seq.iter().fold(vec![String::new()], |prefixes, &r| {
+            let suffixes = language(rules, r);
+            prefixes
+                .iter()
+                .flat_map(|p| suffixes.iter().map(move |s| format!("{}{}", p, s)))
+                .collect()
+        })
+    };
+    match &rules[&rule_num] {
+        Rule::Terminal(t) => vec![t.to_string()],
+        Rule::MatchAll(seq) => sequence(seq),
+        Rule::MatchEither(a, b) => [sequence(a), sequence(b)].concat(),
+    }
+}
+
+/// Returns whether a message is made of one or more messages of `prefixes`.
+fn matches_repeated(message: &str, prefixes: &[String]) -> bool {
+    prefixes
+        .iter()
+        .any(|p| match message.strip_prefix(p.as_str()) {
+            Some("") => true,
+            Some(rest) => matches_repeated(rest, prefixes),
+            None => false,
+        })
+}
+
+/// Returns whether a message is made of `n` messages of `prefixes` followed
+/// by `n` messages of `suffixes`, for some `n` greater than 0.
+fn matches_nested(message: &str, prefixes: &[String], suffixes: &[String]) -> bool {
+    prefixes.iter().any(|p| {
+        suffixes.iter().any(|s| {
+            let inner = message
+                .strip_prefix(p.as_str())
+                .and_then(|x| x.strip_suffix(s.as_str()));
+            match inner {
+                Some("") => true,
+                Some(inner) => matches_nested(inner, prefixes, suffixes),
+                None => false,
+            }
+        })
+    })
+}
+
+/// Adds a random rule with up to `depth` levels of rules below it, and
+/// returns its number.
+fn generate_rule(rng: &mut Rng, rules: &mut HashMap<usize, Rule>, depth: usize) -> usize {
+    if depth == 0 || rng.ratio(1, 4) {
+        return rng.range(1, 3);
+    }
+    let mut sequence = |rng: &mut Rng| {
+        (0..rng.range(1, 3))
+            .map(|_| generate_rule(rng, rules, depth - 1))
+            .collect::<Vec<_>>()
+    };
+    let rule = match rng.ratio(1, 2) {
+        true => Rule::MatchAll(sequence(rng)),
+        false => Rule::MatchEither(sequence(rng), sequence(rng)),
+    };
+    // Rules 8, 11, 31 and 42 have a meaning of their own.
+    let rule_num = (3..)
+        .find(|x| ![8, 11, 31, 42].contains(x) && !rules.contains_key(x))
+        .unwrap();
+    rules.insert(rule_num, rule);
+    rule_num
+}
+
+impl Oracle for Day19 {
+    /// Generates rules with the shape of the puzzle, where rule 0 is `8 11`
+    /// and rules 42 and 31 match distinct messages of the same length, and
+    /// `size` messages, some of them matching the rules with loops.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let (rules, language_42, language_31) = loop {
+            let mut rules = HashMap::new();
+            rules.insert(1, Rule::Terminal('a'));
+            rules.insert(2, Rule::Terminal('b'));
+            let rule_42 = Rule::MatchAll(vec![generate_rule(rng, &mut rules, 3)]);
+            let rule_31 = Rule::MatchAll(vec![generate_rule(rng, &mut rules, 3)]);
+            rules.insert(42, rule_42);
+            rules.insert(31, rule_31);
+            rules.insert(0, Rule::MatchAll(vec![8, 11]));
+            rules.insert(8, Rule::MatchAll(vec![42]));
+            rules.insert(11, Rule::MatchAll(vec![42, 31]));
+
+            let (language_42, language_31) = (language(&rules, 42), language(&rules, 31));
+            let mut messages = [language_42.clone(), language_31.clone()].concat();
+            let len = messages[0].len();
+            messages.sort_unstable();
+            messages.dedup();
+            if messages.len() == language_42.len() + language_31.len()
+                && messages.iter().all(|x| x.len() == len)
+            {
+                break (rules, language_42, language_31);
+            }
+        };
+
+        let format_seq = |seq: &[usize]| {
+            let seq = seq.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+            seq.join(" ")
+        };
+        let mut lines = rules
+            .iter()
+            .map(|(rule_num, rule)| match rule {
+                Rule::Terminal(t) => format!("{}: \"{}\"", rule_num, t),
+                Rule::MatchAll(seq) => format!("{}: {}", rule_num, format_seq(seq)),
+                Rule::MatchEither(a, b) => {
+                    format!("{}: {} | {}", rule_num, format_seq(a), format_seq(b))
+                }
+            })
+            .collect::<Vec<_>>();
+        // Hash maps aren't iterated in the same order every time.
+        lines.sort_unstable();
+        rng.shuffle(&mut lines);
+
+        let messages = (0..size.max(1)).map(|_| {
+            if rng.ratio(1, 3) {
+                let len = rng.range(1, 16);
+                (0..len).map(|_| *rng.choose(&['a', 'b'])).collect()
+            } else {
+                let n = rng.range(1, 5);
+                let m = rng.range(1, 4);
+                let mut message = String::new();
+                (0..n).for_each(|_| message += rng.choose(&language_42).as_str());
+                (0..m).for_each(|_| message += rng.choose(&language_31).as_str());
+                message
+            }
+        });
+        let messages = messages.collect::<Vec<String>>();
+        format!("{}\n\n{}\n", lines.join("\n"), messages.join("\n"))
+    }
+
+    fn oracle(&self, (rules, messages): &Self::Input, part: usize) -> Answer {
+        let count = if part == 1 {
+            let language = language(rules, 0);
+            messages.iter().filter(|m| language.contains(m)).count()
+        } else {
+            // Rule 0 is now rule 42 repeated, then rule 11 nested.
+            let (language_42, language_31) = (language(rules, 42), language(rules, 31));
+            let matches = |m: &str| {
+                (1..m.len()).any(|i| {
+                    matches_repeated(&m[..i], &language_42)
+                        && matches_nested(&m[i..], &language_42, &language_31)
+                })
+            };
+            messages.iter().filter(|m| matches(m)).count()
+        };
+        count.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+    use std::fs;
+
+    #[test]
+    fn test_example_input_1() {
+        assert_eq!(count_valid_strings("example1.txt").unwrap(), 2);
+    }
+
+    #[test]
+    fn test_example_input_2_part_1() {
+        assert_eq!(count_valid_strings("example2-part1.txt").unwrap(), 3);
+    }
+
+    #[test]
+    fn test_example_input_2_part_2() {
+        assert_eq!(count_valid_strings("example2-part2.txt").unwrap(), 12);
+        assert_eq!(count_valid_strings_part2("example2-part1.txt").unwrap(), 12);
+    }
+
+    #[test]
+    fn test_crlf_input() {
+        let content = fs::read_to_string("example2-part1.txt").unwrap();
+        let content = format!("\u{feff}{}\n\n", content).replace('\n', "\r\n");
+        let input = parse(&content).unwrap();
+        assert_eq!(Day19.part1(&input), Answer::Number(3));
+        assert_eq!(Day19.part2(&input), Answer::Number(12));
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day19, 20);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day19, 19, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let input = Day19.parse(Path::new("example2-part1.txt")).unwrap();
+        assert_eq!(Day19.part1(&input), Answer::Number(3));
+        assert_eq!(Day19.part2(&input), Answer::Number(12));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "0: 1 2\n1: \"a\"\n\nab\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "undefined rule `2`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (1, 6));
+    }
+}
@@ -0,0 +1,757 @@
+//! Solution to the puzzle of day 20, _Jurassic Jigsaw_.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+const TOP: usize = 0;
+const RIGHT: usize = 1;
+const BOTTOM: usize = 2;
+const LEFT: usize = 3;
+
+const SEA_MONSTER: &str = "
+                  #
+#    ##    ##    ###
+ #  #  #  #  #  #";
+
+type Grid = [Vec<Option<Tile>>];
+/// The pixels of an image, `#` or `.`.
+pub type Image = Vec<Vec<char>>;
+
+fn mirror(matrix: &mut Image) {
+    matrix.reverse();
+}
+
+fn rotate(matrix: &mut Image, n: usize) {
+    assert_eq!(matrix.len(), matrix[0].len());
+    let last = matrix.len() - 1;
+    for _ in 0..n {
+        for layer in 0..matrix.len() / 2 {
+            for i in 1 + layer..matrix.len() - layer {
+                let tmp = matrix[layer][i];
+                matrix[layer][i] = matrix[last - i][layer];
+                matrix[last - i][layer] = matrix[last - layer][last - i];
+                matrix[last - layer][last - i] = matrix[i][last - layer];
+                matrix[i][last - layer] = tmp;
+            }
+        }
+    }
+}
+
+fn hash_border(border: &str) -> u16 {
+    border
+        .chars()
+        .rev()
+        .enumerate()
+        .filter(|&(_, x)| x == '#')
+        .fold(0, |acc, (i, _)| acc | 1 << i)
+}
+
+fn add_rotations(mut border: Vec<String>) -> Vec<Vec<u16>> {
+    let mut alignments = vec![];
+    for _ in 0..4 {
+        alignments.push(border.iter().map(|x| hash_border(x)).collect());
+        border = vec![
+            border[LEFT].chars().rev().collect(),
+            border[TOP].clone(),
+            border[RIGHT].chars().rev().collect(),
+            border[BOTTOM].clone(),
+        ];
+    }
+    alignments
+}
+
+/// A tile of the image, with its borders in every orientation.
+#[derive(Clone)]
+pub struct Tile {
+    id: usize,
+    image: Image,
+    alignments: Vec<Vec<u16>>,
+    current_alignment: usize,
+}
+
+impl Tile {
+    fn new(id: usize, image: Image, borders: Vec<String>) -> Self {
+        let mirrored = vec![
+            borders[BOTTOM].clone(),
+            borders[RIGHT].chars().rev().collect(),
+            borders[TOP].clone(),
+            borders[LEFT].chars().rev().collect(),
+        ];
+
+        let alignments = add_rotations(mirrored)
+            .into_iter()
+            .chain(add_rotations(borders))
+            .collect();
+
+        Tile {
+            id,
+            image,
+            alignments,
+            current_alignment: 0,
+        }
+    }
+
+    /// Returns the ID of the tile.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    fn next_border_alignment(&mut self) {
+        self.current_alignment += 1;
+        self.current_alignment %= self.alignments.len();
+    }
+
+    fn apply_border_alignment(&mut self) {
+        if self.current_alignment < 4 {
+            mirror(&mut self.image);
+        }
+        match self.current_alignment {
+            0 | 4 => rotate(&mut self.image, 0),
+            1 | 5 => rotate(&mut self.image, 1),
+            2 | 6 => rotate(&mut self.image, 2),
+            3 | 7 => rotate(&mut self.image, 3),
+            _ => unreachable!(),
+        }
+    }
+
+    fn top_border(&self) -> u16 {
+        self.alignments[self.current_alignment][TOP]
+    }
+
+    fn right_border(&self) -> u16 {
+        self.alignments[self.current_alignment][RIGHT]
+    }
+
+    fn bottom_border(&self) -> u16 {
+        self.alignments[self.current_alignment][BOTTOM]
+    }
+
+    fn left_border(&self) -> u16 {
+        self.alignments[self.current_alignment][LEFT]
+    }
+
+    /// Returns the image of the tile, including its borders.
+    pub fn image(&self) -> &Image {
+        &self.image
+    }
+}
+
+fn vertically_aligned(grid: &Grid) -> bool {
+    let aligned = |x: &Option<Tile>, y: &Option<Tile>| {
+        x.as_ref().unwrap().right_border() == y.as_ref().unwrap().left_border()
+    };
+    grid.iter().all(|r| {
+        r.windows(2)
+            .filter(|w| w[1].is_some())
+            .all(|w| aligned(&w[0], &w[1]))
+    })
+}
+
+fn horizontally_aligned(grid: &Grid) -> bool {
+    let aligned = |x: &Option<Tile>, y: &Option<Tile>| {
+        x.as_ref().unwrap().bottom_border() == y.as_ref().unwrap().top_border()
+    };
+    grid.windows(2).all(|w| {
+        w[0].iter()
+            .zip(&w[1])
+            .filter(|(_, y)| y.is_some())
+            .all(|(x, y)| aligned(x, y))
+    })
+}
+
+fn aligned(grid: &Grid) -> bool {
+    vertically_aligned(grid) && horizontally_aligned(grid)
+}
+
+fn find_valid_alignment(
+    tiles: &mut VecDeque<Tile>,
+    row: usize,
+    col: usize,
+    grid: &mut Grid,
+) -> bool {
+    if !aligned(grid) {
+        return false;
+    }
+
+    if row == grid.len() {
+        return true;
+    }
+
+    let (next_row, next_col) = match col + 1 == grid.len() {
+        true => (row + 1, 0),
+        false => (row, col + 1),
+    };
+
+    for _ in 0..tiles.len() {
+        grid[row][col] = Some(tiles.pop_front().unwrap());
+        if (0..8).any(|_| {
+            grid[row][col].as_mut().unwrap().next_border_alignment();
+            find_valid_alignment(tiles, next_row, next_col, grid)
+        }) {
+            return true;
+        }
+        tiles.push_back(grid[row][col].take().unwrap());
+    }
+
+    grid[row][col] = None;
+    false
+}
+
+fn assemble_image(grid: &Grid) -> Image {
+    let tile_len = grid[0][0].as_ref().unwrap().image().len();
+    grid.iter()
+        .flat_map(|row| {
+            (1..tile_len - 1).map(move |i| {
+                row.iter()
+                    .flat_map(|t| {
+                        t.as_ref().unwrap().image()[i]
+                            .iter()
+                            .skip(1)
+                            .take(t.as_ref().unwrap().image()[i].len() - 2)
+                    })
+                    .cloned()
+                    .collect()
+            })
+        })
+        .collect()
+}
+
+fn match_pattern(image: &mut Image, pattern: &[(usize, usize)]) -> usize {
+    let max_row = pattern.iter().map(|(r, _)| r).max().unwrap();
+    let max_col = pattern.iter().map(|(_, c)| c).max().unwrap();
+    let mut matches = 0;
+
+    for (r, row) in image
+        .iter()
+        .enumerate()
+        .take(image.len().saturating_sub(*max_row))
+    {
+        // Sea monsters can be side by side, closer than their length.
+        for c in 0..row.len().saturating_sub(*max_col) {
+            if pattern.iter().all(|(dr, dc)| image[r + dr][c + dc] == '#') {
+                matches += 1;
+            }
+        }
+    }
+
+    matches
+}
+
+fn compile_pattern(pattern: &str) -> Vec<(usize, usize)> {
+    pattern
+        .lines()
+        .skip(1)
+        .enumerate()
+        .flat_map(|(r, row)| {
+            row.chars()
+                .enumerate()
+                .filter(|&(_, s)| s == '#')
+                .map(move |(c, _)| (r, c))
+        })
+        .collect()
+}
+
+fn count_sea_monsters(image: &mut Image) -> usize {
+    let pattern = compile_pattern(SEA_MONSTER);
+    if image.is_empty() {
+        return 0;
+    }
+
+    for _ in 0..4 {
+        match match_pattern(image, &pattern) {
+            0 => rotate(image, 1),
+            count => return count,
+        }
+    }
+
+    mirror(image);
+
+    for _ in 0..4 {
+        match match_pattern(image, &pattern) {
+            0 => rotate(image, 1),
+            count => return count,
+        }
+    }
+
+    0
+}
+
+/// An error found while parsing the tiles.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// The first line of a tile isn't like `Tile 1234:`.
+    InvalidHeader(Location),
+    /// A pixel is neither `#` nor `.`.
+    InvalidPixel(Location),
+    /// The image of a tile isn't a square of up to 16 pixels wide.
+    InvalidSize(Location),
+    /// There are no tiles.
+    NoTiles,
+    /// The number of tiles isn't a square.
+    NotSquare(usize),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidHeader(loc) => write!(f, "invalid tile header `{}`", loc.token),
+            ParseError::InvalidPixel(loc) => write!(f, "invalid pixel `{}`", loc.token),
+            ParseError::InvalidSize(_) => write!(f, "tile image is not square or is too large"),
+            ParseError::NoTiles => write!(f, "no tiles"),
+            ParseError::NotSquare(n) => write!(f, "{} tiles don't make a square", n),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) | ParseError::NoTiles | ParseError::NotSquare(_) => None,
+            ParseError::InvalidHeader(loc)
+            | ParseError::InvalidPixel(loc)
+            | ParseError::InvalidSize(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses a tile from its ID line and image, a slice of `source`.
+pub fn parse_tile(source: &Source, tile: &str) -> Result<Tile, ParseError> {
+    let mut lines = tile.lines();
+    let header = lines.next().unwrap_or(tile);
+    let id = header
+        .strip_prefix("Tile ")
+        .and_then(|x| x.strip_suffix(':'))
+        .and_then(|x| x.parse().ok())
+        .ok_or_else(|| ParseError::InvalidHeader(source.locate(header)))?;
+
+    let size = lines.clone().count();
+    for line in lines.clone() {
+        if let Some(i) = line.find(|c| c != '#' && c != '.') {
+            let pixel = &line[i..i + line[i..].chars().next().unwrap().len_utf8()];
+            return Err(ParseError::InvalidPixel(source.locate(pixel)));
+        }
+        if size == 0 || size > 16 || line.len() != size {
+            return Err(ParseError::InvalidSize(source.locate(line)));
+        }
+    }
+    if size == 0 {
+        return Err(ParseError::InvalidSize(source.locate_end(header)));
+    }
+
+    let image = lines.clone().map(|x| x.chars().collect()).collect();
+
+    let borders = vec![
+        lines.clone().next().unwrap().to_string(),
+        lines.clone().map(|x| x.chars().last().unwrap()).collect(),
+        lines.clone().next_back().unwrap().to_string(),
+        lines.clone().map(|x| x.chars().next().unwrap()).collect(),
+    ];
+
+    Ok(Tile::new(id, image, borders))
+}
+
+/// Parses the tiles, separated by blank lines.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<VecDeque<Tile>, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<VecDeque<Tile>, ParseError> {
+    let tiles = source
+        .paragraphs()
+        .map(|tile| parse_tile(source, tile))
+        .collect::<Result<VecDeque<_>, _>>()?;
+    let len = (tiles.len() as f64).sqrt().round() as usize;
+    if tiles.is_empty() {
+        return Err(ParseError::NoTiles);
+    }
+    if len * len != tiles.len() {
+        return Err(ParseError::NotSquare(tiles.len()));
+    }
+    Ok(tiles)
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<VecDeque<Tile>, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// Arranges the tiles in a square grid, returning `None` if their borders
+/// don't allow it.
+fn arrange_tiles(mut tiles: VecDeque<Tile>) -> Option<Vec<Vec<Option<Tile>>>> {
+    let len = (tiles.len() as f64).sqrt().round() as usize;
+    let mut grid = vec![vec![None; len]; len];
+
+    if !find_valid_alignment(&mut tiles, 0, 0, &mut grid) {
+        return None;
+    }
+
+    grid.iter_mut()
+        .flat_map(|x| x.iter_mut())
+        .for_each(|t| t.as_mut().unwrap().apply_border_alignment());
+
+    Some(grid)
+}
+
+fn corners_product(grid: &Grid) -> usize {
+    let len = grid.len();
+    [(0, 0), (0, len - 1), (len - 1, 0), (len - 1, len - 1)]
+        .iter()
+        .map(|&(r, c)| grid[r][c].as_ref().unwrap().id())
+        .product()
+}
+
+fn water_roughness(grid: &Grid) -> usize {
+    let mut image = assemble_image(grid);
+    image
+        .iter()
+        .flat_map(|x| x.iter())
+        .filter(|&&c| c == '#')
+        .count()
+        - count_sea_monsters(&mut image) * 15
+}
+
+/// Returns the product of the IDs of the corner tiles and the water
+/// roughness of the assembled image, or `None` if the tiles can't be
+/// arranged.
+pub fn solve(tiles: VecDeque<Tile>) -> Option<(usize, usize)> {
+    let grid = arrange_tiles(tiles)?;
+    Some((corners_product(&grid), water_roughness(&grid)))
+}
+
+/// The puzzle of day 20.
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = VecDeque<Tile>;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, tiles: &Self::Input) -> Answer {
+        arrange_tiles(tiles.clone())
+            .map(|grid| corners_product(&grid))
+            .into()
+    }
+
+    fn part2(&self, tiles: &Self::Input) -> Answer {
+        arrange_tiles(tiles.clone())
+            .map(|grid| water_roughness(&grid))
+            .into()
+    }
+}
+
+/// Returns the eight orientations of an image, rotated and flipped.
+fn orientations(image: &Image) -> Vec<Image> {
+    let transpose = |image: &Image| -> Image {
+        (0..image[0].len())
+            .map(|c| image.iter().map(|row| row[c]).collect())
+            .collect()
+    };
+    let mut orientations = vec![image.clone()];
+    for i in 1..8 {
+        // Transposing and then flipping upside down rotates the image.
+        let mut next = transpose(&orientations[i - 1]);
+        if i != 4 {
+            next.reverse();
+        }
+        orientations.push(next);
+    }
+    orientations
+}
+
+/// Returns the number of `#` pixels that aren't part of a sea monster, for
+/// each orientation of the image with sea monsters.
+fn rough_water(image: &Image) -> Vec<usize> {
+    let monster = SEA_MONSTER
+        .lines()
+        .skip(1)
+        .enumerate()
+        .flat_map(|(r, row)| row.char_indices().map(move |(c, x)| (r, c, x)))
+        .filter(|&(_, _, x)| x == '#')
+        .map(|(r, c, _)| (r, c))
+        .collect::<Vec<_>>();
+    let (height, width) = (3, 20);
+
+    let mut rough_water = Vec::new();
+    for image in orientations(image) {
+        let mut in_monster = vec![vec![false; image[0].len()]; image.len()];
+        for r in 0..=image.len() - height {
+            for c in 0..=image[0].len() - width {
+                if monster.iter().all(|&(dr, dc)| image[r + dr][c + dc] == '#') {
+                    monster
+                        .iter()
+                        .for_each(|&(dr, dc)| in_monster[r + dr][c + dc] = true);
+                }
+            }
+        }
+        if in_monster.iter().flatten().any(|&x| x) {
+            let pixels = image.iter().flatten().zip(in_monster.iter().flatten());
+            rough_water.push(
+                pixels
+                    .filter(|&(&x, &monster)| x == '#' && !monster)
+                    .count(),
+            );
+        }
+    }
+    rough_water
+}
+
+/// Returns the pixels on each border of an image, top, right, bottom and
+/// left.
+fn borders(image: &Image) -> [Vec<char>; 4] {
+    [
+        image[0].clone(),
+        image.iter().map(|row| row[row.len() - 1]).collect(),
+        image[image.len() - 1].clone(),
+        image.iter().map(|row| row[0]).collect(),
+    ]
+}
+
+/// Places the tiles in a square, oriented so that every border matches the
+/// one next to it, trying all possibilities one tile at a time.
+fn place_tiles(
+    tiles: &[Vec<Image>],
+    used: &mut Vec<bool>,
+    placed: &mut Vec<(usize, Image)>,
+) -> bool {
+    let len = (tiles.len() as f64).sqrt() as usize;
+    let i = placed.len();
+    if i == tiles.len() {
+        return true;
+    }
+    for t in 0..tiles.len() {
+        if used[t] {
+            continue;
+        }
+        for image in &tiles[t] {
+            let (row, col) = (i / len, i % len);
+            let fits_left = col == 0 || borders(&placed[i - 1].1)[1] == borders(image)[3];
+            let fits_top = row == 0 || borders(&placed[i - len].1)[2] == borders(image)[0];
+            if fits_left && fits_top {
+                used[t] = true;
+                placed.push((t, image.clone()));
+                if place_tiles(tiles, used, placed) {
+                    return true;
+                }
+                placed.pop();
+                used[t] = false;
+            }
+        }
+    }
+    false
+}
+
+impl Oracle for Day20 {
+    /// Generates the tiles of a random image of `size` by `size` tiles, with
+    /// one or two sea monsters in it, in a single orientation. The borders of the tiles match the
+    /// next tile only.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        const TILE_LEN: usize = 10;
+        // The image needs room for a sea monster.
+        let n = size.max(3);
+        let image_len = n * (TILE_LEN - 2);
+        // The tiles share their borders, one pixel wide.
+        let lattice_len = n * (TILE_LEN - 1) + 1;
+
+        loop {
+            let mut image = vec![vec!['.'; image_len]; image_len];
+            for pixel in image.iter_mut().flatten() {
+                if rng.ratio(1, 4) {
+                    *pixel = '#';
+                }
+            }
+            for _ in 0..rng.range(1, 3) {
+                let (r, c) = (rng.range(0, image_len - 2), rng.range(0, image_len - 19));
+                for (dr, line) in SEA_MONSTER.lines().skip(1).enumerate() {
+                    for (dc, _) in line.char_indices().filter(|&(_, x)| x == '#') {
+                        image[r + dr][c + dc] = '#';
+                    }
+                }
+            }
+            // Sea monsters are in one orientation only, and don't overlap.
+            let pixels = image.iter().flatten().filter(|&&x| x == '#').count();
+            match rough_water(&image)[..] {
+                [roughness] if (pixels - roughness) % 15 == 0 => (),
+                _ => continue,
+            }
+
+            let mut lattice = vec![vec!['.'; lattice_len]; lattice_len];
+            for (r, row) in lattice.iter_mut().enumerate() {
+                for (c, pixel) in row.iter_mut().enumerate() {
+                    // The tile and the offset in the tile, 0 on a border.
+                    let (tile_r, offset_r) = (r / (TILE_LEN - 1), r % (TILE_LEN - 1));
+                    let (tile_c, offset_c) = (c / (TILE_LEN - 1), c % (TILE_LEN - 1));
+                    *pixel = if offset_r > 0 && offset_c > 0 {
+                        let pixel_r = tile_r * (TILE_LEN - 2) + offset_r - 1;
+                        image[pixel_r][tile_c * (TILE_LEN - 2) + offset_c - 1]
+                    } else if rng.ratio(1, 2) {
+                        '#'
+                    } else {
+                        '.'
+                    };
+                }
+            }
+
+            let mut ids = Vec::new();
+            while ids.len() < n * n {
+                let id = rng.range(1000, 10000);
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+            let mut tiles = Vec::new();
+            for (i, &id) in ids.iter().enumerate() {
+                let (r, c) = (i / n * (TILE_LEN - 1), i % n * (TILE_LEN - 1));
+                let tile = lattice[r..r + TILE_LEN]
+                    .iter()
+                    .map(|row| row[c..c + TILE_LEN].to_vec())
+                    .collect::<Image>();
+                tiles.push((id, orientations(&tile)[rng.range(0, 8)].clone()));
+            }
+
+            // Every border matches another one at most, and only one way.
+            let mut all_borders = Vec::new();
+            for (_, tile) in &tiles {
+                for border in borders(tile) {
+                    let mut reversed = border.clone();
+                    reversed.reverse();
+                    all_borders.push(border.min(reversed));
+                }
+            }
+            let palindrome = all_borders.iter().any(|b| b.iter().eq(b.iter().rev()));
+            let mut unique_borders = all_borders.clone();
+            unique_borders.sort_unstable();
+            unique_borders.dedup();
+            let max_count = unique_borders
+                .iter()
+                .map(|b| all_borders.iter().filter(|x| *x == b).count())
+                .max();
+            if palindrome || max_count != Some(2) {
+                continue;
+            }
+
+            rng.shuffle(&mut tiles);
+            let tiles = tiles.iter().map(|(id, tile)| {
+                let rows = tile.iter().map(|row| row.iter().collect::<String>());
+                format!("Tile {}:\n{}\n", id, rows.collect::<Vec<_>>().join("\n"))
+            });
+            return tiles.collect::<Vec<_>>().join("\n");
+        }
+    }
+
+    fn oracle(&self, tiles: &Self::Input, part: usize) -> Answer {
+        let images = tiles
+            .iter()
+            .map(|t| orientations(t.image()))
+            .collect::<Vec<_>>();
+        let mut placed = Vec::new();
+        if !place_tiles(&images, &mut vec![false; images.len()], &mut placed) {
+            return Answer::Unsolved;
+        }
+        let len = (tiles.len() as f64).sqrt() as usize;
+        if part == 1 {
+            let corners = [0, len - 1, len * (len - 1), len * len - 1];
+            return corners
+                .iter()
+                .map(|&i| tiles[placed[i].0].id())
+                .product::<usize>()
+                .into();
+        }
+
+        let tile_len = placed[0].1.len();
+        let mut image = Image::new();
+        for row in placed.chunks(len) {
+            for r in 1..tile_len - 1 {
+                image.push(
+                    row.iter()
+                        .flat_map(|(_, tile)| tile[r][1..tile_len - 1].to_vec())
+                        .collect(),
+                );
+            }
+        }
+        rough_water(&image).first().copied().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+    use std::fs;
+
+    #[test]
+    fn test_example_input() {
+        let tiles = parse_input("example.txt").unwrap();
+        assert_eq!(solve(tiles), Some((20899048083289, 273)));
+    }
+
+    #[test]
+    fn test_crlf_input() {
+        let content = fs::read_to_string("example.txt").unwrap();
+        let content = format!("\u{feff}{}\n\n", content).replace('\n', "\r\n");
+        let tiles = parse(&content).unwrap();
+        assert_eq!(solve(tiles), Some((20899048083289, 273)));
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day20, 3);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day20, 20, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let tiles = Day20.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(Day20.part1(&tiles), Answer::Number(20899048083289));
+        assert_eq!(Day20.part2(&tiles), Answer::Number(273));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "Tile 2311:\n#.\n..\n\nTile 1951\n.#\n#.\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid tile header `Tile 1951`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (5, 1));
+
+        let err = parse("").err().unwrap();
+        assert_eq!(err.to_string(), "no tiles");
+        assert!(err.location().is_none());
+        let err = parse("Tile 1:\n#.\n..\n\nTile 2:\n.#\n#.\n").err().unwrap();
+        assert_eq!(err.to_string(), "2 tiles don't make a square");
+    }
+
+    #[test]
+    fn test_unsolved() {
+        let tiles = parse("Tile 1:\n#\n").unwrap();
+        assert_eq!(solve(tiles), Some((1, 0)));
+
+        let tile = |id: usize, pixel: char| {
+            format!("Tile {}:\n{}{}\n{}{}\n\n", id, pixel, pixel, pixel, pixel)
+        };
+        let input = tile(1, '#') + &tile(2, '#') + &tile(3, '#') + &tile(4, '.');
+        let tiles = parse(&input).unwrap();
+        assert_eq!(Day20.part1(&tiles), Answer::Unsolved);
+        assert_eq!(Day20.part2(&tiles), Answer::Unsolved);
+    }
+}
@@ -0,0 +1,321 @@
+//! Solution to the puzzle of day 21, _Allergen Assessment_.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+use regex::Regex;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+/// Maps each allergen to the ingredient lists of the foods containing it.
+pub type AlergenIngredientsLists = HashMap<String, Vec<HashSet<String>>>;
+
+/// Returns the number of appearances of the ingredients that can't
+/// contain an allergen, and the canonical dangerous ingredient list.
+pub fn find_allergens(
+    ingredients: &[String],
+    allergens: &AlergenIngredientsLists,
+) -> (usize, String) {
+    // Ingredient candidates for an allergen
+    let mut allergen_candidates = HashMap::new();
+
+    for (allergen, ingredients_lists) in allergens {
+        // Get the list of ingredients that can contain this allergen.
+        // For an ingredient to be a candidate, it must appear in all
+        // ingredient lists that have that allergen.
+        let intersect = ingredients_lists[0]
+            .iter()
+            .filter(|x| ingredients_lists[1..].iter().all(|s| s.contains(*x)))
+            .collect::<HashSet<_>>();
+        allergen_candidates.insert(allergen, intersect);
+    }
+
+    let safe_ingredients_count = ingredients
+        .iter()
+        .filter(|&ingredient| {
+            !allergen_candidates
+                .values()
+                .any(|candidates| candidates.contains(ingredient))
+        })
+        .count();
+
+    // (allergen, ingredient) tuples with the ingredient that was found
+    // to correspond to a given allergen.
+    let mut allergens_discovered = vec![];
+
+    while allergens_discovered.len() != allergens.len() {
+        let (allergen, candidates) = allergen_candidates
+            .iter_mut()
+            .find(|(_, candidates)| candidates.len() == 1)
+            .unwrap();
+        let allergen = *allergen;
+        let ingredient = *candidates.iter().next().unwrap();
+
+        allergen_candidates.remove(&allergen);
+        for candidates in &mut allergen_candidates.values_mut() {
+            candidates.remove(&ingredient);
+        }
+
+        allergens_discovered.push((allergen, ingredient));
+    }
+
+    allergens_discovered.sort_by_key(|(allergen, _)| *allergen);
+    let unsafe_ingredients = allergens_discovered
+        .into_iter()
+        .map(|(_, ingredient)| ingredient.to_owned())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    (safe_ingredients_count, unsafe_ingredients)
+}
+
+/// An error found while parsing the list of foods.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A line isn't a food like `a b (contains x, y)`.
+    InvalidFood(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidFood(loc) => write!(f, "invalid food `{}`", loc.token),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidFood(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the ingredients of every food and the ingredient lists of
+/// each allergen.
+pub fn parse_input(
+    file_name: impl AsRef<Path>,
+) -> Result<(Vec<String>, AlergenIngredientsLists), ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<(Vec<String>, AlergenIngredientsLists), ParseError> {
+    let re = Regex::new(r"(?P<ingredients>.+) \(contains (?P<allergens>.+)\)").unwrap();
+
+    let mut ingredients = vec![];
+    let mut allergens = HashMap::new();
+
+    for line in source.content().trim().lines() {
+        let caps = re
+            .captures(line)
+            .ok_or_else(|| ParseError::InvalidFood(source.locate(line)))?;
+        let parsed_ingredients = caps["ingredients"].split_whitespace();
+        let parsed_allergens = caps["allergens"].split(", ");
+
+        for ingredient in parsed_ingredients.clone() {
+            ingredients.push(ingredient.to_owned());
+        }
+
+        for allergen in parsed_allergens {
+            allergens
+                .entry(allergen.to_string())
+                .or_insert_with(Vec::new)
+                .push(
+                    parsed_ingredients
+                        .clone()
+                        .map(|ingredient| ingredient.to_owned())
+                        .collect::<HashSet<String>>(),
+                );
+        }
+    }
+
+    Ok((ingredients, allergens))
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<(Vec<String>, AlergenIngredientsLists), ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The puzzle of day 21.
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = (Vec<String>, AlergenIngredientsLists);
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, (ingredients, allergens): &Self::Input) -> Answer {
+        find_allergens(ingredients, allergens).0.into()
+    }
+
+    fn part2(&self, (ingredients, allergens): &Self::Input) -> Answer {
+        find_allergens(ingredients, allergens).1.into()
+    }
+}
+
+/// Returns every way to assign a different ingredient to each allergen, in
+/// the order of `allergens`, so that every food with an allergen has its
+/// ingredient.
+fn assignments<'a>(
+    ingredients: &[&'a String],
+    allergens: &[(&String, &Vec<HashSet<String>>)],
+) -> Vec<Vec<&'a String>> {
+    let Some(((_, lists), rest)) = allergens.split_first() else {
+        return vec![vec![]];
+    };
+    let mut assignments = Vec::new();
+    for (i, ingredient) in ingredients.iter().enumerate() {
+        if lists.iter().all(|list| list.contains(*ingredient)) {
+            let others = [&ingredients[..i], &ingredients[i + 1..]].concat();
+            for mut assignment in self::assignments(&others, rest) {
+                assignment.insert(0, ingredient);
+                assignments.push(assignment);
+            }
+        }
+    }
+    assignments
+}
+
+impl Oracle for Day21 {
+    /// Generates `size` foods with up to 4 allergens, which can be told
+    /// apart.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let names = [
+            "dairy", "eggs", "fish", "nuts", "peanuts", "sesame", "soy", "wheat",
+        ];
+        loop {
+            let mut allergens = names.to_vec();
+            rng.shuffle(&mut allergens);
+            allergens.truncate(rng.range(1, 5));
+            let ingredients = (0..allergens.len() + rng.range(2, 6))
+                .map(|i| format!("{}{}", ["mx", "kf", "sq", "fv"][i % 4], i))
+                .collect::<Vec<_>>();
+
+            let mut foods = Vec::new();
+            for _ in 0..size.max(1) {
+                let mut food = ingredients
+                    .iter()
+                    .enumerate()
+                    .filter(|_| rng.ratio(1, 2))
+                    .collect::<Vec<_>>();
+                if !food.iter().any(|&(i, _)| i < allergens.len()) {
+                    let i = rng.range(0, allergens.len());
+                    food.push((i, &ingredients[i]));
+                }
+                rng.shuffle(&mut food);
+                // Allergens aren't always listed.
+                let mut contains = food
+                    .iter()
+                    .filter(|&&(i, _)| i < allergens.len())
+                    .map(|&(i, _)| allergens[i])
+                    .collect::<Vec<_>>();
+                rng.shuffle(&mut contains);
+                contains.truncate(rng.range(1, contains.len() + 1));
+                let food = food.iter().map(|&(_, x)| x.as_str()).collect::<Vec<_>>();
+                foods.push(format!(
+                    "{} (contains {})\n",
+                    food.join(" "),
+                    contains.join(", ")
+                ));
+            }
+            let input = foods.concat();
+
+            // Only one assignment of the allergens fits, and ingredients
+            // without allergens are ruled out by the foods of each allergen.
+            let (ingredients, allergens) = parse(&input).unwrap();
+            let mut allergens = allergens.iter().collect::<Vec<_>>();
+            allergens.sort_unstable_by_key(|(allergen, _)| *allergen);
+            let mut distinct = ingredients.iter().collect::<Vec<_>>();
+            distinct.sort_unstable();
+            distinct.dedup();
+            let assignments = assignments(&distinct, &allergens);
+            let candidates = distinct.iter().filter(|x| {
+                let mut lists = allergens.iter().map(|(_, lists)| lists);
+                lists.any(|lists| lists.iter().all(|list| list.contains(**x)))
+            });
+            if assignments.len() == 1 && candidates.count() == allergens.len() {
+                return input;
+            }
+        }
+    }
+
+    fn oracle(&self, (ingredients, allergens): &Self::Input, part: usize) -> Answer {
+        let mut allergens = allergens.iter().collect::<Vec<_>>();
+        allergens.sort_unstable_by_key(|(allergen, _)| *allergen);
+        let mut distinct = ingredients.iter().collect::<Vec<_>>();
+        distinct.sort_unstable();
+        distinct.dedup();
+        let assignments = assignments(&distinct, &allergens);
+
+        if part == 1 {
+            let possible = |x: &String| assignments.iter().any(|a| a.contains(&x));
+            ingredients.iter().filter(|x| !possible(x)).count().into()
+        } else if let [assignment] = &assignments[..] {
+            let assignment = assignment.iter().map(|x| x.as_str()).collect::<Vec<_>>();
+            assignment.join(",").into()
+        } else {
+            Answer::Unsolved
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input() {
+        let (ingredients, allergens) = parse_input("example.txt").unwrap();
+        let s = "mxmxvkd,sqjhc,fvjkl".to_string();
+        assert_eq!(find_allergens(&ingredients, &allergens), (5, s));
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day21, 8);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day21, 21, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let food = Day21.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(Day21.part1(&food), Answer::Number(5));
+        assert_eq!(Day21.part2(&food), Answer::from("mxmxvkd,sqjhc,fvjkl"));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new(
+            "test.txt",
+            "mxmxvkd kfcds (contains dairy, fish)\ntrh fvjkl sbzzf mxmxvkd\n",
+        );
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid food `trh fvjkl sbzzf mxmxvkd`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (2, 1));
+    }
+}
@@ -0,0 +1,298 @@
+//! Solution to the puzzle of day 22, _Crab Combat_.
+
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+/// A deck of space cards, top card first.
+pub type Deck = VecDeque<usize>;
+
+enum Player {
+    One,
+    Two,
+}
+
+fn calculate_score(deck1: &Deck, deck2: &Deck) -> usize {
+    let winner_hand = if !deck1.is_empty() { deck1 } else { deck2 };
+    winner_hand
+        .iter()
+        .rev()
+        .enumerate()
+        .fold(0, |acc, (i, v)| acc + v * (i + 1))
+}
+
+/// Plays Combat and returns the winning player's score.
+pub fn combat_score(deck1: &mut Deck, deck2: &mut Deck) -> usize {
+    while !deck1.is_empty() && !deck2.is_empty() {
+        let card1 = deck1.pop_front().unwrap();
+        let card2 = deck2.pop_front().unwrap();
+        if card1 > card2 {
+            deck1.push_back(card1);
+            deck1.push_back(card2);
+        } else {
+            deck2.push_back(card2);
+            deck2.push_back(card1);
+        }
+    }
+    calculate_score(deck1, deck2)
+}
+
+fn recursive_combat(deck1: &mut Deck, deck2: &mut Deck) -> Player {
+    let mut played_hands = HashSet::new();
+
+    while !deck1.is_empty() && !deck2.is_empty() {
+        if !played_hands.insert((deck1.clone(), deck2.clone())) {
+            return Player::One;
+        }
+
+        let card1 = deck1.pop_front().unwrap();
+        let card2 = deck2.pop_front().unwrap();
+
+        if deck1.len() >= card1 && deck2.len() >= card2 {
+            let mut subdeck1 = deck1.iter().take(card1).cloned().collect();
+            let mut subdeck2 = deck2.iter().take(card2).cloned().collect();
+            match recursive_combat(&mut subdeck1, &mut subdeck2) {
+                Player::One => {
+                    deck1.push_back(card1);
+                    deck1.push_back(card2);
+                }
+                Player::Two => {
+                    deck2.push_back(card2);
+                    deck2.push_back(card1);
+                }
+            }
+        } else if card1 > card2 {
+            deck1.push_back(card1);
+            deck1.push_back(card2);
+        } else {
+            deck2.push_back(card2);
+            deck2.push_back(card1);
+        }
+    }
+
+    if !deck1.is_empty() {
+        Player::One
+    } else {
+        Player::Two
+    }
+}
+
+/// Plays Recursive Combat and returns the winning player's score.
+pub fn recursive_combat_score(deck1: &mut Deck, deck2: &mut Deck) -> usize {
+    recursive_combat(deck1, deck2);
+    calculate_score(deck1, deck2)
+}
+
+/// An error found while parsing the starting decks.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A card isn't a number.
+    InvalidCard(Location),
+    /// The notes end before the deck of player 2.
+    MissingDeck(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidCard(loc) => write!(f, "invalid card `{}`", loc.token),
+            ParseError::MissingDeck(_) => write!(f, "missing deck of player 2"),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidCard(loc) | ParseError::MissingDeck(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the starting decks of both players.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<(Deck, Deck), ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<(Deck, Deck), ParseError> {
+    let content = source.content();
+    let parse_deck = |block: &str| {
+        block
+            .trim()
+            .lines()
+            .skip(1)
+            .map(|x| {
+                x.parse()
+                    .map_err(|_| ParseError::InvalidCard(source.locate(x)))
+            })
+            .collect::<Result<Deck, _>>()
+    };
+
+    let mut blocks = source.paragraphs();
+    match (blocks.next(), blocks.next()) {
+        (Some(block1), Some(block2)) => Ok((parse_deck(block1)?, parse_deck(block2)?)),
+        _ => Err(ParseError::MissingDeck(
+            source.locate_end(content.trim_end()),
+        )),
+    }
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<(Deck, Deck), ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The puzzle of day 22.
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = (Deck, Deck);
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, (deck1, deck2): &Self::Input) -> Answer {
+        combat_score(&mut deck1.clone(), &mut deck2.clone()).into()
+    }
+
+    fn part2(&self, (deck1, deck2): &Self::Input) -> Answer {
+        recursive_combat_score(&mut deck1.clone(), &mut deck2.clone()).into()
+    }
+}
+
+/// Plays a game of Combat, recursive or not, returning whether player 1
+/// wins and the decks at the end, or `None` if a game without recursion
+/// never ends.
+fn play(
+    mut deck1: Vec<usize>,
+    mut deck2: Vec<usize>,
+    recursive: bool,
+) -> Option<(bool, Vec<usize>)> {
+    let mut history = Vec::new();
+    while !deck1.is_empty() && !deck2.is_empty() {
+        let round = (deck1.clone(), deck2.clone());
+        if history.contains(&round) {
+            return match recursive {
+                true => Some((true, deck1)),
+                false => None,
+            };
+        }
+        history.push(round);
+
+        let card1 = deck1.remove(0);
+        let card2 = deck2.remove(0);
+        let player1_wins = if recursive && deck1.len() >= card1 && deck2.len() >= card2 {
+            play(deck1[..card1].to_vec(), deck2[..card2].to_vec(), true)?.0
+        } else {
+            card1 > card2
+        };
+        if player1_wins {
+            deck1.extend([card1, card2]);
+        } else {
+            deck2.extend([card2, card1]);
+        }
+    }
+    match deck2.is_empty() {
+        true => Some((true, deck1)),
+        false => Some((false, deck2)),
+    }
+}
+
+impl Oracle for Day22 {
+    /// Generates two decks of `size` cards, with which a game without
+    /// recursion ends.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let size = size.max(1);
+        loop {
+            let mut cards = (1..=2 * size).collect::<Vec<_>>();
+            rng.shuffle(&mut cards);
+            let (deck1, deck2) = cards.split_at(size);
+            if play(deck1.to_vec(), deck2.to_vec(), false).is_some() {
+                let deck = |cards: &[usize]| {
+                    let cards = cards.iter().map(|x| format!("{}\n", x));
+                    cards.collect::<String>()
+                };
+                return format!("Player 1:\n{}\nPlayer 2:\n{}", deck(deck1), deck(deck2));
+            }
+        }
+    }
+
+    fn oracle(&self, (deck1, deck2): &Self::Input, part: usize) -> Answer {
+        let deck1 = deck1.iter().copied().collect();
+        let deck2 = deck2.iter().copied().collect();
+        play(deck1, deck2, part == 2)
+            .map(|(_, deck)| {
+                let cards = deck.iter().rev().enumerate();
+                cards.map(|(i, card)| (i + 1) * card).sum::<usize>()
+            })
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+    use std::fs;
+
+    #[test]
+    fn test_example_input() {
+        let (deck1, deck2) = parse_input("example.txt").unwrap();
+        assert_eq!(combat_score(&mut deck1.clone(), &mut deck2.clone()), 306);
+        assert_eq!(
+            recursive_combat_score(&mut deck1.clone(), &mut deck2.clone()),
+            291
+        );
+    }
+
+    #[test]
+    fn test_crlf_input() {
+        let content = fs::read_to_string("example.txt").unwrap();
+        let content = format!("\u{feff}{}\n\n", content).replace('\n', "\r\n");
+        let (deck1, deck2) = parse(&content).unwrap();
+        assert_eq!(combat_score(&mut deck1.clone(), &mut deck2.clone()), 306);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day22, 10);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day22, 22, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let decks = Day22.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(Day22.part1(&decks), Answer::Number(306));
+        assert_eq!(Day22.part2(&decks), Answer::Number(291));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "Player 1:\n9\n2\n\nPlayer 2:\n5\nten\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid card `ten`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (7, 1));
+    }
+}
@@ -0,0 +1,326 @@
+//! Solution to the puzzle of day 23, _Crab Cups_.
+
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+/// The number of moves in part 1.
+pub const PART_1_NUM_ITERS: usize = 100;
+/// The number of moves in part 2.
+pub const PART_2_NUM_ITERS: usize = 10_000_000;
+
+/// The number of cups in part 2.
+pub const PART_2_NUM_CUPS: usize = 1_000_000;
+
+fn iterate(cups: &mut [usize], cup: usize) -> usize {
+    let lowest = 1;
+    let highest = cups.len() - 1;
+
+    let a = cups[cup];
+    let b = cups[a];
+    let c = cups[b];
+
+    cups[cup] = cups[c];
+
+    let mut dest = cup - 1;
+    while dest == a || dest == b || dest == c || dest < lowest {
+        if dest < lowest {
+            dest = highest;
+        } else {
+            dest -= 1;
+        }
+    }
+
+    let dest_next = cups[dest];
+    cups[dest] = a;
+    cups[c] = dest_next;
+
+    cups[cup]
+}
+
+/// Returns the labels of the cups after cup 1 following `num_iters`
+/// moves, given labels validated by [`parse_source`].
+pub fn calculate_part1(cups: &str, num_iters: usize) -> String {
+    let input = cups
+        .chars()
+        .map(|x| x.to_digit(10).unwrap() as usize)
+        .collect::<Vec<_>>();
+
+    let mut cups = vec![0; input.len() + 1];
+    for i in 0..input.len() - 1 {
+        cups[input[i]] = input[i + 1];
+    }
+
+    cups[input[input.len() - 1]] = input[0];
+
+    let mut cup = input[0];
+    for _ in 0..num_iters {
+        cup = iterate(&mut cups, cup);
+    }
+
+    let mut cup = cups[1];
+    let mut result = String::new();
+    while cup != 1 {
+        result.push(cup.to_string().chars().next().unwrap());
+        cup = cups[cup];
+    }
+
+    result
+}
+
+/// Multiplies the labels of the two cups after cup 1 following
+/// `num_iters` moves with one million cups, given labels validated by
+/// [`parse_source`].
+pub fn calculate_part2(cups: &str, num_iters: usize) -> usize {
+    calculate_part2_with_cups(cups, PART_2_NUM_CUPS, num_iters)
+}
+
+/// Same as [`calculate_part2`], but with `num_cups` cups, which must be
+/// more than the labels.
+pub fn calculate_part2_with_cups(cups: &str, num_cups: usize, num_iters: usize) -> usize {
+    let input = cups
+        .chars()
+        .map(|x| x.to_digit(10).unwrap() as usize)
+        .collect::<Vec<_>>();
+
+    let mut cups = vec![0; num_cups + 1];
+    for i in 0..input.len() - 1 {
+        cups[input[i]] = input[i + 1];
+    }
+
+    cups[*input.iter().last().unwrap()] = input.len() + 1;
+
+    for (i, cup) in cups
+        .iter_mut()
+        .enumerate()
+        .take(num_cups)
+        .skip(input.len() + 1)
+    {
+        *cup = i + 1;
+    }
+
+    *cups.last_mut().unwrap() = input[0];
+
+    let mut cup = input[0];
+    for _ in 0..num_iters {
+        cup = iterate(&mut cups, cup);
+    }
+
+    cups[1] * cups[cups[1]]
+}
+
+/// An error found while parsing the cup labels.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A label isn't a digit from 1 up to the number of cups.
+    InvalidCup(Location),
+    /// A label appears more than once.
+    DuplicateCup(Location),
+    /// There are no cups.
+    MissingCups(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidCup(loc) => write!(f, "invalid cup label `{}`", loc.token),
+            ParseError::DuplicateCup(loc) => write!(f, "duplicate cup label `{}`", loc.token),
+            ParseError::MissingCups(_) => write!(f, "missing cup labels"),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidCup(loc)
+            | ParseError::DuplicateCup(loc)
+            | ParseError::MissingCups(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the labels of the cups, where each digit from 1 up to the
+/// number of cups appears once.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<String, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<String, ParseError> {
+    let cups = source.content().trim();
+    if cups.is_empty() {
+        return Err(ParseError::MissingCups(source.locate(cups)));
+    }
+
+    let num_cups = cups.chars().count();
+    let mut seen = [false; 10];
+    for (i, ch) in cups.char_indices() {
+        let label = &cups[i..i + ch.len_utf8()];
+        match ch.to_digit(10) {
+            Some(d) if d >= 1 && d as usize <= num_cups => {
+                if seen[d as usize] {
+                    return Err(ParseError::DuplicateCup(source.locate(label)));
+                }
+                seen[d as usize] = true;
+            }
+            _ => return Err(ParseError::InvalidCup(source.locate(label))),
+        }
+    }
+
+    Ok(cups.to_owned())
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<String, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The puzzle of day 23.
+pub struct Day23 {
+    /// Number of moves in part 1.
+    pub part1_num_iters: usize,
+    /// Number of moves in part 2.
+    pub part2_num_iters: usize,
+    /// Number of cups in part 2.
+    pub part2_num_cups: usize,
+}
+
+impl Default for Day23 {
+    fn default() -> Self {
+        Day23 {
+            part1_num_iters: PART_1_NUM_ITERS,
+            part2_num_iters: PART_2_NUM_ITERS,
+            part2_num_cups: PART_2_NUM_CUPS,
+        }
+    }
+}
+
+impl Solution for Day23 {
+    type Input = String;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, cups: &Self::Input) -> Answer {
+        calculate_part1(cups, self.part1_num_iters).into()
+    }
+
+    fn part2(&self, cups: &Self::Input) -> Answer {
+        calculate_part2_with_cups(cups, self.part2_num_cups, self.part2_num_iters).into()
+    }
+}
+
+/// Plays the moves on the cups, in order from the current cup.
+fn play_moves(mut cups: Vec<usize>, num_moves: usize) -> Vec<usize> {
+    let highest = cups.len();
+    for _ in 0..num_moves {
+        let current = cups[0];
+        let picked_up = cups.drain(1..4).collect::<Vec<_>>();
+        let mut destination = current;
+        loop {
+            destination = if destination == 1 {
+                highest
+            } else {
+                destination - 1
+            };
+            if !picked_up.contains(&destination) {
+                break;
+            }
+        }
+        let i = cups.iter().position(|&x| x == destination).unwrap();
+        cups.splice(i + 1..i + 1, picked_up);
+        cups.rotate_left(1);
+    }
+    // Start from cup 1.
+    let i = cups.iter().position(|&x| x == 1).unwrap();
+    cups.rotate_left(i);
+    cups
+}
+
+impl Oracle for Day23 {
+    /// Generates the labels of 5 to `size` cups.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let mut cups = (1..=rng.range(5, size.clamp(5, 9) + 1)).collect::<Vec<_>>();
+        rng.shuffle(&mut cups);
+        cups.iter().map(|x| x.to_string()).collect::<String>() + "\n"
+    }
+
+    fn oracle(&self, labels: &Self::Input, part: usize) -> Answer {
+        let mut cups = labels
+            .chars()
+            .map(|x| x.to_digit(10).unwrap() as usize)
+            .collect::<Vec<_>>();
+        if part == 1 {
+            let cups = play_moves(cups, self.part1_num_iters);
+            let labels = cups[1..].iter().map(|x| x.to_string());
+            labels.collect::<String>().into()
+        } else {
+            cups.extend(cups.len() + 1..=self.part2_num_cups);
+            let cups = play_moves(cups, self.part2_num_iters);
+            (cups[1] * cups[2]).into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input() {
+        assert_eq!(calculate_part1("389125467", PART_1_NUM_ITERS), "67384529");
+        assert_eq!(calculate_part2("389125467", PART_2_NUM_ITERS), 149245887792);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(
+            &Day23 {
+                part1_num_iters: 100,
+                part2_num_iters: 100,
+                part2_num_cups: 100,
+            },
+            9,
+        );
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day23::default(), 23, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let day = Day23 {
+            part1_num_iters: 10,
+            ..Default::default()
+        };
+        let cups = day.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(day.part1(&cups), Answer::from("92658374"));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "3891254670\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid cup label `0`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (1, 10));
+    }
+}
@@ -0,0 +1,263 @@
+//! Solution to the puzzle of day 24, _Lobby Layout_.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+/// The number of days the tiles are flipped in part 2.
+pub const NUM_DAYS: usize = 100;
+
+const NEIGHBORS: [(isize, isize); 6] = [(-1, 1), (1, 1), (2, 0), (1, -1), (-1, -1), (-2, 0)];
+
+/// The positions of the black tiles, in doubled coordinates.
+pub type Tiles = HashSet<(isize, isize)>;
+
+fn iterate_once(tiles: Tiles) -> Tiles {
+    let mut counter = HashMap::new();
+
+    tiles
+        .iter()
+        .flat_map(|(x, y)| {
+            NEIGHBORS
+                .iter()
+                .map(move |neigh| (x + neigh.0, y + neigh.1))
+        })
+        .for_each(|(x, y)| *counter.entry((x, y)).or_insert(0) += 1);
+
+    counter
+        .iter()
+        .filter(|&(k, &c)| c == 2 || (c == 1 && tiles.contains(k)))
+        .map(|(k, _)| k)
+        .cloned()
+        .collect()
+}
+
+/// Flips the tiles for 100 days.
+pub fn iterate(tiles: Tiles) -> Tiles {
+    iterate_days(tiles, NUM_DAYS)
+}
+
+/// Flips the tiles for the given number of days.
+pub fn iterate_days(mut tiles: Tiles, num_days: usize) -> Tiles {
+    for _ in 0..num_days {
+        tiles = iterate_once(tiles);
+    }
+    tiles
+}
+
+/// An error found while parsing the tiles to flip.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A direction isn't one of `e`, `se`, `sw`, `w`, `nw` or `ne`.
+    InvalidDirection(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidDirection(loc) => write!(f, "invalid direction `{}`", loc.token),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidDirection(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the tiles to flip and returns the black tiles.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<Tiles, ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<Tiles, ParseError> {
+    let mut tiles = HashSet::new();
+
+    /*
+     * Use double coordinates to represent the hexagonal grid as
+     * explained here:
+     * https://www.redblobgames.com/grids/hexagons/#coordinates-doubled
+     */
+    for line in source.content().lines() {
+        let (mut x, mut y, mut rest) = (0, 0, line);
+        while !rest.is_empty() {
+            let n = if rest.starts_with('n') || rest.starts_with('s') {
+                2
+            } else {
+                1
+            };
+            let len = rest.chars().take(n).map(char::len_utf8).sum();
+            let (coord, tail) = rest.split_at(len);
+            match coord {
+                "nw" => {
+                    x -= 1;
+                    y += 1;
+                }
+                "ne" => {
+                    x += 1;
+                    y += 1;
+                }
+                "e" => x += 2,
+                "se" => {
+                    x += 1;
+                    y -= 1;
+                }
+                "sw" => {
+                    x -= 1;
+                    y -= 1;
+                }
+                "w" => x -= 2,
+                _ => return Err(ParseError::InvalidDirection(source.locate(coord))),
+            }
+            rest = tail;
+        }
+        if !tiles.remove(&(x, y)) {
+            tiles.insert((x, y));
+        }
+    }
+
+    Ok(tiles)
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<Tiles, ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The puzzle of day 24.
+pub struct Day24 {
+    /// Number of days the tiles are flipped in part 2.
+    pub num_days: usize,
+}
+
+impl Default for Day24 {
+    fn default() -> Self {
+        Day24 { num_days: NUM_DAYS }
+    }
+}
+
+impl Solution for Day24 {
+    type Input = Tiles;
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, tiles: &Self::Input) -> Answer {
+        tiles.len().into()
+    }
+
+    fn part2(&self, tiles: &Self::Input) -> Answer {
+        iterate_days(tiles.clone(), self.num_days).len().into()
+    }
+}
+
+impl Oracle for Day24 {
+    /// Generates `size` tiles to flip, close to the reference tile so that
+    /// some get flipped twice.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let directions = ["e", "se", "sw", "w", "nw", "ne"];
+        let lines = (0..size.max(1)).map(|_| {
+            let steps = (0..rng.range(0, 6)).map(|_| *rng.choose(&directions));
+            steps.collect::<String>() + "\n"
+        });
+        lines.collect()
+    }
+
+    fn oracle(&self, tiles: &Self::Input, part: usize) -> Answer {
+        // The tiles are flipped while parsing.
+        if part == 1 {
+            return tiles.len().into();
+        }
+
+        // Flip the tiles in axial coordinates instead.
+        const AXIAL_NEIGHBORS: [(isize, isize); 6] =
+            [(1, 0), (-1, 0), (0, 1), (0, -1), (1, -1), (-1, 1)];
+        let mut black = tiles
+            .iter()
+            .map(|&(x, y)| ((x - y) / 2, y))
+            .collect::<HashSet<_>>();
+        for _ in 0..self.num_days {
+            let neighbors =
+                |(q, r): (isize, isize)| AXIAL_NEIGHBORS.map(|(dq, dr)| (q + dq, r + dr));
+            let candidates = black
+                .iter()
+                .flat_map(|&tile| {
+                    neighbors(tile)
+                        .iter()
+                        .copied()
+                        .chain(Some(tile))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<HashSet<_>>();
+            black = candidates
+                .into_iter()
+                .filter(|&tile| {
+                    let count = neighbors(tile).iter().filter(|x| black.contains(x)).count();
+                    count == 2 || count == 1 && black.contains(&tile)
+                })
+                .collect();
+        }
+        black.len().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input() {
+        let tiles1 = parse_input("example.txt").unwrap();
+        let tiles2 = iterate(tiles1.clone());
+        assert_eq!(tiles1.len(), 10);
+        assert_eq!(tiles2.len(), 2208);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day24 { num_days: 10 }, 10);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day24::default(), 24, &[1, 2]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let day = Day24::default();
+        let tiles = day.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(day.part1(&tiles), Answer::Number(10));
+        assert_eq!(day.part2(&tiles), Answer::Number(2208));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "sesenwnene\nneeenesw\nwwnx\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid direction `nx`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (3, 3));
+    }
+}
@@ -0,0 +1,185 @@
+//! Solution to the puzzle of day 25, _Combo Breaker_.
+
+use std::fmt;
+use std::path::Path;
+
+use common::{Answer, Diagnostic, IoError, Location, Oracle, Rng, Solution, Source};
+
+const MODULUS: usize = 20201227;
+
+fn discrete_logarithm(power: usize, base: usize, modulus: usize) -> usize {
+    /* Solve the following congruence:
+     *
+     * base^exponent ≡ power (mod modulus)
+     *
+     * A possible improvement would be finding the discrete logarithm
+     * using the baby-step giant-step algorithm, but this brute-force
+     * approach already runs fast enough for the input.
+     */
+    let mut num = 1;
+    let mut exponent = 0;
+    while num != power {
+        num = num * base % modulus;
+        exponent += 1;
+    }
+    exponent
+}
+
+/// Returns the encryption key the card and the door are using.
+pub fn find_encryption_key(door_pub_key: usize, card_pub_key: usize) -> usize {
+    let loop_size = discrete_logarithm(door_pub_key, 7, MODULUS);
+    (0..loop_size).fold(1, |acc, _| acc * card_pub_key % MODULUS)
+}
+
+/// An error found while parsing the public keys.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The input file couldn't be read.
+    Io(IoError),
+    /// A public key isn't a number between 1 and 20201226.
+    InvalidKey(Location),
+    /// The input ends before the public key of the card.
+    MissingKey(Location),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidKey(loc) => write!(f, "invalid public key `{}`", loc.token),
+            ParseError::MissingKey(_) => write!(f, "missing public key of the card"),
+        }
+    }
+}
+
+impl Diagnostic for ParseError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidKey(loc) | ParseError::MissingKey(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for ParseError {
+    fn from(err: IoError) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+/// Parses the public keys of the door and the card.
+pub fn parse_input(file_name: impl AsRef<Path>) -> Result<(usize, usize), ParseError> {
+    parse_source(&Source::read(file_name)?)
+}
+
+/// Same as [`parse_input`], but parsing an in-memory source.
+pub fn parse_source(source: &Source) -> Result<(usize, usize), ParseError> {
+    let content = source.content();
+    let parse_key = |key: &str| match key.parse() {
+        Ok(n) if (1..MODULUS).contains(&n) => Ok(n),
+        _ => Err(ParseError::InvalidKey(source.locate(key))),
+    };
+
+    let mut lines = content.lines();
+    let door_pub_key = parse_key(lines.next().unwrap_or(content))?;
+    let card_pub_key = lines
+        .next()
+        .ok_or_else(|| ParseError::MissingKey(source.locate_end(content.trim_end())))?;
+
+    Ok((door_pub_key, parse_key(card_pub_key)?))
+}
+
+/// Same as [`parse_input`], but parsing a string.
+pub fn parse(input: &str) -> Result<(usize, usize), ParseError> {
+    parse_source(&Source::from(input))
+}
+
+/// The puzzle of day 25.
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = (usize, usize);
+
+    type Error = ParseError;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error> {
+        parse_source(source)
+    }
+
+    fn part1(&self, &(door_pub_key, card_pub_key): &Self::Input) -> Answer {
+        find_encryption_key(door_pub_key, card_pub_key).into()
+    }
+
+    fn part2(&self, _: &Self::Input) -> Answer {
+        Answer::Unsolved
+    }
+}
+
+/// Transforms a subject number with the given loop size.
+fn transform(subject: usize, loop_size: usize) -> usize {
+    let mut value = 1;
+    for _ in 0..loop_size {
+        value = value * subject % MODULUS;
+    }
+    value
+}
+
+impl Oracle for Day25 {
+    /// Generates the public keys of a door and a card with loop sizes up to
+    /// `size`.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        let door_loop_size = rng.range(1, size.max(2));
+        let card_loop_size = rng.range(1, size.max(2));
+        format!(
+            "{}\n{}\n",
+            transform(7, door_loop_size),
+            transform(7, card_loop_size)
+        )
+    }
+
+    fn oracle(&self, &(door_pub_key, card_pub_key): &Self::Input, part: usize) -> Answer {
+        if part == 2 {
+            return Answer::Unsolved;
+        }
+        // Work out the loop size of the card, rather than the door's.
+        let card_loop_size = (0..).find(|&x| transform(7, x) == card_pub_key).unwrap();
+        transform(door_pub_key, card_loop_size).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{check_answers, cross_check};
+
+    #[test]
+    fn test_example_input() {
+        assert_eq!(find_encryption_key(5764801, 17807724), 14897079);
+    }
+
+    #[test]
+    fn test_oracle() {
+        cross_check(&Day25, 100);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        check_answers(&Day25, 25, &[1]);
+    }
+
+    #[test]
+    fn test_solution() {
+        let keys = Day25.parse(Path::new("example.txt")).unwrap();
+        assert_eq!(Day25.part1(&keys), Answer::Number(14897079));
+        assert_eq!(Day25.part2(&keys), Answer::Unsolved);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("test.txt", "5764801\n0\n");
+        let err = parse_source(&source).err().unwrap();
+        assert_eq!(err.to_string(), "invalid public key `0`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (2, 1));
+    }
+}
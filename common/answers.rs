@@ -0,0 +1,208 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::{Answer, Diagnostic, IoError, Location, Solution, Source};
+
+/// The answers file at the root of the workspace.
+pub const ANSWERS_FILE: &str = "answers.tsv";
+
+/// The header line of an answers file.
+const HEADER: &str = "day\tpart\tinput\tanswer";
+
+/// The expected answer to a part of the puzzle of a day, for a given input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expected {
+    pub day: usize,
+    pub part: usize,
+    /// The input file, relative to the current directory.
+    pub input: PathBuf,
+    pub answer: String,
+}
+
+impl Expected {
+    /// Returns whether the answer is the expected one. Answers are compared
+    /// as text, so that `00123` isn't the same as `123`.
+    pub fn matches(&self, answer: &Answer) -> bool {
+        answer.to_string() == self.answer
+    }
+}
+
+/// An error found while parsing an answers file.
+#[derive(Debug)]
+pub enum RegistryError {
+    /// The answers file couldn't be read.
+    Io(IoError),
+    /// A day isn't a number between 1 and 25.
+    InvalidDay(Location),
+    /// A part isn't 1 or 2.
+    InvalidPart(Location),
+    /// A line doesn't have the four fields.
+    MissingField(Location),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistryError::Io(err) => write!(f, "{}", err),
+            RegistryError::InvalidDay(loc) => write!(f, "invalid day `{}`", loc.token),
+            RegistryError::InvalidPart(loc) => write!(f, "invalid part `{}`", loc.token),
+            RegistryError::MissingField(_) => write!(f, "expected day, part, input and answer"),
+        }
+    }
+}
+
+impl Diagnostic for RegistryError {
+    fn location(&self) -> Option<&Location> {
+        match self {
+            RegistryError::Io(_) => None,
+            RegistryError::InvalidDay(loc)
+            | RegistryError::InvalidPart(loc)
+            | RegistryError::MissingField(loc) => Some(loc),
+        }
+    }
+}
+
+impl From<IoError> for RegistryError {
+    fn from(err: IoError) -> Self {
+        RegistryError::Io(err)
+    }
+}
+
+/// The expected answers of the puzzle inputs, read from a file of
+/// tab-separated values with the day, part, input file and answer. Input
+/// files are relative to the directory of the answers file, and lines
+/// starting with `#` are ignored.
+#[derive(Debug, Default, PartialEq)]
+pub struct Registry {
+    entries: Vec<Expected>,
+}
+
+impl Registry {
+    /// Reads an answers file.
+    pub fn read(file_name: impl AsRef<Path>) -> Result<Self, RegistryError> {
+        let dir = file_name.as_ref().parent().unwrap_or_else(|| Path::new(""));
+        Registry::parse(&Source::read(&file_name)?, dir)
+    }
+
+    /// Parses an answers file, with the input files relative to `dir`.
+    pub fn parse(source: &Source, dir: &Path) -> Result<Self, RegistryError> {
+        let mut entries = Vec::new();
+        for line in source.content().lines() {
+            if line == HEADER || line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields = line.splitn(4, '\t').collect::<Vec<_>>();
+            if fields.len() < 4 {
+                return Err(RegistryError::MissingField(source.locate_end(line)));
+            }
+            let day = match fields[0].parse() {
+                Ok(day) if (1..=25).contains(&day) => day,
+                _ => return Err(RegistryError::InvalidDay(source.locate(fields[0]))),
+            };
+            let part = match fields[1].parse() {
+                Ok(part) if part == 1 || part == 2 => part,
+                _ => return Err(RegistryError::InvalidPart(source.locate(fields[1]))),
+            };
+            entries.push(Expected {
+                day,
+                part,
+                input: dir.join(fields[2]),
+                answer: fields[3].to_owned(),
+            });
+        }
+        Ok(Registry { entries })
+    }
+
+    /// Returns the expected answers, in the order of the file.
+    pub fn entries(&self) -> &[Expected] {
+        &self.entries
+    }
+}
+
+/// Checks the given parts of a day against the answers file at the root of
+/// the workspace, panicking on a wrong answer. Meant for the tests of each
+/// day, which run from the directory of the day.
+pub fn check_answers<S: Solution>(solution: &S, day: usize, parts: &[usize]) {
+    let file_name = Path::new("..").join(ANSWERS_FILE);
+    let registry = Registry::read(&file_name).unwrap_or_else(|err| panic!("{}", err.render()));
+
+    for &part in parts {
+        let mut checked = false;
+        for expected in registry.entries() {
+            if expected.day != day || expected.part != part {
+                continue;
+            }
+            let input = solution
+                .parse(&expected.input)
+                .unwrap_or_else(|err| panic!("{}", err.render()));
+            let answer = solution.solve(&input, part);
+            assert!(
+                expected.matches(&answer),
+                "wrong answer for part {} of `{}`: expected {}, got {}",
+                part,
+                expected.input.display(),
+                expected.answer,
+                answer
+            );
+            checked = true;
+        }
+        assert!(checked, "no answer for day {} part {}", day, part);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let source = Source::new(
+            "answers.tsv",
+            "day\tpart\tinput\tanswer\n# Comment\n1\t1\t01/input.txt\t918339\n21\t2\tinput\tkqv,jxx\n",
+        );
+        let registry = Registry::parse(&source, Path::new("puzzles")).unwrap();
+        assert_eq!(
+            registry.entries(),
+            &[
+                Expected {
+                    day: 1,
+                    part: 1,
+                    input: PathBuf::from("puzzles/01/input.txt"),
+                    answer: "918339".to_owned(),
+                },
+                Expected {
+                    day: 21,
+                    part: 2,
+                    input: PathBuf::from("puzzles/input"),
+                    answer: "kqv,jxx".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matches() {
+        let expected = Expected {
+            day: 23,
+            part: 1,
+            input: PathBuf::from("input.txt"),
+            answer: "0123".to_owned(),
+        };
+        assert!(expected.matches(&Answer::from("0123")));
+        assert!(!expected.matches(&Answer::Number(123)));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new("answers.tsv", "1\t1\tinput.txt\t5\n1\t3\tinput.txt\t7\n");
+        let err = Registry::parse(&source, Path::new("")).err().unwrap();
+        assert_eq!(err.to_string(), "invalid part `3`");
+        let loc = err.location().unwrap();
+        assert_eq!((loc.line, loc.column), (2, 3));
+
+        let source = Source::new("answers.tsv", "1\t1\tinput.txt\n");
+        let err = Registry::parse(&source, Path::new("")).err().unwrap();
+        assert_eq!(err.to_string(), "expected day, part, input and answer");
+    }
+}
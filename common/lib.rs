@@ -0,0 +1,347 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process;
+
+mod answers;
+mod oracle;
+mod output;
+
+pub use answers::{check_answers, Expected, Registry, RegistryError, ANSWERS_FILE};
+pub use oracle::{cross_check, cross_check_seeds, Oracle, Rng};
+pub use output::{
+    csv_field, json_string, parse_arg, parse_args, take_flag, take_format, take_option, Format,
+    Printer, Record,
+};
+
+/// The answer to one part of a puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+    Unsolved,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Unsolved => write!(f, "none"),
+        }
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Number(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(i32, i64, i128, isize, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_owned())
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(answer: Option<T>) -> Self {
+        answer.map_or(Answer::Unsolved, Into::into)
+    }
+}
+
+/// A token in an input file, used to point at the cause of a parse error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Location {
+    /// Name of the input file.
+    pub file: String,
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Column number in characters, starting at 1.
+    pub column: usize,
+    /// The whole line containing the token.
+    pub text: String,
+    /// The offending token. Empty if something is missing at this point.
+    pub token: String,
+}
+
+/// An error reading an input file.
+#[derive(Debug)]
+pub struct IoError {
+    pub file: String,
+    pub error: io::Error,
+}
+
+impl fmt::Display for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "couldn't read `{}`: {}", self.file, self.error)
+    }
+}
+
+/// The file name that stands for the standard input.
+pub const STDIN: &str = "-";
+
+/// The contents of an input file, normalized so that the days don't need
+/// to care about how the file was edited: the byte order mark is removed,
+/// line endings are `\n`, lines have no trailing whitespace, and the file
+/// has no trailing blank lines.
+pub struct Source {
+    name: String,
+    content: String,
+}
+
+impl Source {
+    /// Creates a source from the contents of a file called `name`.
+    pub fn new(name: impl Into<String>, content: impl AsRef<str>) -> Self {
+        Source {
+            name: name.into(),
+            content: normalize(content.as_ref()),
+        }
+    }
+
+    /// Reads a source from a file, or from the standard input if the file
+    /// name is [`STDIN`].
+    pub fn read(file_name: impl AsRef<Path>) -> Result<Self, IoError> {
+        if file_name.as_ref() == Path::new(STDIN) {
+            let name = "<stdin>".to_owned();
+            let mut content = String::new();
+            return match io::stdin().read_to_string(&mut content) {
+                Ok(_) => Ok(Source::new(name, content)),
+                Err(error) => Err(IoError { file: name, error }),
+            };
+        }
+
+        let name = file_name.as_ref().display().to_string();
+        match fs::read_to_string(file_name) {
+            Ok(content) => Ok(Source::new(name, content)),
+            Err(error) => Err(IoError { file: name, error }),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// Returns the location of `token`, which must be a slice of the
+    /// contents of this source.
+    pub fn locate(&self, token: &str) -> Location {
+        let start = self.content.as_ptr() as usize;
+        let offset = token.as_ptr() as usize - start;
+        assert!(offset + token.len() <= self.content.len());
+
+        let line_start = self.content[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.content[offset..]
+            .find('\n')
+            .map_or(self.content.len(), |i| offset + i);
+
+        Location {
+            file: self.name.clone(),
+            line: self.content[..offset].matches('\n').count() + 1,
+            column: self.content[line_start..offset].chars().count() + 1,
+            text: self.content[line_start..line_end].to_owned(),
+            token: token.to_owned(),
+        }
+    }
+
+    /// Returns the location right after `s`, a slice of the contents of
+    /// this source, for reporting something missing there.
+    pub fn locate_end(&self, s: &str) -> Location {
+        self.locate(&s[s.len()..])
+    }
+
+    /// Returns the paragraphs of the contents, that is, the groups of lines
+    /// separated by one or more blank lines.
+    pub fn paragraphs(&self) -> impl Iterator<Item = &str> {
+        self.content
+            .split("\n\n")
+            .map(|x| x.trim_matches('\n'))
+            .filter(|x| !x.is_empty())
+    }
+}
+
+fn normalize(content: &str) -> String {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut normalized = String::with_capacity(content.len());
+    for line in content.lines() {
+        normalized.push_str(line.trim_end());
+        normalized.push('\n');
+    }
+    normalized.truncate(normalized.trim_end().len());
+    if !normalized.is_empty() {
+        normalized.push('\n');
+    }
+    normalized
+}
+
+impl From<&str> for Source {
+    /// Creates a source from an in-memory string, called `<input>`.
+    fn from(content: &str) -> Self {
+        Source::new("<input>", content)
+    }
+}
+
+/// An error that can be reported as a compiler-style diagnostic.
+pub trait Diagnostic: fmt::Display {
+    /// Returns the location of the error in the input file, if any.
+    fn location(&self) -> Option<&Location>;
+
+    /// Renders the error, quoting the offending line of the input file.
+    fn render(&self) -> String {
+        let mut s = format!("error: {}\n", self);
+        if let Some(loc) = self.location() {
+            let line = loc.line.to_string();
+            let margin = " ".repeat(line.len());
+            let indent = " ".repeat(loc.column - 1);
+            let marker = "^".repeat(loc.token.chars().count().max(1));
+            s += &format!("{}--> {}:{}:{}\n", margin, loc.file, loc.line, loc.column);
+            s += &format!("{} |\n", margin);
+            s += &format!("{} | {}\n", line, loc.text);
+            s += &format!("{} | {}{}\n", margin, indent, marker);
+        }
+        s
+    }
+}
+
+impl Diagnostic for IoError {
+    fn location(&self) -> Option<&Location> {
+        None
+    }
+}
+
+/// Returns the parsed value, or prints the diagnostic and exits.
+pub fn or_exit<T, E: Diagnostic>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|err| {
+        eprint!("{}", err.render());
+        process::exit(1);
+    })
+}
+
+/// The solution to the puzzle of a day.
+///
+/// The puzzle input is parsed once into a model that is shared by the
+/// two parts of the puzzle. Days that take extra parameters keep them in
+/// the type implementing this trait.
+pub trait Solution {
+    /// The model parsed from the puzzle input.
+    type Input;
+
+    /// The error returned when the puzzle input is malformed.
+    type Error: Diagnostic + From<IoError>;
+
+    fn parse_source(&self, source: &Source) -> Result<Self::Input, Self::Error>;
+
+    /// Reads and parses the puzzle input from a file.
+    fn parse(&self, file_name: &Path) -> Result<Self::Input, Self::Error> {
+        self.parse_source(&Source::read(file_name)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Answer;
+
+    fn part2(&self, input: &Self::Input) -> Answer;
+
+    /// Solves the given part (1 or 2) of the puzzle.
+    fn solve(&self, input: &Self::Input, part: usize) -> Answer {
+        match part {
+            1 => self.part1(input),
+            2 => self.part2(input),
+            _ => panic!("Invalid part: {}", part),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer() {
+        assert_eq!(Answer::from(42usize).to_string(), "42");
+        assert_eq!(Answer::from(-7i32).to_string(), "-7");
+        assert_eq!(Answer::from("kqv,jxx").to_string(), "kqv,jxx");
+        assert_eq!(Answer::from(Some(1u64)), Answer::Number(1));
+        assert_eq!(Answer::from(None::<u64>), Answer::Unsolved);
+        assert_eq!(Answer::Unsolved.to_string(), "none");
+    }
+
+    struct TestError(Location);
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "invalid token `{}`", self.0.token)
+        }
+    }
+
+    impl Diagnostic for TestError {
+        fn location(&self) -> Option<&Location> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_locate() {
+        let source = Source::new("test.txt", "abc\r\nd\u{e9}f ghi\n");
+        let loc = source.locate(&source.content()[9..12]);
+        assert_eq!((loc.line, loc.column), (2, 5));
+        assert_eq!(
+            (loc.text.as_str(), loc.token.as_str()),
+            ("d\u{e9}f ghi", "ghi")
+        );
+
+        let loc = source.locate_end(&source.content()[..3]);
+        assert_eq!((loc.line, loc.column, loc.token.as_str()), (1, 4, ""));
+
+        let source = Source::from("abc");
+        assert_eq!((source.name(), source.content()), ("<input>", "abc\n"));
+    }
+
+    #[test]
+    fn test_normalize() {
+        let source = Source::new(
+            "test.txt",
+            "\u{feff}a b \r\nc\t\r\n\r\n \r\n\r\nd\r\n\r\n\r\n",
+        );
+        assert_eq!(source.content(), "a b\nc\n\n\n\nd\n");
+        assert_eq!(source.paragraphs().collect::<Vec<_>>(), vec!["a b\nc", "d"]);
+
+        let source = Source::new("test.txt", "\n\n");
+        assert_eq!(source.content(), "");
+        assert_eq!(source.paragraphs().count(), 0);
+
+        let source = Source::new("test.txt", "\u{feff}ab\r\ncd");
+        let loc = source.locate(&source.content()[3..5]);
+        assert_eq!((loc.line, loc.column, loc.text.as_str()), (2, 1, "cd"));
+    }
+
+    #[test]
+    fn test_render() {
+        let source = Source::new("test.txt", "1\n2\n3\n4\n5\n6\n7\n8\n9\n1x\n");
+        let err = TestError(source.locate(&source.content()[18..20]));
+        let expected = "\
+error: invalid token `1x`
+  --> test.txt:10:1
+   |
+10 | 1x
+   | ^^
+";
+        assert_eq!(err.render(), expected);
+    }
+}
@@ -0,0 +1,128 @@
+use std::env;
+use std::ops::Range;
+
+use crate::{Answer, Diagnostic, Solution, Source};
+
+/// The number of seeds [`cross_check`] tries unless `AOC_SEEDS` is set.
+const DEFAULT_SEEDS: u64 = 1000;
+
+/// A small pseudo-random number generator (SplitMix64), seedable so that
+/// every generated input can be reproduced.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `low..high`.
+    pub fn range(&mut self, low: usize, high: usize) -> usize {
+        assert!(low < high, "empty range {}..{}", low, high);
+        low + (self.next_u64() % (high - low) as u64) as usize
+    }
+
+    /// Returns true with a probability of `numerator / denominator`.
+    pub fn ratio(&mut self, numerator: usize, denominator: usize) -> bool {
+        self.range(0, denominator) < numerator
+    }
+
+    /// Returns a random element of a non-empty slice.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0, items.len())]
+    }
+
+    /// Shuffles a slice in place.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0, i + 1));
+        }
+    }
+}
+
+/// A solution that can be checked against random puzzle inputs.
+pub trait Oracle: Solution {
+    /// Generates a valid puzzle input, which gets bigger with `size`.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String;
+
+    /// Solves a part of the puzzle in a slow but straightforward way.
+    fn oracle(&self, input: &Self::Input, part: usize) -> Answer;
+}
+
+fn num_seeds() -> u64 {
+    env::var("AOC_SEEDS")
+        .ok()
+        .and_then(|x| x.parse().ok())
+        .unwrap_or(DEFAULT_SEEDS)
+}
+
+fn input_size(default: usize) -> usize {
+    env::var("AOC_SIZE")
+        .ok()
+        .and_then(|x| x.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Checks both parts of a solution against its oracle on inputs generated
+/// from a number of seeds, taken from the `AOC_SEEDS` environment variable,
+/// panicking with the seed and the input of the first mismatch. The size of
+/// the inputs is taken from the `AOC_SIZE` environment variable, if set.
+pub fn cross_check<S: Oracle>(solution: &S, size: usize) {
+    cross_check_seeds(solution, 0..num_seeds(), input_size(size));
+}
+
+/// Same as [`cross_check`], but for the given seeds and size of the inputs.
+pub fn cross_check_seeds<S: Oracle>(solution: &S, seeds: Range<u64>, size: usize) {
+    for seed in seeds {
+        let content = solution.generate(&mut Rng::new(seed), size);
+        let source = Source::new(format!("seed {}", seed), &content);
+        let input = solution
+            .parse_source(&source)
+            .unwrap_or_else(|err| panic!("{}", err.render()));
+
+        for part in 1..=2 {
+            let answer = solution.solve(&input, part);
+            let expected = solution.oracle(&input, part);
+            assert!(
+                answer == expected,
+                "wrong answer for part {} of seed {}: expected {}, got {}\n{}",
+                part,
+                seed,
+                expected,
+                answer,
+                content
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(42);
+        let numbers = (0..1000).map(|_| rng.range(3, 7)).collect::<Vec<_>>();
+        assert!(numbers.iter().all(|x| (3..7).contains(x)));
+        assert!((3..7).all(|x| numbers.contains(&x)));
+
+        let mut rng2 = Rng::new(42);
+        assert_eq!(rng2.range(3, 7), numbers[0]);
+
+        let mut items = (0..10).collect::<Vec<_>>();
+        rng.shuffle(&mut items);
+        items.sort_unstable();
+        assert_eq!(items, (0..10).collect::<Vec<_>>());
+    }
+}
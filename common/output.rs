@@ -0,0 +1,344 @@
+use std::env;
+use std::process;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::Answer;
+
+/// The format in which the answers are printed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// One `Result (Day NN, Part N): ANSWER` line per part.
+    Text,
+    /// One JSON object per line, with `null` for unsolved parts.
+    Json,
+    /// Tab-separated values, with a header line and an empty answer for
+    /// unsolved parts.
+    Tsv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "tsv" => Ok(Format::Tsv),
+            _ => Err(format!("invalid format: {}", s)),
+        }
+    }
+}
+
+/// The answer to one part of the puzzle of a day, and the time it took to
+/// solve it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    pub day: usize,
+    pub part: usize,
+    pub answer: Answer,
+    pub elapsed: Duration,
+}
+
+impl Record {
+    /// The header line of the TSV format.
+    pub const TSV_HEADER: &'static str = "day\tpart\tanswer\telapsed_ms";
+
+    /// Solves a part of the puzzle with `f`, timing it.
+    pub fn solve<T: Into<Answer>>(day: usize, part: usize, f: impl FnOnce() -> T) -> Self {
+        let start = Instant::now();
+        let answer = f().into();
+        Record {
+            day,
+            part,
+            answer,
+            elapsed: start.elapsed(),
+        }
+    }
+
+    fn elapsed_ms(&self) -> String {
+        format!("{:.3}", self.elapsed.as_secs_f64() * 1000.0)
+    }
+
+    /// Formats the record as a line, without the trailing newline.
+    pub fn format(&self, format: Format) -> String {
+        match format {
+            Format::Text => format!(
+                "Result (Day {:02}, Part {}): {}",
+                self.day, self.part, self.answer
+            ),
+            Format::Json => {
+                let answer = match &self.answer {
+                    Answer::Number(n) => n.to_string(),
+                    Answer::Text(s) => json_string(s),
+                    Answer::Unsolved => "null".to_owned(),
+                };
+                format!(
+                    r#"{{"day":{},"part":{},"answer":{},"elapsed_ms":{}}}"#,
+                    self.day,
+                    self.part,
+                    answer,
+                    self.elapsed_ms()
+                )
+            }
+            Format::Tsv => {
+                let answer = match &self.answer {
+                    Answer::Unsolved => String::new(),
+                    answer => answer.to_string().replace(&['\t', '\n'][..], " "),
+                };
+                format!(
+                    "{}\t{}\t{}\t{}",
+                    self.day,
+                    self.part,
+                    answer,
+                    self.elapsed_ms()
+                )
+            }
+        }
+    }
+}
+
+/// Quotes `s` as a JSON string.
+pub fn json_string(s: &str) -> String {
+    let mut json = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Quotes `s` as a CSV field, if it has a comma, a quote or a newline.
+pub fn csv_field(s: &str) -> String {
+    if s.contains(&[',', '"', '\n'][..]) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+/// Prints the answers of a day as they are solved.
+pub struct Printer {
+    day: usize,
+    format: Format,
+    header_printed: bool,
+}
+
+impl Printer {
+    pub fn new(day: usize, format: Format) -> Self {
+        Printer {
+            day,
+            format,
+            header_printed: false,
+        }
+    }
+
+    /// Solves a part of the puzzle with `f` and prints its record.
+    pub fn solve<T: Into<Answer>>(&mut self, part: usize, f: impl FnOnce() -> T) {
+        let record = Record::solve(self.day, part, f);
+        self.print(&record);
+    }
+
+    pub fn print(&mut self, record: &Record) {
+        if self.format == Format::Tsv && !self.header_printed {
+            println!("{}", Record::TSV_HEADER);
+            self.header_printed = true;
+        }
+        println!("{}", record.format(self.format));
+    }
+}
+
+/// Removes the `name VALUE` option from `args`, returning its value.
+pub fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>, String> {
+    match args.iter().position(|x| x == name) {
+        Some(i) if i + 1 < args.len() => {
+            let value = args.remove(i + 1);
+            args.remove(i);
+            Ok(Some(value))
+        }
+        Some(_) => Err(format!("missing value for {}", name)),
+        None => Ok(None),
+    }
+}
+
+/// Parses the value of the argument `name`, printing an error and exiting
+/// if it's invalid.
+pub fn parse_arg<T: FromStr>(name: &str, value: &str) -> T {
+    value.parse().unwrap_or_else(|_| {
+        eprintln!("error: invalid {}: {}", name, value);
+        process::exit(1);
+    })
+}
+
+/// Removes the `--format FORMAT` option from `args`.
+pub fn take_format(args: &mut Vec<String>) -> Result<Format, String> {
+    take_option(args, "--format")?.map_or(Ok(Format::Text), |x| x.parse())
+}
+
+/// Removes the `flag` option from `args`, returning whether it was present.
+pub fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let len = args.len();
+    args.retain(|x| x != flag);
+    args.len() != len
+}
+
+/// Counts the positional arguments in `args`, skipping the `options`, which
+/// are followed by a value if `true`.
+fn count_positional(args: &[String], options: &[(&str, bool)]) -> usize {
+    let mut count = 0;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match options.iter().find(|(name, _)| name == arg) {
+            Some((_, true)) => {
+                args.next();
+            }
+            Some((_, false)) => (),
+            None => count += 1,
+        }
+    }
+    count
+}
+
+/// Parses the command line of a day binary, returning the positional
+/// arguments and the output format. Arguments in `names` within brackets
+/// are optional, a last one ending with `...` can be repeated, and the ones
+/// starting with `--` are options, like
+/// `[--count]` or `[--report FORMAT]`, which are left in the arguments to
+/// be removed with [`take_flag`] or [`take_option`]. Prints the usage and
+/// exits if the command line is invalid.
+pub fn parse_args(names: &[&str]) -> (Vec<String>, Format) {
+    let mut args = env::args().collect::<Vec<_>>();
+    let program = args.remove(0);
+    let options = names
+        .iter()
+        .filter(|x| x.starts_with("[--"))
+        .map(|x| {
+            let mut words = x.trim_matches(|c| c == '[' || c == ']').split_whitespace();
+            (words.next().unwrap_or_default(), words.next().is_some())
+        })
+        .collect::<Vec<_>>();
+    let required = names.iter().filter(|x| !x.starts_with('[')).count();
+    let allowed = if names.iter().any(|x| x.ends_with("...]")) {
+        usize::MAX
+    } else {
+        names.len() - options.len()
+    };
+
+    match take_format(&mut args) {
+        Ok(format) => {
+            let positional = count_positional(&args, &options);
+            if positional >= required && positional <= allowed {
+                return (args, format);
+            }
+        }
+        Err(err) => eprintln!("error: {}\n", err),
+    }
+    eprintln!(
+        "USAGE: {} {} [--format text|json|tsv]",
+        program,
+        names.join(" ")
+    );
+    process::exit(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(answer: Answer) -> Record {
+        Record {
+            day: 21,
+            part: 2,
+            answer,
+            elapsed: Duration::from_micros(1500),
+        }
+    }
+
+    #[test]
+    fn test_format() {
+        let text = record(Answer::from("kqv,\"jxx\""));
+        assert_eq!(
+            text.format(Format::Text),
+            "Result (Day 21, Part 2): kqv,\"jxx\""
+        );
+        assert_eq!(
+            text.format(Format::Json),
+            r#"{"day":21,"part":2,"answer":"kqv,\"jxx\"","elapsed_ms":1.500}"#
+        );
+        assert_eq!(text.format(Format::Tsv), "21\t2\tkqv,\"jxx\"\t1.500");
+
+        let unsolved = record(Answer::Unsolved);
+        assert_eq!(
+            unsolved.format(Format::Json),
+            r#"{"day":21,"part":2,"answer":null,"elapsed_ms":1.500}"#
+        );
+        assert_eq!(unsolved.format(Format::Tsv), "21\t2\t\t1.500");
+
+        let number = record(Answer::Number(-7));
+        assert_eq!(
+            number.format(Format::Json),
+            r#"{"day":21,"part":2,"answer":-7,"elapsed_ms":1.500}"#
+        );
+    }
+
+    #[test]
+    fn test_take_format() {
+        let mut args = vec![
+            "input.txt".to_owned(),
+            "--format".to_owned(),
+            "json".to_owned(),
+        ];
+        assert_eq!(take_format(&mut args), Ok(Format::Json));
+        assert_eq!(args, vec!["input.txt"]);
+
+        assert_eq!(take_format(&mut args), Ok(Format::Text));
+        args.push("--format".to_owned());
+        assert!(take_format(&mut args).is_err());
+        args.push("xml".to_owned());
+        assert!(take_format(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("abc"), "abc");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("a\nb"), "\"a\nb\"");
+    }
+
+    #[test]
+    fn test_take_option() {
+        let mut args = vec!["a".to_owned(), "--report".to_owned(), "csv".to_owned()];
+        assert_eq!(
+            take_option(&mut args, "--report"),
+            Ok(Some("csv".to_owned()))
+        );
+        assert_eq!(args, vec!["a"]);
+        assert_eq!(take_option(&mut args, "--report"), Ok(None));
+        args.push("--report".to_owned());
+        assert!(take_option(&mut args, "--report").is_err());
+    }
+
+    #[test]
+    fn test_count_positional() {
+        let args = ["--count", "a", "--report", "csv", "b"].map(String::from);
+        let options = [("--count", false), ("--report", true)];
+        assert_eq!(count_positional(&args, &options), 2);
+        assert_eq!(count_positional(&args, &[]), 5);
+    }
+
+    #[test]
+    fn test_take_flag() {
+        let mut args = vec!["--count".to_owned(), "input.txt".to_owned()];
+        assert!(take_flag(&mut args, "--count"));
+        assert_eq!(args, vec!["input.txt"]);
+        assert!(!take_flag(&mut args, "--count"));
+    }
+}
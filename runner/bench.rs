@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+use std::fs;
+use std::time::{Duration, Instant};
+
+use common::IoError;
+
+use crate::alloc;
+
+/// The header line of a baseline file.
+const BASELINE_HEADER: &str = "day\tstep\tmedian_ms\tpeak_bytes";
+
+/// Slowdowns smaller than this are considered noise, whatever the threshold.
+const MIN_REGRESSION: Duration = Duration::from_micros(100);
+
+/// Statistics of the repeated runs of a step.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+    /// Peak of bytes allocated on top of the ones allocated before the step.
+    pub peak: usize,
+}
+
+impl Stats {
+    fn new(mut samples: Vec<Duration>, peak: usize) -> Self {
+        samples.sort();
+        let n = samples.len();
+        let median = if n % 2 == 1 {
+            samples[n / 2]
+        } else {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        };
+        Stats {
+            min: samples[0],
+            median,
+            max: samples[n - 1],
+            peak,
+        }
+    }
+}
+
+/// Runs `f` the given number of times, returning the result of the last
+/// run and the statistics of all of them.
+pub fn measure<T>(runs: usize, mut f: impl FnMut() -> T) -> (T, Stats) {
+    assert!(runs > 0, "at least one run is required");
+    let mut samples = Vec::with_capacity(runs);
+    let mut peak = 0;
+    let mut result = None;
+
+    for _ in 0..runs {
+        drop(result.take());
+        let allocated = alloc::reset_peak();
+        let start = Instant::now();
+        result = Some(f());
+        samples.push(start.elapsed());
+        peak = peak.max(alloc::peak().saturating_sub(allocated));
+    }
+
+    (result.unwrap(), Stats::new(samples, peak))
+}
+
+/// The statistics of a step (`parse`, `part1` or `part2`) of a day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measurement {
+    pub day: usize,
+    pub step: String,
+    pub stats: Stats,
+}
+
+/// The header line of [`Measurement::format`].
+pub const TABLE_HEADER: &str = "day  step     min (ms)  median (ms)     max (ms)   peak (KiB)";
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_nanos() as f64 / 1e6
+}
+
+impl Measurement {
+    /// Formats the measurement as a line of a table.
+    pub fn format(&self) -> String {
+        format!(
+            "{:02}   {:<5} {:>11.3}  {:>11.3}  {:>11.3}  {:>11.1}",
+            self.day,
+            self.step,
+            ms(self.stats.min),
+            ms(self.stats.median),
+            ms(self.stats.max),
+            self.stats.peak as f64 / 1024.0
+        )
+    }
+}
+
+/// The median time and peak allocation of previous measurements.
+#[derive(Debug, Default, PartialEq)]
+pub struct Baseline {
+    entries: HashMap<(usize, String), (f64, usize)>,
+}
+
+/// The relative change in the median time of a step against its baseline.
+#[derive(Debug, PartialEq)]
+pub struct Change {
+    /// The change in percentage, positive when slower.
+    pub percent: f64,
+    /// Whether the slowdown is beyond the threshold.
+    pub regression: bool,
+}
+
+impl Baseline {
+    /// Creates a baseline from the given measurements.
+    pub fn new(measurements: &[Measurement]) -> Self {
+        let entries = measurements
+            .iter()
+            .map(|m| ((m.day, m.step.clone()), (ms(m.stats.median), m.stats.peak)))
+            .collect();
+        Baseline { entries }
+    }
+
+    /// Parses a baseline, as written by [`Baseline::to_tsv`].
+    pub fn parse(file_name: &str, content: &str) -> Result<Self, String> {
+        let mut entries = HashMap::new();
+        for (i, line) in content.lines().enumerate() {
+            if i == 0 && line == BASELINE_HEADER || line.is_empty() {
+                continue;
+            }
+            let fields = line.split('\t').collect::<Vec<_>>();
+            let entry = match fields[..] {
+                [day, step, median, peak] => day.parse().ok().and_then(|day| {
+                    let median = median.parse::<f64>().ok()?;
+                    let peak = peak.parse().ok()?;
+                    Some(((day, step.to_owned()), (median, peak)))
+                }),
+                _ => None,
+            };
+            match entry {
+                Some((key, (median, peak))) if median >= 0.0 => {
+                    entries.insert(key, (median, peak));
+                }
+                _ => return Err(format!("{}:{}: invalid baseline entry", file_name, i + 1)),
+            }
+        }
+        Ok(Baseline { entries })
+    }
+
+    /// Reads a baseline file.
+    pub fn read(file_name: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(file_name).map_err(|error| {
+            IoError {
+                file: file_name.to_owned(),
+                error,
+            }
+            .to_string()
+        })?;
+        Baseline::parse(file_name, &content)
+    }
+
+    /// Formats the baseline as tab-separated values, sorted by day and step.
+    pub fn to_tsv(&self) -> String {
+        let mut entries = self.entries.iter().collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        let mut tsv = format!("{}\n", BASELINE_HEADER);
+        for ((day, step), (median, peak)) in entries {
+            tsv += &format!("{}\t{}\t{:.3}\t{}\n", day, step, median, peak);
+        }
+        tsv
+    }
+
+    /// Compares a measurement against the baseline, flagging it as a
+    /// regression if its median is slower by more than `threshold` percent.
+    pub fn compare(&self, measurement: &Measurement, threshold: usize) -> Option<Change> {
+        let key = (measurement.day, measurement.step.clone());
+        let &(median, _) = self.entries.get(&key)?;
+        let current = ms(measurement.stats.median);
+        let percent = if median == 0.0 {
+            0.0
+        } else {
+            (current / median - 1.0) * 100.0
+        };
+        let regression = percent > threshold as f64 && current - median > ms(MIN_REGRESSION);
+        Some(Change {
+            percent,
+            regression,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(step: &str, median_ms: u64) -> Measurement {
+        let median = Duration::from_millis(median_ms);
+        Measurement {
+            day: 15,
+            step: step.to_owned(),
+            stats: Stats {
+                min: median,
+                median,
+                max: median,
+                peak: 2048,
+            },
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let samples = [5, 1, 3, 2].iter().map(|&x| Duration::from_millis(x));
+        let stats = Stats::new(samples.collect(), 0);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.max, Duration::from_millis(5));
+
+        let (result, stats) = measure(3, || vec![0u8; 4096]);
+        assert_eq!(result.len(), 4096);
+        assert!(stats.min <= stats.median && stats.median <= stats.max);
+    }
+
+    #[test]
+    fn test_baseline() {
+        let baseline = Baseline::new(&[measurement("part1", 10), measurement("part2", 0)]);
+        let tsv = baseline.to_tsv();
+        assert_eq!(
+            tsv,
+            "day\tstep\tmedian_ms\tpeak_bytes\n15\tpart1\t10.000\t2048\n15\tpart2\t0.000\t2048\n"
+        );
+        assert_eq!(Baseline::parse("baseline.tsv", &tsv), Ok(baseline));
+        assert_eq!(
+            Baseline::parse("baseline.tsv", "15\tpart1\tfast\t0\n"),
+            Err("baseline.tsv:1: invalid baseline entry".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = Baseline::new(&[measurement("part1", 10), measurement("part2", 0)]);
+        let change = baseline.compare(&measurement("part1", 12), 10).unwrap();
+        assert!((change.percent - 20.0).abs() < 1e-9);
+        assert!(change.regression);
+        assert!(
+            !baseline
+                .compare(&measurement("part1", 12), 25)
+                .unwrap()
+                .regression
+        );
+        assert!(
+            !baseline
+                .compare(&measurement("part1", 9), 0)
+                .unwrap()
+                .regression
+        );
+        assert!(
+            !baseline
+                .compare(&measurement("part2", 0), 0)
+                .unwrap()
+                .regression
+        );
+        assert_eq!(baseline.compare(&measurement("parse", 1), 10), None);
+    }
+}
@@ -0,0 +1,607 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+use common::{
+    Answer, Diagnostic, Expected, Format, Printer, Record, Registry, Solution, ANSWERS_FILE, STDIN,
+};
+
+use crate::bench::{Baseline, Measurement};
+
+mod alloc;
+mod bench;
+
+#[global_allocator]
+static ALLOCATOR: alloc::CountingAllocator = alloc::CountingAllocator;
+
+const USAGE: &str = "\
+USAGE: aoc run --day DAY [--part PART] [--input FILE] [OPTIONS]
+       aoc bench [--day DAY] [--input FILE] [--runs N] [--save FILE]
+                 [--compare FILE] [--threshold PERCENT] [OPTIONS]
+       aoc verify [--day DAY] [--answers FILE] [OPTIONS]
+
+The run command solves the puzzle of the given day. Both parts are solved
+unless --part is given, and the input defaults to the puzzle input of the
+day.
+
+The bench command times the parsing and each part of the given day, or of
+every day, over a number of runs, and reports the minimum, median and
+maximum times and the peak of memory allocated. The medians can be saved
+to a baseline file, and compared against a previous baseline to flag the
+regressions.
+
+The verify command solves every input registered in the answers file,
+of the given day or of every day, and prints which parts got the expected
+answer. The answers file has a line with the day, part, input file and
+answer for every expected answer, separated by tabs, with the input files
+relative to the answers file.
+
+OPTIONS:
+    --preamble N    Preamble size for day 09 (default: 25)
+    --turn N        Target turn for day 15 (default: 2020 for part 1,
+                    30000000 for part 2)
+    --cycles N      Number of boot cycles for day 17 (default: 6)
+    --moves N       Number of crab moves for day 23 (default: 100 for
+                    part 1, 10000000 for part 2)
+    --format FORMAT Output format: text, json or tsv (default: text)
+
+BENCH OPTIONS:
+    --runs N        Number of runs of every step (default: 5)
+    --save FILE     Save the measurements as a baseline to FILE
+    --compare FILE  Compare the measurements against the baseline in FILE
+    --threshold PERCENT
+                    Slowdown against the baseline flagged as a regression
+                    (default: 10)
+
+VERIFY OPTIONS:
+    --answers FILE  The answers file (default: answers.tsv)";
+
+const NUM_DAYS: usize = 25;
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Run,
+    Bench,
+    Verify,
+}
+
+struct Options {
+    command: Command,
+    day: Option<usize>,
+    part: Option<usize>,
+    input: Option<String>,
+    preamble: Option<usize>,
+    turn: Option<usize>,
+    cycles: Option<usize>,
+    moves: Option<usize>,
+    format: Format,
+    runs: usize,
+    save: Option<String>,
+    compare: Option<String>,
+    threshold: usize,
+    answers: String,
+}
+
+impl Options {
+    fn input(&self, day: usize) -> String {
+        match &self.input {
+            Some(input) => input.clone(),
+            None if day == 19 => "19/input-part1.txt".to_owned(),
+            None => format!("{:02}/input.txt", day),
+        }
+    }
+
+    fn days(&self) -> Vec<usize> {
+        match self.day {
+            Some(day) => vec![day],
+            None => (1..=NUM_DAYS).collect(),
+        }
+    }
+
+    fn parts(&self) -> Vec<usize> {
+        match self.part {
+            Some(part) => vec![part],
+            None => vec![1, 2],
+        }
+    }
+}
+
+fn parse_number(option: &str, value: Option<String>) -> Result<usize, String> {
+    let value = value.ok_or(format!("missing value for {}", option))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {}: {}", option, value))
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let command = match args.next().as_deref() {
+        Some("run") => Command::Run,
+        Some("bench") => Command::Bench,
+        Some("verify") => Command::Verify,
+        Some(command) => return Err(format!("unknown command: {}", command)),
+        None => return Err("missing command".to_owned()),
+    };
+
+    let mut options = Options {
+        command,
+        day: None,
+        part: None,
+        input: None,
+        preamble: None,
+        turn: None,
+        cycles: None,
+        moves: None,
+        format: Format::Text,
+        runs: 5,
+        save: None,
+        compare: None,
+        threshold: 10,
+        answers: ANSWERS_FILE.to_owned(),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => options.day = Some(parse_number(&arg, args.next())?),
+            "--part" => options.part = Some(parse_number(&arg, args.next())?),
+            "--input" => options.input = Some(args.next().ok_or("missing value for --input")?),
+            "--preamble" => options.preamble = Some(parse_number(&arg, args.next())?),
+            "--turn" => options.turn = Some(parse_number(&arg, args.next())?),
+            "--cycles" => options.cycles = Some(parse_number(&arg, args.next())?),
+            "--moves" => options.moves = Some(parse_number(&arg, args.next())?),
+            "--format" => {
+                let format = args.next().ok_or("missing value for --format")?;
+                options.format = format.parse()?;
+            }
+            "--runs" if options.command == Command::Bench => {
+                options.runs = parse_number(&arg, args.next())?
+            }
+            "--save" if options.command == Command::Bench => {
+                options.save = Some(args.next().ok_or("missing value for --save")?)
+            }
+            "--compare" if options.command == Command::Bench => {
+                options.compare = Some(args.next().ok_or("missing value for --compare")?)
+            }
+            "--threshold" if options.command == Command::Bench => {
+                options.threshold = parse_number(&arg, args.next())?
+            }
+            "--answers" if options.command == Command::Verify => {
+                options.answers = args.next().ok_or("missing value for --answers")?
+            }
+            _ => return Err(format!("unknown option: {}", arg)),
+        }
+    }
+
+    match options.day {
+        Some(day) if !(1..=NUM_DAYS).contains(&day) => return Err(format!("invalid day: {}", day)),
+        Some(_) => (),
+        None if options.command == Command::Run => return Err("missing option: --day".to_owned()),
+        None if options.input.is_some() => return Err("--input requires --day".to_owned()),
+        None => (),
+    }
+
+    if options.command == Command::Bench && options.input.as_deref() == Some(STDIN) {
+        return Err("the standard input can't be read more than once by bench".to_owned());
+    }
+
+    if options.command == Command::Verify && (options.input.is_some() || options.part.is_some()) {
+        return Err("--input and --part aren't supported by verify".to_owned());
+    }
+
+    if let Some(part) = options.part {
+        if part != 1 && part != 2 {
+            return Err(format!("invalid part: {}", part));
+        }
+    }
+
+    if options.runs == 0 {
+        return Err("invalid value for --runs: 0".to_owned());
+    }
+
+    Ok(options)
+}
+
+/// A solution whose input type is hidden, so that every day can be handled
+/// through the same trait object.
+trait Puzzle {
+    fn run(&self, day: usize, input: &Path, parts: &[usize]) -> Result<Vec<Record>, String>;
+
+    fn bench(&self, day: usize, input: &Path, runs: usize) -> Result<Vec<Measurement>, String>;
+}
+
+impl<S: Solution> Puzzle for S {
+    fn run(&self, day: usize, input: &Path, parts: &[usize]) -> Result<Vec<Record>, String> {
+        let input = self.parse(input).map_err(|err| err.render())?;
+        Ok(parts
+            .iter()
+            .map(|&part| Record::solve(day, part, || self.solve(&input, part)))
+            .collect())
+    }
+
+    fn bench(&self, day: usize, input: &Path, runs: usize) -> Result<Vec<Measurement>, String> {
+        let (input, stats) = bench::measure(runs, || self.parse(input));
+        let input = input.map_err(|err| err.render())?;
+        let mut measurements = vec![Measurement {
+            day,
+            step: "parse".to_owned(),
+            stats,
+        }];
+        for part in 1..=2 {
+            let (_, stats) = bench::measure(runs, || self.solve(&input, part));
+            measurements.push(Measurement {
+                day,
+                step: format!("part{}", part),
+                stats,
+            });
+        }
+        Ok(measurements)
+    }
+}
+
+fn puzzle(options: &Options, day: usize) -> Box<dyn Puzzle> {
+    match day {
+        1 => Box::new(day01::Day01),
+        2 => Box::new(day02::Day02),
+        3 => Box::new(day03::Day03),
+        4 => Box::new(day04::Day04),
+        5 => Box::new(day05::Day05),
+        6 => Box::new(day06::Day06),
+        7 => Box::new(day07::Day07),
+        8 => Box::new(day08::Day08),
+        9 => {
+            let mut puzzle = day09::Day09::default();
+            if let Some(preamble) = options.preamble {
+                puzzle.preamble_length = preamble;
+            }
+            Box::new(puzzle)
+        }
+        10 => Box::new(day10::Day10),
+        11 => Box::new(day11::Day11),
+        12 => Box::new(day12::Day12),
+        13 => Box::new(day13::Day13),
+        14 => Box::new(day14::Day14),
+        15 => {
+            let mut puzzle = day15::Day15::default();
+            if let Some(turn) = options.turn {
+                puzzle.part1_target_turn = turn;
+                puzzle.part2_target_turn = turn;
+            }
+            Box::new(puzzle)
+        }
+        16 => Box::new(day16::Day16),
+        17 => {
+            let mut puzzle = day17::Day17::default();
+            if let Some(cycles) = options.cycles {
+                puzzle.num_cycles = cycles;
+            }
+            Box::new(puzzle)
+        }
+        18 => Box::new(day18::Day18),
+        19 => Box::new(day19::Day19),
+        20 => Box::new(day20::Day20),
+        21 => Box::new(day21::Day21),
+        22 => Box::new(day22::Day22),
+        23 => {
+            let mut puzzle = day23::Day23::default();
+            if let Some(moves) = options.moves {
+                puzzle.part1_num_iters = moves;
+                puzzle.part2_num_iters = moves;
+            }
+            Box::new(puzzle)
+        }
+        24 => Box::new(day24::Day24::default()),
+        25 => Box::new(day25::Day25),
+        _ => unreachable!(),
+    }
+}
+
+fn solve(options: &Options, day: usize) -> Result<Vec<Record>, String> {
+    let input = options.input(day);
+    puzzle(options, day).run(day, Path::new(&input), &options.parts())
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let day = options.day.unwrap();
+    let mut printer = Printer::new(day, options.format);
+    for record in solve(options, day)? {
+        printer.print(&record);
+    }
+    Ok(())
+}
+
+/// Benchmarks the days, returning whether no regressions were found.
+fn bench(options: &Options) -> Result<bool, String> {
+    let baseline = match &options.compare {
+        Some(file_name) => {
+            Some(Baseline::read(file_name).map_err(|err| format!("error: {}\n", err))?)
+        }
+        None => None,
+    };
+
+    let mut measurements = Vec::new();
+    let mut passed = true;
+    println!("{}", bench::TABLE_HEADER);
+    for day in options.days() {
+        let input = options.input(day);
+        for measurement in puzzle(options, day).bench(day, Path::new(&input), options.runs)? {
+            let change = baseline
+                .as_ref()
+                .and_then(|baseline| baseline.compare(&measurement, options.threshold));
+            match change {
+                Some(change) => {
+                    let flag = if change.regression {
+                        "  REGRESSION"
+                    } else {
+                        ""
+                    };
+                    println!("{}  {:+7.1}%{}", measurement.format(), change.percent, flag);
+                    passed &= !change.regression;
+                }
+                None => println!("{}", measurement.format()),
+            }
+            measurements.push(measurement);
+        }
+    }
+
+    if let Some(file_name) = &options.save {
+        fs::write(file_name, Baseline::new(&measurements).to_tsv())
+            .map_err(|err| format!("error: couldn't write `{}`: {}\n", file_name, err))?;
+    }
+
+    if !passed {
+        eprintln!("error: regressions found against the baseline");
+    }
+    Ok(passed)
+}
+
+fn status(answers: &[(usize, Answer)], expected: &[&Expected], part: usize) -> &'static str {
+    let expected = match expected.iter().find(|x| x.part == part) {
+        Some(expected) => expected,
+        None => return "-",
+    };
+    match answers.iter().find(|(x, _)| *x == part) {
+        Some((_, answer)) if expected.matches(answer) => "pass",
+        Some(_) => "FAIL",
+        None => "error",
+    }
+}
+
+/// Solves the inputs of the answers file, returning whether all the
+/// answers were the expected ones.
+fn verify(options: &Options) -> Result<bool, String> {
+    let registry = Registry::read(&options.answers).map_err(|err| err.render())?;
+    let days = options.days();
+
+    // Group the expected answers by day and input, keeping the file order.
+    let mut rows: Vec<(usize, &Path, Vec<&Expected>)> = Vec::new();
+    for expected in registry.entries() {
+        if !days.contains(&expected.day) {
+            continue;
+        }
+        match rows
+            .iter_mut()
+            .find(|(day, input, _)| *day == expected.day && *input == expected.input)
+        {
+            Some((_, _, row)) => row.push(expected),
+            None => rows.push((expected.day, &expected.input, vec![expected])),
+        }
+    }
+
+    let mut failures = Vec::new();
+    println!("day  part 1  part 2  input");
+    for (day, input, expected) in rows {
+        let parts = expected.iter().map(|x| x.part).collect::<Vec<_>>();
+        let answers = match puzzle(options, day).run(day, input, &parts) {
+            Ok(records) => records.into_iter().map(|x| (x.part, x.answer)).collect(),
+            Err(diagnostic) => {
+                failures.push(diagnostic);
+                Vec::new()
+            }
+        };
+        for expected in &expected {
+            let answer = answers.iter().find(|(part, _)| *part == expected.part);
+            if let Some((_, answer)) = answer.filter(|(_, answer)| !expected.matches(answer)) {
+                failures.push(format!(
+                    "error: wrong answer for day {:02}, part {} of `{}`: expected {}, got {}\n",
+                    day,
+                    expected.part,
+                    input.display(),
+                    expected.answer,
+                    answer
+                ));
+            }
+        }
+        println!(
+            "{:02}   {:<6}  {:<6}  {}",
+            day,
+            status(&answers, &expected, 1),
+            status(&answers, &expected, 2),
+            input.display()
+        );
+    }
+
+    for failure in &failures {
+        eprint!("{}", failure);
+    }
+    Ok(failures.is_empty())
+}
+
+fn main() {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            process::exit(1);
+        }
+    };
+
+    let result = match options.command {
+        Command::Run => run(&options).map(|_| true),
+        Command::Bench => bench(&options),
+        Command::Verify => verify(&options),
+    };
+
+    match result {
+        Ok(true) => (),
+        Ok(false) => process::exit(1),
+        Err(diagnostic) => {
+            eprint!("{}", diagnostic);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::cross_check_seeds;
+
+    fn args(s: &str) -> impl Iterator<Item = String> + '_ {
+        s.split_whitespace().map(|x| x.to_owned())
+    }
+
+    fn answers(options: &Options) -> Vec<(usize, Answer)> {
+        solve(options, options.day.unwrap())
+            .unwrap()
+            .into_iter()
+            .map(|record| (record.part, record.answer))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(args("run --day 9 --part 2 --input foo.txt")).unwrap();
+        assert_eq!(options.command, Command::Run);
+        assert_eq!(options.day, Some(9));
+        assert_eq!(options.parts(), vec![2]);
+        assert_eq!(options.input(9), "foo.txt");
+        assert_eq!(options.preamble, None);
+        assert_eq!(options.format, Format::Text);
+
+        let options = parse_args(args("run --day 19")).unwrap();
+        assert_eq!(options.parts(), vec![1, 2]);
+        assert_eq!(options.input(19), "19/input-part1.txt");
+
+        let options = parse_args(args("run --day 1 --format json")).unwrap();
+        assert_eq!(options.format, Format::Json);
+
+        assert!(parse_args(args("run")).is_err());
+        assert!(parse_args(args("run --day 26")).is_err());
+        assert!(parse_args(args("run --day 1 --part 3")).is_err());
+        assert!(parse_args(args("run --day 1 --cycles")).is_err());
+        assert!(parse_args(args("run --day 1 --format xml")).is_err());
+        assert!(parse_args(args("run --day 1 --runs 3")).is_err());
+        assert!(parse_args(args("walk --day 1")).is_err());
+    }
+
+    #[test]
+    fn test_parse_bench_args() {
+        let options = parse_args(args("bench")).unwrap();
+        assert_eq!(options.command, Command::Bench);
+        assert_eq!(options.days().len(), NUM_DAYS);
+        assert_eq!((options.runs, options.threshold), (5, 10));
+
+        let options = parse_args(args(
+            "bench --day 15 --runs 3 --save base.tsv --threshold 20",
+        ));
+        let options = options.unwrap();
+        assert_eq!(options.days(), vec![15]);
+        assert_eq!(options.runs, 3);
+        assert_eq!(options.save.as_deref(), Some("base.tsv"));
+        assert_eq!(options.compare, None);
+        assert_eq!(options.threshold, 20);
+
+        assert!(parse_args(args("bench --input foo.txt")).is_err());
+        assert!(parse_args(args("bench --runs 0")).is_err());
+        assert!(parse_args(args("bench --day 1 --input -")).is_err());
+        assert!(parse_args(args("bench --compare")).is_err());
+    }
+
+    #[test]
+    fn test_parse_verify_args() {
+        let options = parse_args(args("verify")).unwrap();
+        assert_eq!(options.command, Command::Verify);
+        assert_eq!(options.answers, "answers.tsv");
+
+        let options = parse_args(args("verify --day 3 --answers mine.tsv")).unwrap();
+        assert_eq!(options.days(), vec![3]);
+        assert_eq!(options.answers, "mine.tsv");
+
+        assert!(parse_args(args("verify --day 3 --input foo.txt")).is_err());
+        assert!(parse_args(args("run --day 3 --answers mine.tsv")).is_err());
+    }
+
+    #[test]
+    fn test_verify() {
+        let options = parse_args(args("verify --day 8 --answers ../answers.tsv"));
+        assert_eq!(verify(&options.unwrap()), Ok(true));
+    }
+
+    #[test]
+    fn test_example_input() {
+        let options = parse_args(args("run --day 9 --input ../09/example.txt --preamble 5"));
+        let expected = vec![(1, Answer::Number(127)), (2, Answer::Number(62))];
+        assert_eq!(answers(&options.unwrap()), expected);
+
+        let options = parse_args(args("run --day 23 --part 1 --input ../23/example.txt")).unwrap();
+        assert_eq!(answers(&options), vec![(1, Answer::from("67384529"))]);
+    }
+
+    #[test]
+    fn test_bench() {
+        let options = parse_args(args("bench --day 9 --input ../09/example.txt --preamble 5"));
+        let options = options.unwrap();
+        let input = Path::new("../09/example.txt");
+        let measurements = puzzle(&options, 9).bench(9, input, 2).unwrap();
+        let steps = measurements
+            .iter()
+            .map(|m| m.step.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(steps, vec!["parse", "part1", "part2"]);
+        assert!(measurements[0].stats.peak > 0);
+    }
+
+    /// Cross-checks every day against its oracle on a thousand seeds, far
+    /// from the ones of the `test_oracle` tests. Run it with
+    /// `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn test_oracles_large_seeds() {
+        let seeds = || 1 << 32..(1 << 32) + 1000;
+        cross_check_seeds(&day01::Day01, seeds(), 12);
+        cross_check_seeds(&day02::Day02, seeds(), 50);
+        cross_check_seeds(&day03::Day03, seeds(), 20);
+        cross_check_seeds(&day04::Day04, seeds(), 20);
+        cross_check_seeds(&day05::Day05, seeds(), 100);
+        cross_check_seeds(&day06::Day06, seeds(), 20);
+        cross_check_seeds(&day07::Day07, seeds(), 8);
+        cross_check_seeds(&day08::Day08, seeds(), 10);
+        let day09 = day09::Day09 { preamble_length: 5 };
+        cross_check_seeds(&day09, seeds(), 20);
+        cross_check_seeds(&day10::Day10, seeds(), 12);
+        cross_check_seeds(&day11::Day11, seeds(), 8);
+        cross_check_seeds(&day12::Day12, seeds(), 50);
+        cross_check_seeds(&day13::Day13, seeds(), 5);
+        cross_check_seeds(&day14::Day14, seeds(), 12);
+        let day15 = day15::Day15 {
+            part1_target_turn: 10,
+            part2_target_turn: 500,
+        };
+        cross_check_seeds(&day15, seeds(), 4);
+        cross_check_seeds(&day16::Day16, seeds(), 20);
+        cross_check_seeds(&day17::Day17 { num_cycles: 2 }, seeds(), 3);
+        cross_check_seeds(&day18::Day18, seeds(), 10);
+        cross_check_seeds(&day19::Day19, seeds(), 20);
+        cross_check_seeds(&day20::Day20, seeds(), 3);
+        cross_check_seeds(&day21::Day21, seeds(), 8);
+        cross_check_seeds(&day22::Day22, seeds(), 10);
+        let day23 = day23::Day23 {
+            part1_num_iters: 100,
+            part2_num_iters: 100,
+            part2_num_cups: 100,
+        };
+        cross_check_seeds(&day23, seeds(), 9);
+        cross_check_seeds(&day24::Day24 { num_days: 10 }, seeds(), 10);
+        cross_check_seeds(&day25::Day25, seeds(), 100);
+    }
+}